@@ -1,11 +1,19 @@
 use leptos::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use crate::types::{
+    ChannelKind, DateWindow, DeliveryStatus, Locale, PlanFilter, QuietHours, RouteStatus,
+};
+
 #[cfg(feature = "ssr")]
 use std::sync::Arc;
 
 #[cfg(feature = "ssr")]
-use crate::{api_impl, db, scraper::BusScraper};
+use crate::{
+    api_impl, db,
+    route_events::{RouteChange, publish_route_change},
+    scraper::BusScraper,
+};
 
 /// Get the `BusScraper` from Leptos context
 #[cfg(feature = "ssr")]
@@ -21,7 +29,11 @@ pub struct UserDto {
     pub enabled: bool,
     pub notify_on_change_only: bool,
     pub scrape_interval_secs: i64,
-    pub discord_webhook_url: Option<String>,
+    /// Alerts are held back during these hours and sent as one digest afterwards
+    pub quiet_hours: Option<QuietHours>,
+    /// Language notifications are written in
+    pub locale: Locale,
+    pub notification_channels: Vec<NotificationChannelDto>,
     pub created_at: String,
 }
 
@@ -31,7 +43,19 @@ pub struct UserFormDto {
     pub enabled: bool,
     pub notify_on_change_only: bool,
     pub scrape_interval_secs: i64,
-    pub discord_webhook_url: Option<String>,
+    /// Alerts are held back during these hours and sent as one digest afterwards
+    pub quiet_hours: Option<QuietHours>,
+    /// Language notifications are written in
+    pub locale: Locale,
+    pub notification_channels: Vec<NotificationChannelDto>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationChannelDto {
+    pub kind: ChannelKind,
+    /// Webhook URL or address, depending on `kind`
+    pub target: String,
+    pub enabled: bool,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -44,8 +68,22 @@ pub struct UserRouteDto {
     pub arrival_station: String,
     pub date_start: String,
     pub date_end: String,
+    /// Checked instead of `date_start`..`date_end` when set
+    pub date_window: Option<DateWindow>,
     pub departure_time_min: Option<String>,
     pub departure_time_max: Option<String>,
+    pub plan_filter: PlanFilter,
+    /// Replaces the user's interval when set
+    pub scrape_interval_secs: Option<i64>,
+    /// Check more often as departure approaches
+    pub adaptive_interval: bool,
+    /// Also alert when seats seen on sale are gone
+    pub notify_on_sold_out: bool,
+    pub status: RouteStatus,
+    /// Switched off by the owner; the tracker skips the route
+    pub enabled: bool,
+    /// RFC 3339 end of a pause, only while it lasts
+    pub paused_until: Option<String>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -57,8 +95,17 @@ pub struct UserRouteFormDto {
     pub arrival_station: String,
     pub date_start: String,
     pub date_end: String,
+    /// Checked instead of `date_start`..`date_end` when set
+    pub date_window: Option<DateWindow>,
     pub departure_time_min: Option<String>,
     pub departure_time_max: Option<String>,
+    pub plan_filter: PlanFilter,
+    /// Replaces the user's interval when set
+    pub scrape_interval_secs: Option<i64>,
+    /// Check more often as departure approaches
+    pub adaptive_interval: bool,
+    /// Also alert when seats seen on sale are gone
+    pub notify_on_sold_out: bool,
     pub adult_men: i16,
     pub adult_women: i16,
     pub child_men: i16,
@@ -83,6 +130,13 @@ pub struct StationDto {
     pub area_id: i32,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AvailableDateDto {
+    /// Date in `YYYYMMDD` format
+    pub date_id: String,
+    pub display_name: String,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct UserRouteWithPassengersDto {
     pub id: String,
@@ -93,8 +147,22 @@ pub struct UserRouteWithPassengersDto {
     pub arrival_station: String,
     pub date_start: String,
     pub date_end: String,
+    /// Checked instead of `date_start`..`date_end` when set
+    pub date_window: Option<DateWindow>,
     pub departure_time_min: Option<String>,
     pub departure_time_max: Option<String>,
+    pub plan_filter: PlanFilter,
+    /// Replaces the user's interval when set
+    pub scrape_interval_secs: Option<i64>,
+    /// Check more often as departure approaches
+    pub adaptive_interval: bool,
+    /// Also alert when seats seen on sale are gone
+    pub notify_on_sold_out: bool,
+    pub status: RouteStatus,
+    /// Switched off by the owner; the tracker skips the route
+    pub enabled: bool,
+    /// RFC 3339 end of a pause, only while it lasts
+    pub paused_until: Option<String>,
     pub adult_men: i16,
     pub adult_women: i16,
     pub child_men: i16,
@@ -105,6 +173,26 @@ pub struct UserRouteWithPassengersDto {
     pub handicap_child_women: i16,
 }
 
+/// One availability alert and how far its delivery to one channel got
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AlertDeliveryDto {
+    pub id: String,
+    pub user_route_id: String,
+    pub departure_station_name: String,
+    pub arrival_station_name: String,
+    pub channel_kind: ChannelKind,
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    /// Why the last attempt failed
+    pub last_error: Option<String>,
+    /// RFC 3339 time the alert was raised
+    pub created_at: String,
+    /// RFC 3339 time the channel accepted it
+    pub delivered_at: Option<String>,
+    /// Buses listed in the alert
+    pub bus_count: usize,
+}
+
 #[server]
 pub async fn get_users() -> Result<Vec<UserDto>, ServerFnError> {
     let db = db::get_db_from_context()?;
@@ -125,9 +213,11 @@ pub async fn create_user(form: UserFormDto) -> Result<UserDto, ServerFnError> {
 pub async fn update_user(id: String, form: UserFormDto) -> Result<UserDto, ServerFnError> {
     let db = db::get_db_from_context()?;
     let uuid = api_impl::parse_uuid(&id).map_err(|e| ServerFnError::new(e.to_string()))?;
-    api_impl::update_user_impl(&db, uuid, form)
+    let user = api_impl::update_user_impl(&db, uuid, form)
         .await
-        .map_err(|e| ServerFnError::new(e.to_string()))
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    publish_route_change(RouteChange::UserChanged(uuid));
+    Ok(user)
 }
 
 #[server]
@@ -136,7 +226,9 @@ pub async fn delete_user(id: String) -> Result<(), ServerFnError> {
     let uuid = api_impl::parse_uuid(&id).map_err(|e| ServerFnError::new(e.to_string()))?;
     api_impl::delete_user_impl(&db, uuid)
         .await
-        .map_err(|e| ServerFnError::new(e.to_string()))
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    publish_route_change(RouteChange::UserChanged(uuid));
+    Ok(())
 }
 
 /// Fetch routes from Highway Bus API for a given area
@@ -169,12 +261,34 @@ pub async fn get_arrival_stations(
         .map_err(|e| ServerFnError::new(e.to_string()))
 }
 
+/// Fetch the dates the operator sells tickets for on a route and station pair
+#[server]
+pub async fn get_available_dates(
+    route_id: String,
+    departure_station_id: String,
+    arrival_station_id: String,
+) -> Result<Vec<AvailableDateDto>, ServerFnError> {
+    let scraper = get_scraper_from_context()?;
+    api_impl::fetch_available_dates_impl(
+        &scraper,
+        &route_id,
+        &departure_station_id,
+        &arrival_station_id,
+    )
+    .await
+    .map_err(|e| ServerFnError::new(e.to_string()))
+}
+
 #[server]
 pub async fn create_user_route(form: UserRouteFormDto) -> Result<UserRouteDto, ServerFnError> {
     let db = db::get_db_from_context()?;
-    api_impl::create_user_route_impl(&db, form)
+    let route = api_impl::create_user_route_impl(&db, form)
         .await
-        .map_err(|e| ServerFnError::new(e.to_string()))
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    if let Ok(uuid) = api_impl::parse_uuid(&route.id) {
+        publish_route_change(RouteChange::RouteCreated(uuid));
+    }
+    Ok(route)
 }
 
 #[server]
@@ -195,9 +309,11 @@ pub async fn update_user_route(
 ) -> Result<UserRouteDto, ServerFnError> {
     let db = db::get_db_from_context()?;
     let uuid = api_impl::parse_uuid(&id).map_err(|e| ServerFnError::new(e.to_string()))?;
-    api_impl::update_user_route_impl(&db, uuid, form)
+    let route = api_impl::update_user_route_impl(&db, uuid, form)
         .await
-        .map_err(|e| ServerFnError::new(e.to_string()))
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    publish_route_change(RouteChange::RouteUpdated(uuid));
+    Ok(route)
 }
 
 #[server]
@@ -205,6 +321,53 @@ pub async fn delete_user_route(id: String) -> Result<(), ServerFnError> {
     let db = db::get_db_from_context()?;
     let uuid = api_impl::parse_uuid(&id).map_err(|e| ServerFnError::new(e.to_string()))?;
     api_impl::delete_user_route_impl(&db, uuid)
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    publish_route_change(RouteChange::RouteDeleted(uuid));
+    Ok(())
+}
+
+#[server]
+pub async fn set_user_route_enabled(
+    id: String,
+    enabled: bool,
+) -> Result<UserRouteDto, ServerFnError> {
+    let db = db::get_db_from_context()?;
+    let uuid = api_impl::parse_uuid(&id).map_err(|e| ServerFnError::new(e.to_string()))?;
+    let route = api_impl::set_user_route_enabled_impl(&db, uuid, enabled)
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    publish_route_change(RouteChange::RouteUpdated(uuid));
+    Ok(route)
+}
+
+#[server]
+pub async fn snooze_user_route(id: String) -> Result<UserRouteDto, ServerFnError> {
+    let db = db::get_db_from_context()?;
+    let uuid = api_impl::parse_uuid(&id).map_err(|e| ServerFnError::new(e.to_string()))?;
+    let route = api_impl::snooze_user_route_impl(&db, uuid)
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    publish_route_change(RouteChange::RouteUpdated(uuid));
+    Ok(route)
+}
+
+#[server]
+pub async fn resume_user_route(id: String) -> Result<UserRouteDto, ServerFnError> {
+    let db = db::get_db_from_context()?;
+    let uuid = api_impl::parse_uuid(&id).map_err(|e| ServerFnError::new(e.to_string()))?;
+    let route = api_impl::pause_user_route_impl(&db, uuid, None)
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    publish_route_change(RouteChange::RouteUpdated(uuid));
+    Ok(route)
+}
+
+#[server]
+pub async fn get_alert_deliveries(user_id: String) -> Result<Vec<AlertDeliveryDto>, ServerFnError> {
+    let db = db::get_db_from_context()?;
+    let uuid = api_impl::parse_uuid(&user_id).map_err(|e| ServerFnError::new(e.to_string()))?;
+    api_impl::get_alert_deliveries_impl(&db, uuid)
         .await
         .map_err(|e| ServerFnError::new(e.to_string()))
 }
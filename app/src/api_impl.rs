@@ -6,14 +6,24 @@
 //! code inside procedural macros.
 
 use crate::api::{
-    RouteDto, StationDto, UserDto, UserFormDto, UserRouteDto, UserRouteFormDto,
-    UserRouteWithPassengersDto,
+    AlertDeliveryDto, AvailableDateDto, NotificationChannelDto, RouteDto, StationDto, UserDto,
+    UserFormDto, UserRouteDto, UserRouteFormDto, UserRouteWithPassengersDto,
+};
+use crate::entities::{
+    notification_channels, notification_outbox, prelude::*, user_passengers, user_routes, users,
 };
-use crate::entities::{prelude::*, user_passengers, user_routes, users};
 use crate::error::{Result, ScraperError};
+use crate::notifier::QueuedAlert;
+use crate::repositories::{plan_filter_from_route, set_plan_filter, user_quiet_hours};
 use crate::scraper::BusScraper;
 use crate::translations::{translate_route_name, translate_station_name};
-use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use crate::types::{
+    ChannelKind, DateWindow, DeliveryStatus, Locale, MIN_ROUTE_INTERVAL_SECS, RouteStatus,
+};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, JoinType, QueryFilter,
+    QueryOrder, QuerySelect, RelationTrait, Set,
+};
 use uuid::Uuid;
 
 // === UUID Parsing ===
@@ -25,21 +35,49 @@ pub fn parse_uuid(id: &str) -> Result<Uuid> {
 
 // === DTO Conversions ===
 
-/// Convert a user model to a DTO.
-pub fn user_to_dto(user: users::Model) -> UserDto {
+/// Convert a user model and its notification channels to a DTO.
+/// Channels of a kind this build does not know are left out.
+pub fn user_to_dto(user: users::Model, channels: Vec<notification_channels::Model>) -> UserDto {
+    let quiet_hours = user_quiet_hours(&user);
+
     UserDto {
         id: user.id.to_string(),
         email: user.email,
         enabled: user.enabled,
         notify_on_change_only: user.notify_on_change_only,
         scrape_interval_secs: user.scrape_interval_secs,
-        discord_webhook_url: user.discord_webhook_url,
+        quiet_hours,
+        locale: Locale::parse(&user.locale).unwrap_or_default(),
+        notification_channels: channels
+            .into_iter()
+            .filter_map(notification_channel_to_dto)
+            .collect(),
         created_at: user.created_at.to_string(),
     }
 }
 
+/// Convert a notification channel model to a DTO, or `None` for an unknown kind.
+pub fn notification_channel_to_dto(
+    channel: notification_channels::Model,
+) -> Option<NotificationChannelDto> {
+    ChannelKind::parse(&channel.kind).map(|kind| NotificationChannelDto {
+        kind,
+        target: channel.target,
+        enabled: channel.enabled,
+    })
+}
+
+/// End of a route's pause in RFC 3339, or `None` once it has passed
+fn active_pause(paused_until: Option<chrono::DateTime<chrono::Utc>>) -> Option<String> {
+    paused_until
+        .filter(|until| *until > chrono::Utc::now())
+        .map(|until| until.to_rfc3339())
+}
+
 /// Convert a user route model to a DTO.
 pub fn user_route_to_dto(route: user_routes::Model) -> UserRouteDto {
+    let plan_filter = plan_filter_from_route(&route);
+
     UserRouteDto {
         id: route.id.to_string(),
         user_id: route.user_id.to_string(),
@@ -49,8 +87,16 @@ pub fn user_route_to_dto(route: user_routes::Model) -> UserRouteDto {
         arrival_station: route.arrival_station,
         date_start: route.date_start,
         date_end: route.date_end,
+        date_window: route.date_window.as_deref().and_then(DateWindow::parse),
         departure_time_min: route.departure_time_min,
         departure_time_max: route.departure_time_max,
+        plan_filter,
+        scrape_interval_secs: route.scrape_interval_secs,
+        adaptive_interval: route.adaptive_interval,
+        notify_on_sold_out: route.notify_on_sold_out,
+        status: RouteStatus::parse(&route.status).unwrap_or_default(),
+        enabled: route.enabled,
+        paused_until: active_pause(route.paused_until),
     }
 }
 
@@ -71,6 +117,8 @@ pub fn user_route_with_passengers_to_dto(
         handicap_child_women: 0,
     });
 
+    let plan_filter = plan_filter_from_route(&route);
+
     UserRouteWithPassengersDto {
         id: route.id.to_string(),
         user_id: route.user_id.to_string(),
@@ -80,8 +128,16 @@ pub fn user_route_with_passengers_to_dto(
         arrival_station: route.arrival_station,
         date_start: route.date_start,
         date_end: route.date_end,
+        date_window: route.date_window.as_deref().and_then(DateWindow::parse),
         departure_time_min: route.departure_time_min,
         departure_time_max: route.departure_time_max,
+        plan_filter,
+        scrape_interval_secs: route.scrape_interval_secs,
+        adaptive_interval: route.adaptive_interval,
+        notify_on_sold_out: route.notify_on_sold_out,
+        status: RouteStatus::parse(&route.status).unwrap_or_default(),
+        enabled: route.enabled,
+        paused_until: active_pause(route.paused_until),
         adult_men: p.adult_men,
         adult_women: p.adult_women,
         child_men: p.child_men,
@@ -93,28 +149,67 @@ pub fn user_route_with_passengers_to_dto(
     }
 }
 
+/// Convert an outbox row to a DTO.
+/// Rows of a channel kind this build does not know are left out.
+pub fn alert_delivery_to_dto(delivery: notification_outbox::Model) -> Option<AlertDeliveryDto> {
+    let channel_kind = ChannelKind::parse(&delivery.channel_kind)?;
+    let alert = serde_json::from_str::<QueuedAlert>(&delivery.payload).ok();
+
+    Some(AlertDeliveryDto {
+        id: delivery.id.to_string(),
+        user_route_id: delivery.user_route_id.to_string(),
+        departure_station_name: alert
+            .as_ref()
+            .map(|a| a.context.departure_station_name.clone())
+            .unwrap_or_default(),
+        arrival_station_name: alert
+            .as_ref()
+            .map(|a| a.context.arrival_station_name.clone())
+            .unwrap_or_default(),
+        channel_kind,
+        status: DeliveryStatus::parse(&delivery.status).unwrap_or_default(),
+        attempts: delivery.attempts,
+        last_error: delivery.last_error,
+        created_at: delivery.created_at.to_rfc3339(),
+        delivered_at: delivery.delivered_at.map(|at| at.to_rfc3339()),
+        bus_count: alert.map_or(0, |a| a.schedules.len()),
+    })
+}
+
 // === User Operations ===
 
 /// Fetch all users from the database.
 pub async fn get_users_impl(db: &DatabaseConnection) -> Result<Vec<UserDto>> {
     let users = Users::find()
+        .find_with_related(NotificationChannels)
+        .order_by_asc(notification_channels::Column::CreatedAt)
         .all(db)
         .await
         .map_err(|e| ScraperError::Database(format!("Database error: {e}")))?;
 
-    Ok(users.into_iter().map(user_to_dto).collect())
+    Ok(users
+        .into_iter()
+        .map(|(user, channels)| user_to_dto(user, channels))
+        .collect())
 }
 
 /// Create a new user in the database.
 pub async fn create_user_impl(db: &DatabaseConnection, form: UserFormDto) -> Result<UserDto> {
+    if let Some(quiet_hours) = &form.quiet_hours {
+        quiet_hours.validate()?;
+    }
+
     let new_user = users::ActiveModel {
         id: Set(Uuid::new_v4()),
         email: Set(form.email),
         enabled: Set(form.enabled),
         notify_on_change_only: Set(form.notify_on_change_only),
         scrape_interval_secs: Set(form.scrape_interval_secs),
-        discord_webhook_url: Set(form.discord_webhook_url),
         created_at: Set(chrono::Utc::now()),
+        quiet_hours_start: Set(form.quiet_hours.as_ref().map(|q| q.start.clone())),
+        quiet_hours_end: Set(form.quiet_hours.as_ref().map(|q| q.end.clone())),
+        quiet_hours_tz: Set(form.quiet_hours.as_ref().map(|q| q.time_zone.clone())),
+        locale: Set(form.locale.as_str().to_string()),
     };
 
     let user = new_user
@@ -122,7 +217,9 @@ pub async fn create_user_impl(db: &DatabaseConnection, form: UserFormDto) -> Res
         .await
         .map_err(|e| ScraperError::Database(format!("Failed to create user: {e}")))?;
 
-    Ok(user_to_dto(user))
+    let channels = replace_notification_channels(db, user.id, form.notification_channels).await?;
+
+    Ok(user_to_dto(user, channels))
 }
 
 /// Update an existing user in the database.
@@ -131,6 +228,10 @@ pub async fn update_user_impl(
     id: Uuid,
     form: UserFormDto,
 ) -> Result<UserDto> {
+    if let Some(quiet_hours) = &form.quiet_hours {
+        quiet_hours.validate()?;
+    }
+
     let user = Users::find_by_id(id)
         .one(db)
         .await
@@ -142,14 +243,58 @@ pub async fn update_user_impl(
     active_user.enabled = Set(form.enabled);
     active_user.notify_on_change_only = Set(form.notify_on_change_only);
     active_user.scrape_interval_secs = Set(form.scrape_interval_secs);
-    active_user.discord_webhook_url = Set(form.discord_webhook_url);
+    active_user.quiet_hours_start = Set(form.quiet_hours.as_ref().map(|q| q.start.clone()));
+    active_user.quiet_hours_end = Set(form.quiet_hours.as_ref().map(|q| q.end.clone()));
+    active_user.quiet_hours_tz = Set(form.quiet_hours.map(|q| q.time_zone));
+    active_user.locale = Set(form.locale.as_str().to_string());
 
     let updated_user = active_user
         .update(db)
         .await
         .map_err(|e| ScraperError::Database(format!("Failed to update user: {e}")))?;
 
-    Ok(user_to_dto(updated_user))
+    let channels = replace_notification_channels(db, id, form.notification_channels).await?;
+
+    Ok(user_to_dto(updated_user, channels))
+}
+
+/// Replace every notification channel of a user with `channels`.
+/// Entries with an empty target are dropped.
+pub async fn replace_notification_channels(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    channels: Vec<NotificationChannelDto>,
+) -> Result<Vec<notification_channels::Model>> {
+    NotificationChannels::delete_many()
+        .filter(notification_channels::Column::UserId.eq(user_id))
+        .exec(db)
+        .await
+        .map_err(|e| {
+            ScraperError::Database(format!("Failed to clear notification channels: {e}"))
+        })?;
+
+    let mut saved = Vec::new();
+    for channel in channels {
+        let target = channel.target.trim();
+        if target.is_empty() {
+            continue;
+        }
+
+        let model = notification_channels::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(user_id),
+            kind: Set(channel.kind.as_str().to_string()),
+            target: Set(target.to_string()),
+            enabled: Set(channel.enabled),
+            created_at: Set(chrono::Utc::now()),
+        }
+        .insert(db)
+        .await
+        .map_err(|e| ScraperError::Database(format!("Failed to save notification channel: {e}")))?;
+        saved.push(model);
+    }
+
+    Ok(saved)
 }
 
 /// Delete a user from the database.
@@ -189,9 +334,14 @@ pub async fn create_user_route_impl(
 ) -> Result<UserRouteDto> {
     let user_id =
         parse_uuid(&form.user_id).map_err(|_| ScraperError::Config("Invalid user UUID".into()))?;
+    if let Some(window) = &form.date_window {
+        window.validate()?;
+    }
+    validate_route_interval(form.scrape_interval_secs)?;
+
     let route_id = Uuid::new_v4();
 
-    let new_route = user_routes::ActiveModel {
+    let mut new_route = user_routes::ActiveModel {
         id: Set(route_id),
         user_id: Set(user_id),
         area_id: Set(form.area_id),
@@ -200,10 +350,17 @@ pub async fn create_user_route_impl(
         arrival_station: Set(form.arrival_station),
         date_start: Set(form.date_start),
         date_end: Set(form.date_end),
+        date_window: Set(form.date_window.as_ref().map(DateWindow::to_spec)),
         departure_time_min: Set(form.departure_time_min),
         departure_time_max: Set(form.departure_time_max),
         created_at: Set(chrono::Utc::now()),
+        status: Set(RouteStatus::Active.as_str().to_string()),
+        scrape_interval_secs: Set(form.scrape_interval_secs),
+        adaptive_interval: Set(form.adaptive_interval),
+        notify_on_sold_out: Set(form.notify_on_sold_out),
+        ..Default::default()
     };
+    set_plan_filter(&mut new_route, &form.plan_filter);
 
     let route = new_route
         .insert(db)
@@ -236,6 +393,11 @@ pub async fn update_user_route_impl(
     id: Uuid,
     form: UserRouteFormDto,
 ) -> Result<UserRouteDto> {
+    if let Some(window) = &form.date_window {
+        window.validate()?;
+    }
+    validate_route_interval(form.scrape_interval_secs)?;
+
     let route = UserRoutes::find_by_id(id)
         .one(db)
         .await
@@ -249,8 +411,15 @@ pub async fn update_user_route_impl(
     active_route.arrival_station = Set(form.arrival_station);
     active_route.date_start = Set(form.date_start);
     active_route.date_end = Set(form.date_end);
+    active_route.date_window = Set(form.date_window.as_ref().map(DateWindow::to_spec));
+    // New dates may bring an archived route back; the next sweep archives it again if not
+    active_route.status = Set(RouteStatus::Active.as_str().to_string());
     active_route.departure_time_min = Set(form.departure_time_min);
     active_route.departure_time_max = Set(form.departure_time_max);
+    active_route.scrape_interval_secs = Set(form.scrape_interval_secs);
+    active_route.adaptive_interval = Set(form.adaptive_interval);
+    active_route.notify_on_sold_out = Set(form.notify_on_sold_out);
+    set_plan_filter(&mut active_route, &form.plan_filter);
 
     let updated_route = active_route
         .update(db)
@@ -283,6 +452,16 @@ pub async fn update_user_route_impl(
     Ok(user_route_to_dto(updated_route))
 }
 
+/// A route's own check interval must be at least [`MIN_ROUTE_INTERVAL_SECS`]
+fn validate_route_interval(scrape_interval_secs: Option<i64>) -> Result<()> {
+    match scrape_interval_secs {
+        Some(secs) if secs < MIN_ROUTE_INTERVAL_SECS => Err(ScraperError::Config(format!(
+            "Check interval must be at least {MIN_ROUTE_INTERVAL_SECS} seconds"
+        ))),
+        _ => Ok(()),
+    }
+}
+
 /// Delete a user route from the database.
 pub async fn delete_user_route_impl(db: &DatabaseConnection, id: Uuid) -> Result<()> {
     UserRoutes::delete_by_id(id)
@@ -293,6 +472,91 @@ pub async fn delete_user_route_impl(db: &DatabaseConnection, id: Uuid) -> Result
     Ok(())
 }
 
+/// Switch tracking of a user route on or off. Switching it on also ends any pause.
+pub async fn set_user_route_enabled_impl(
+    db: &DatabaseConnection,
+    id: Uuid,
+    enabled: bool,
+) -> Result<UserRouteDto> {
+    let route = find_user_route(db, id).await?;
+
+    let mut active_route: user_routes::ActiveModel = route.into();
+    active_route.enabled = Set(enabled);
+    if enabled {
+        active_route.paused_until = Set(None);
+    }
+
+    update_route(db, active_route).await
+}
+
+/// Pause a user route until `until`, or resume it at once with `None`.
+pub async fn pause_user_route_impl(
+    db: &DatabaseConnection,
+    id: Uuid,
+    until: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<UserRouteDto> {
+    let route = find_user_route(db, id).await?;
+
+    let mut active_route: user_routes::ActiveModel = route.into();
+    active_route.paused_until = Set(until);
+
+    update_route(db, active_route).await
+}
+
+/// Pause a user route for [`SNOOZE_HOURS`](crate::notifier::SNOOZE_HOURS) from now.
+pub async fn snooze_user_route_impl(db: &DatabaseConnection, id: Uuid) -> Result<UserRouteDto> {
+    let until = chrono::Utc::now() + chrono::Duration::hours(crate::notifier::SNOOZE_HOURS);
+    pause_user_route_impl(db, id, Some(until)).await
+}
+
+async fn find_user_route(db: &DatabaseConnection, id: Uuid) -> Result<user_routes::Model> {
+    UserRoutes::find_by_id(id)
+        .one(db)
+        .await
+        .map_err(|e| ScraperError::Database(format!("Database error: {e}")))?
+        .ok_or_else(|| ScraperError::NotFound("Route not found".to_string()))
+}
+
+async fn update_route(
+    db: &DatabaseConnection,
+    active_route: user_routes::ActiveModel,
+) -> Result<UserRouteDto> {
+    let route = active_route
+        .update(db)
+        .await
+        .map_err(|e| ScraperError::Database(format!("Failed to update route: {e}")))?;
+
+    Ok(user_route_to_dto(route))
+}
+
+// === Alert Delivery Operations ===
+
+/// How many of a user's latest alert deliveries are listed
+pub const RECENT_ALERT_DELIVERIES: u64 = 20;
+
+/// Fetch the latest alert deliveries across a user's routes, newest first.
+pub async fn get_alert_deliveries_impl(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+) -> Result<Vec<AlertDeliveryDto>> {
+    let deliveries = NotificationOutbox::find()
+        .join(
+            JoinType::InnerJoin,
+            notification_outbox::Relation::UserRoutes.def(),
+        )
+        .filter(user_routes::Column::UserId.eq(user_id))
+        .order_by_desc(notification_outbox::Column::CreatedAt)
+        .limit(RECENT_ALERT_DELIVERIES)
+        .all(db)
+        .await
+        .map_err(|e| ScraperError::Database(format!("Database error: {e}")))?;
+
+    Ok(deliveries
+        .into_iter()
+        .filter_map(alert_delivery_to_dto)
+        .collect())
+}
+
 // === Scraper Operations ===
 
 /// Fetch routes from the Highway Bus API and translate names.
@@ -349,6 +613,26 @@ pub async fn fetch_and_translate_arrival_stations(
         .collect())
 }
 
+/// Fetch the operator's sales calendar for a route and station pair
+pub async fn fetch_available_dates_impl(
+    scraper: &BusScraper,
+    route_id: &str,
+    departure_station_id: &str,
+    arrival_station_id: &str,
+) -> Result<Vec<AvailableDateDto>> {
+    let dates = scraper
+        .fetch_available_dates(route_id, departure_station_id, arrival_station_id)
+        .await?;
+
+    Ok(dates
+        .into_iter()
+        .map(|d| AvailableDateDto {
+            date_id: d.date_id,
+            display_name: d.display_name,
+        })
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     #![allow(clippy::unwrap_used, clippy::panic)]
@@ -379,9 +663,21 @@ mod tests {
             arrival_station: "064".to_string(),
             date_start: "20250101".to_string(),
             date_end: "20250107".to_string(),
+            date_window: None,
             departure_time_min: None,
             departure_time_max: None,
             created_at: chrono::Utc::now(),
+            max_price: None,
+            seat_type: None,
+            plan_ids_allow: None,
+            plan_ids_deny: None,
+            min_remaining_seats: None,
+            status: "active".to_string(),
+            enabled: true,
+            paused_until: None,
+            scrape_interval_secs: None,
+            adaptive_interval: false,
+            notify_on_sold_out: false,
         };
 
         let dto = user_route_with_passengers_to_dto(route.clone(), None);
@@ -403,9 +699,21 @@ mod tests {
             arrival_station: "064".to_string(),
             date_start: "20250101".to_string(),
             date_end: "20250107".to_string(),
+            date_window: None,
             departure_time_min: Some("08:00".to_string()),
             departure_time_max: Some("12:00".to_string()),
             created_at: chrono::Utc::now(),
+            max_price: None,
+            seat_type: None,
+            plan_ids_allow: None,
+            plan_ids_deny: None,
+            min_remaining_seats: None,
+            status: "active".to_string(),
+            enabled: true,
+            paused_until: None,
+            scrape_interval_secs: None,
+            adaptive_interval: false,
+            notify_on_sold_out: false,
         };
 
         let passengers = user_passengers::Model {
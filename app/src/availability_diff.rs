@@ -0,0 +1,293 @@
+//! What changed between two checks of a route.
+//!
+//! The schedules seen by the last check are stored with the route state; a
+//! new check is compared with them plan by plan, keyed by departure date,
+//! departure time and plan id. Buses that no longer appear are left out:
+//! they have departed or dropped out of the searched dates.
+
+use crate::types::{BusSchedule, PricingPlan, SeatAvailability};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChangeKind {
+    /// Seats went on sale, on a new bus or one that was full
+    NowAvailable,
+    SeatsIncreased {
+        from: SeatAvailability,
+    },
+    SeatsDecreased {
+        from: SeatAvailability,
+    },
+    /// Price in yen before the change
+    PriceChanged {
+        from: u32,
+    },
+    /// No more seats for sale (full, or waitlist only)
+    SoldOut,
+}
+
+/// One change of one plan; `schedule` and `plan` are as seen by the latest check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AvailabilityChange {
+    pub schedule: BusSchedule,
+    pub plan: PricingPlan,
+    pub kind: ChangeKind,
+}
+
+/// Changes from `previous` to `current`, in the order of `current`.
+///
+/// A plan may have both a seat and a price change.
+pub fn diff_schedules(
+    previous: &[BusSchedule],
+    current: &[BusSchedule],
+) -> Vec<AvailabilityChange> {
+    let before: HashMap<(&str, &str, u32), &PricingPlan> = previous
+        .iter()
+        .flat_map(|schedule| {
+            schedule.available_plans.iter().map(move |plan| {
+                (
+                    (
+                        schedule.departure_date.as_str(),
+                        schedule.departure_time.as_str(),
+                        plan.plan_id,
+                    ),
+                    plan,
+                )
+            })
+        })
+        .collect();
+
+    let mut changes = Vec::new();
+    for schedule in current {
+        for plan in &schedule.available_plans {
+            let key = (
+                schedule.departure_date.as_str(),
+                schedule.departure_time.as_str(),
+                plan.plan_id,
+            );
+            let kinds = before
+                .get(&key)
+                .map_or_else(|| new_plan_changes(plan), |old| plan_changes(old, plan));
+
+            changes.extend(kinds.into_iter().map(|kind| AvailabilityChange {
+                schedule: schedule.clone(),
+                plan: plan.clone(),
+                kind,
+            }));
+        }
+    }
+
+    changes
+}
+
+fn new_plan_changes(plan: &PricingPlan) -> Vec<ChangeKind> {
+    if plan.availability.is_bookable() {
+        vec![ChangeKind::NowAvailable]
+    } else {
+        Vec::new()
+    }
+}
+
+fn plan_changes(old: &PricingPlan, new: &PricingPlan) -> Vec<ChangeKind> {
+    let mut kinds = Vec::new();
+
+    match (
+        old.availability.is_bookable(),
+        new.availability.is_bookable(),
+    ) {
+        (false, true) => kinds.push(ChangeKind::NowAvailable),
+        (true, false) => kinds.push(ChangeKind::SoldOut),
+        (true, true) => {
+            let from = old.availability.clone();
+            match compare_seats(&new.availability, &old.availability) {
+                Some(Ordering::Greater) => kinds.push(ChangeKind::SeatsIncreased { from }),
+                Some(Ordering::Less) => kinds.push(ChangeKind::SeatsDecreased { from }),
+                _ => {}
+            }
+        }
+        (false, false) => {}
+    }
+
+    if old.price != new.price && new.availability.is_bookable() {
+        kinds.push(ChangeKind::PriceChanged { from: old.price });
+    }
+
+    kinds
+}
+
+/// Compare the seats left on two bookable plans, as far as the site tells.
+///
+/// No count means many seats; "few left" and exact counts both mean few, and
+/// only exact counts can be compared with each other.
+fn compare_seats(new: &SeatAvailability, old: &SeatAvailability) -> Option<Ordering> {
+    let level = |availability: &SeatAvailability| match availability {
+        SeatAvailability::Available { remaining_seats } => *remaining_seats,
+        _ => None,
+    };
+    let many = |availability: &SeatAvailability| {
+        matches!(
+            availability,
+            SeatAvailability::Available {
+                remaining_seats: None
+            }
+        )
+    };
+
+    match (many(new), many(old)) {
+        (true, true) => Some(Ordering::Equal),
+        (true, false) => Some(Ordering::Greater),
+        (false, true) => Some(Ordering::Less),
+        (false, false) => match (level(new), level(old)) {
+            (Some(a), Some(b)) => Some(a.cmp(&b)),
+            (None, None) => Some(Ordering::Equal),
+            _ => None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(time: &str, price: u32, availability: SeatAvailability) -> BusSchedule {
+        BusSchedule {
+            bus_number: "Bus_1".to_string(),
+            route_name: "Test Route".to_string(),
+            departure_station: "001".to_string(),
+            departure_date: "20250115".to_string(),
+            departure_time: time.to_string(),
+            arrival_station: "064".to_string(),
+            arrival_date: "20250115".to_string(),
+            arrival_time: "10:00".to_string(),
+            way_no: 1,
+            available_plans: vec![PricingPlan {
+                plan_id: 12345,
+                plan_index: 0,
+                plan_name: "Standard".to_string(),
+                price,
+                display_price: format!("{price}円"),
+                availability,
+                details: None,
+            }],
+            booking_url: None,
+        }
+    }
+
+    fn seats(n: u32) -> SeatAvailability {
+        SeatAvailability::Available {
+            remaining_seats: Some(n),
+        }
+    }
+
+    fn kinds(previous: &[BusSchedule], current: &[BusSchedule]) -> Vec<(String, ChangeKind)> {
+        diff_schedules(previous, current)
+            .into_iter()
+            .map(|c| (c.schedule.departure_time, c.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_no_changes() {
+        let state = vec![schedule("08:30", 2100, seats(3))];
+        assert!(kinds(&state, &state).is_empty());
+    }
+
+    #[test]
+    fn test_new_bus_and_reopened_bus() {
+        let previous = vec![schedule("08:30", 2100, SeatAvailability::SoldOut)];
+        let current = vec![
+            schedule("08:30", 2100, SeatAvailability::FewLeft),
+            schedule("10:00", 2100, seats(2)),
+            schedule("12:00", 2100, SeatAvailability::SoldOut),
+        ];
+
+        assert_eq!(
+            kinds(&previous, &current),
+            vec![
+                ("08:30".to_string(), ChangeKind::NowAvailable),
+                ("10:00".to_string(), ChangeKind::NowAvailable),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_seat_changes() {
+        let previous = vec![
+            schedule("08:30", 2100, seats(2)),
+            schedule("10:00", 2100, seats(5)),
+            schedule(
+                "12:00",
+                2100,
+                SeatAvailability::Available {
+                    remaining_seats: None,
+                },
+            ),
+            schedule("14:00", 2100, SeatAvailability::FewLeft),
+        ];
+        let current = vec![
+            schedule("08:30", 2100, seats(4)),
+            schedule("10:00", 2100, seats(1)),
+            schedule("12:00", 2100, SeatAvailability::FewLeft),
+            schedule("14:00", 2100, seats(2)),
+        ];
+
+        assert_eq!(
+            kinds(&previous, &current),
+            vec![
+                (
+                    "08:30".to_string(),
+                    ChangeKind::SeatsIncreased { from: seats(2) }
+                ),
+                (
+                    "10:00".to_string(),
+                    ChangeKind::SeatsDecreased { from: seats(5) }
+                ),
+                (
+                    "12:00".to_string(),
+                    ChangeKind::SeatsDecreased {
+                        from: SeatAvailability::Available {
+                            remaining_seats: None
+                        }
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sold_out_and_price_change() {
+        let previous = vec![
+            schedule("08:30", 2100, seats(1)),
+            schedule("10:00", 2100, seats(3)),
+        ];
+        let current = vec![
+            schedule("08:30", 2100, SeatAvailability::Waitlist),
+            schedule("10:00", 2400, seats(2)),
+        ];
+
+        assert_eq!(
+            kinds(&previous, &current),
+            vec![
+                ("08:30".to_string(), ChangeKind::SoldOut),
+                (
+                    "10:00".to_string(),
+                    ChangeKind::SeatsDecreased { from: seats(3) }
+                ),
+                ("10:00".to_string(), ChangeKind::PriceChanged { from: 2100 }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_departed_bus_is_not_a_change() {
+        let previous = vec![
+            schedule("08:30", 2100, seats(1)),
+            schedule("10:00", 2100, seats(3)),
+        ];
+        let current = vec![schedule("10:00", 2100, seats(3))];
+
+        assert!(kinds(&previous, &current).is_empty());
+    }
+}
@@ -0,0 +1,287 @@
+//! Circuit breaker in front of the bus operator's site.
+//!
+//! After `failure_threshold` consecutive upstream failures the circuit opens and
+//! requests fail at once with [`ScraperError::CircuitOpen`]. Once `open_for` has
+//! passed, a single probe request is let through: success closes the circuit,
+//! another upstream failure keeps it open for a new period.
+
+use crate::error::{Result, ScraperError};
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::Instant;
+
+const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+const DEFAULT_OPEN_SECS: u64 = 120;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive upstream failures that open the circuit
+    pub failure_threshold: u32,
+    /// How long the circuit stays open before a probe is allowed
+    pub open_for: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            open_for: Duration::from_secs(DEFAULT_OPEN_SECS),
+        }
+    }
+}
+
+impl CircuitBreakerConfig {
+    /// Read `SCRAPER_BREAKER_THRESHOLD` and `SCRAPER_BREAKER_OPEN_SECS`, falling back to the defaults
+    pub fn from_env() -> Self {
+        let default = Self::default();
+
+        Self {
+            failure_threshold: dotenvy::var("SCRAPER_BREAKER_THRESHOLD")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(default.failure_threshold),
+            open_for: dotenvy::var("SCRAPER_BREAKER_OPEN_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map_or(default.open_for, Duration::from_secs),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests go through
+    Closed,
+    /// Requests fail at once
+    Open,
+    /// One probe request is in flight
+    HalfOpen,
+}
+
+#[derive(Debug)]
+enum Inner {
+    Closed { failures: u32 },
+    Open { until: Instant },
+    HalfOpen { probe_started: Instant },
+}
+
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    inner: Mutex<Inner>,
+    state: watch::Sender<CircuitState>,
+}
+
+impl CircuitBreaker {
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config: CircuitBreakerConfig {
+                failure_threshold: config.failure_threshold.max(1),
+                ..config
+            },
+            inner: Mutex::new(Inner::Closed { failures: 0 }),
+            state: watch::Sender::new(CircuitState::Closed),
+        }
+    }
+
+    pub fn state(&self) -> CircuitState {
+        *self.state.borrow()
+    }
+
+    /// Receiver notified on every state change
+    pub fn subscribe(&self) -> watch::Receiver<CircuitState> {
+        self.state.subscribe()
+    }
+
+    /// Whether a request may be sent now.
+    ///
+    /// A caller that gets `Ok` must report the outcome with [`Self::record`].
+    pub fn check(&self) -> Result<()> {
+        let mut inner = self.lock();
+        let now = Instant::now();
+
+        match *inner {
+            Inner::Closed { .. } => Ok(()),
+            Inner::Open { until } if now < until => Err(ScraperError::CircuitOpen {
+                retry_in: until - now,
+            }),
+            // A probe that never reported back (its task was cancelled) must not block forever
+            Inner::HalfOpen { probe_started } if now < probe_started + self.config.open_for => {
+                Err(ScraperError::CircuitOpen {
+                    retry_in: probe_started + self.config.open_for - now,
+                })
+            }
+            Inner::Open { .. } | Inner::HalfOpen { .. } => {
+                *inner = Inner::HalfOpen { probe_started: now };
+                self.publish(CircuitState::HalfOpen);
+                Ok(())
+            }
+        }
+    }
+
+    /// Count the outcome of a request let through by [`Self::check`].
+    ///
+    /// Only failures of the site itself count; a 404 or an unparsable page
+    /// still means the site is answering.
+    pub fn record<T>(&self, result: &Result<T>) {
+        let failed = result
+            .as_ref()
+            .is_err_and(ScraperError::is_upstream_failure);
+        let mut inner = self.lock();
+
+        if !failed {
+            *inner = Inner::Closed { failures: 0 };
+            self.publish(CircuitState::Closed);
+            return;
+        }
+
+        let failures = match *inner {
+            Inner::Closed { failures } => failures + 1,
+            // The probe failed, or a request sent before the circuit opened did
+            Inner::Open { .. } | Inner::HalfOpen { .. } => self.config.failure_threshold,
+        };
+
+        if failures >= self.config.failure_threshold {
+            *inner = Inner::Open {
+                until: Instant::now() + self.config.open_for,
+            };
+            self.publish(CircuitState::Open);
+        } else {
+            *inner = Inner::Closed { failures };
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn publish(&self, state: CircuitState) {
+        self.state.send_if_modified(|current| {
+            let changed = *current != state;
+            *current = state;
+            changed
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    fn breaker(threshold: u32, open_ms: u64) -> CircuitBreaker {
+        CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: threshold,
+            open_for: Duration::from_millis(open_ms),
+        })
+    }
+
+    fn upstream_failure() -> Result<()> {
+        Err(ScraperError::Upstream {
+            status: 503,
+            retry_after: None,
+        })
+    }
+
+    #[test]
+    fn test_opens_after_consecutive_failures() {
+        let breaker = breaker(3, 60_000);
+
+        for _ in 0..2 {
+            breaker.record(&upstream_failure());
+        }
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        breaker.record(&upstream_failure());
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(matches!(
+            breaker.check(),
+            Err(ScraperError::CircuitOpen { .. })
+        ));
+    }
+
+    #[test]
+    fn test_success_resets_failure_count() {
+        let breaker = breaker(2, 60_000);
+
+        breaker.record(&upstream_failure());
+        breaker.record(&Ok(()));
+        breaker.record(&upstream_failure());
+
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_non_upstream_errors_do_not_count() {
+        let breaker = breaker(1, 60_000);
+
+        breaker.record::<()>(&Err(ScraperError::InvalidResponse("HTTP 404".to_string())));
+        breaker.record::<()>(&Err(ScraperError::Parse("bad xml".to_string())));
+
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert!(breaker.check().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_half_open_lets_one_probe_through() {
+        let breaker = breaker(1, 20);
+        breaker.record(&upstream_failure());
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+
+        assert!(breaker.check().is_ok());
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        // Others keep failing fast while the probe is in flight
+        assert!(breaker.check().is_err());
+
+        breaker.record(&Ok(()));
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert!(breaker.check().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_failed_probe_reopens() {
+        let breaker = breaker(3, 20);
+        for _ in 0..3 {
+            breaker.record(&upstream_failure());
+        }
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert!(breaker.check().is_ok());
+
+        // A single failed probe is enough, whatever the threshold
+        breaker.record(&upstream_failure());
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(breaker.check().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_sees_transitions() {
+        let breaker = breaker(1, 60_000);
+        let mut rx = breaker.subscribe();
+
+        breaker.record(&upstream_failure());
+        assert!(rx.has_changed().unwrap_or_default());
+        assert_eq!(*rx.borrow_and_update(), CircuitState::Open);
+
+        // Repeated failures while open publish nothing new
+        breaker.record(&upstream_failure());
+        assert!(!rx.has_changed().unwrap_or_default());
+    }
+
+    #[test]
+    #[serial]
+    fn test_config_from_env() {
+        temp_env::with_vars(
+            [
+                ("SCRAPER_BREAKER_THRESHOLD", Some("10")),
+                ("SCRAPER_BREAKER_OPEN_SECS", Some("30")),
+            ],
+            || {
+                let config = CircuitBreakerConfig::from_env();
+                assert_eq!(config.failure_threshold, 10);
+                assert_eq!(config.open_for, Duration::from_secs(30));
+            },
+        );
+    }
+}
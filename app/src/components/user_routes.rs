@@ -1,8 +1,12 @@
 use crate::api::*;
 use crate::components_impl::{
-    PassengerCountData, build_user_route_form_dto, calculate_total_passengers,
-    extract_user_route_form_state,
+    DateWindowData, PassengerCountData, PauseAction, PlanFilterData, PollingData,
+    build_service_calendar, build_user_route_form_dto, calculate_total_passengers,
+    delivery_status_badge_class, extract_user_route_form_state, format_paused_until,
+    partition_routes_by_status, pick_service_day,
 };
+use crate::types::SeatType;
+use chrono::Weekday;
 use leptos::prelude::*;
 
 #[cfg(target_arch = "wasm32")]
@@ -27,6 +31,16 @@ pub fn UserRoutesPage() -> impl IntoView {
         },
     );
 
+    let deliveries_resource = Resource::new(
+        move || selected_user_id.get(),
+        |user_id| async move {
+            match user_id {
+                Some(id) => get_alert_deliveries(id).await,
+                None => Ok(vec![]),
+            }
+        },
+    );
+
     let refetch_routes = move || routes_resource.refetch();
 
     view! {
@@ -113,6 +127,19 @@ pub fn UserRoutesPage() -> impl IntoView {
                                                         }
                                                     });
                                                 }
+                                                on_pause=move |id: String, action: PauseAction| {
+                                                    leptos::task::spawn_local(async move {
+                                                        let result = match action {
+                                                            PauseAction::Enable => set_user_route_enabled(id, true).await,
+                                                            PauseAction::Disable => set_user_route_enabled(id, false).await,
+                                                            PauseAction::Snooze => snooze_user_route(id).await,
+                                                            PauseAction::Resume => resume_user_route(id).await,
+                                                        };
+                                                        if result.is_ok() {
+                                                            refetch_routes();
+                                                        }
+                                                    });
+                                                }
                                             />
                                         }.into_any()
                                     }
@@ -124,6 +151,22 @@ pub fn UserRoutesPage() -> impl IntoView {
                             })
                     }}
                 </Suspense>
+                <Suspense fallback=|| ()>
+                    {move || {
+                        deliveries_resource
+                            .get()
+                            .map(|result| match result {
+                                Ok(deliveries) if deliveries.is_empty() => ().into_any(),
+                                Ok(deliveries) => {
+                                    view! { <AlertDeliveriesTable deliveries=deliveries/> }.into_any()
+                                }
+                                Err(e) => {
+                                    view! { <p class="text-danger-600">"Error loading alerts: " {e.to_string()}</p> }
+                                        .into_any()
+                                }
+                            })
+                    }}
+                </Suspense>
             </Show>
         </div>
     }
@@ -150,6 +193,7 @@ fn RoutesTableSkeleton() -> impl IntoView {
                         <th class="table-header-cell">"Stations"</th>
                         <th class="table-header-cell">"Dates"</th>
                         <th class="table-header-cell">"Passengers"</th>
+                        <th class="table-header-cell">"Tracking"</th>
                         <th class="table-header-cell text-right">"Actions"</th>
                     </tr>
                 </thead>
@@ -160,6 +204,7 @@ fn RoutesTableSkeleton() -> impl IntoView {
                             <td class="table-cell"><div class="skeleton-text w-40"/></td>
                             <td class="table-cell"><div class="skeleton-text w-36"/></td>
                             <td class="table-cell"><div class="skeleton h-5 w-16 rounded-full"/></td>
+                            <td class="table-cell"><div class="skeleton-text w-20"/></td>
                             <td class="table-cell">
                                 <div class="flex justify-end gap-2">
                                     <div class="skeleton h-8 w-16 rounded-lg"/>
@@ -235,6 +280,32 @@ fn UserRoutesTable(
     routes: Vec<UserRouteWithPassengersDto>,
     on_edit: impl Fn(UserRouteWithPassengersDto) + 'static + Copy,
     on_delete: impl Fn(String) + 'static + Copy,
+    on_pause: impl Fn(String, PauseAction) + 'static + Copy,
+) -> impl IntoView {
+    let (active, archived) = partition_routes_by_status(routes);
+
+    view! {
+        <div class="space-y-6">
+            {(!active.is_empty()).then(|| view! {
+                <RoutesTable routes=active on_edit=on_edit on_delete=on_delete on_pause=on_pause />
+            })}
+            {(!archived.is_empty()).then(|| view! {
+                <div>
+                    <h3 class="text-sm font-medium text-surface-500 mb-2">"Archived Routes"</h3>
+                    <RoutesTable routes=archived on_edit=on_edit on_delete=on_delete on_pause=on_pause />
+                    <p class="form-hint">"Their dates have passed; edit the dates to track them again"</p>
+                </div>
+            })}
+        </div>
+    }
+}
+
+#[component]
+fn RoutesTable(
+    routes: Vec<UserRouteWithPassengersDto>,
+    on_edit: impl Fn(UserRouteWithPassengersDto) + 'static + Copy,
+    on_delete: impl Fn(String) + 'static + Copy,
+    on_pause: impl Fn(String, PauseAction) + 'static + Copy,
 ) -> impl IntoView {
     view! {
         <div class="table-container">
@@ -245,6 +316,7 @@ fn UserRoutesTable(
                         <th class="table-header-cell">"Stations"</th>
                         <th class="table-header-cell">"Dates"</th>
                         <th class="table-header-cell">"Passengers"</th>
+                        <th class="table-header-cell">"Tracking"</th>
                         <th class="table-header-cell text-right">"Actions"</th>
                     </tr>
                 </thead>
@@ -252,7 +324,7 @@ fn UserRoutesTable(
                     {routes
                         .into_iter()
                         .map(|route| {
-                            view! { <RouteRow route=route on_edit=on_edit on_delete=on_delete /> }
+                            view! { <RouteRow route=route on_edit=on_edit on_delete=on_delete on_pause=on_pause /> }
                         })
                         .collect_view()}
                 </tbody>
@@ -266,6 +338,7 @@ fn RouteRow(
     route: UserRouteWithPassengersDto,
     on_edit: impl Fn(UserRouteWithPassengersDto) + 'static + Copy,
     on_delete: impl Fn(String) + 'static + Copy,
+    on_pause: impl Fn(String, PauseAction) + 'static + Copy,
 ) -> impl IntoView {
     let route_clone = route.clone();
     let route_id = route.id.clone();
@@ -291,12 +364,73 @@ fn RouteRow(
                 <div class="text-sm text-surface-500">"→ " {route.arrival_station.clone()}</div>
             </td>
             <td class="table-cell">
-                <div class="text-surface-900">{route.date_start.clone()}</div>
-                <div class="text-sm text-surface-500">"to " {route.date_end.clone()}</div>
+                {match &route.date_window {
+                    Some(window) => view! {
+                        <div class="text-surface-900">{window.describe()}</div>
+                        <div class="text-sm text-surface-500">"Rolling window"</div>
+                    }.into_any(),
+                    None => view! {
+                        <div class="text-surface-900">{route.date_start.clone()}</div>
+                        <div class="text-sm text-surface-500">"to " {route.date_end.clone()}</div>
+                    }.into_any(),
+                }}
             </td>
             <td class="table-cell">
                 <span class="badge-info">{total_passengers} " passengers"</span>
             </td>
+            <td class="table-cell">
+                <label class="flex items-center gap-2 cursor-pointer">
+                    <input
+                        type="checkbox"
+                        class="form-checkbox"
+                        prop:checked=route.enabled
+                        on:change={
+                            let uid = route_id.clone();
+                            move |ev| {
+                                let action = if event_target_checked(&ev) {
+                                    PauseAction::Enable
+                                } else {
+                                    PauseAction::Disable
+                                };
+                                on_pause(uid.clone(), action);
+                            }
+                        }
+                    />
+                    <span class="text-sm text-surface-700">
+                        {if route.enabled { "Enabled" } else { "Disabled" }}
+                    </span>
+                </label>
+                {match (route.enabled, route.paused_until.clone()) {
+                    (true, Some(until)) => view! {
+                        <div class="mt-1 flex items-center gap-2">
+                            <span class="badge-neutral">"Snoozed until " {format_paused_until(&until)}</span>
+                            <button
+                                type="button"
+                                class="btn-ghost btn-sm"
+                                on:click={
+                                    let uid = route_id.clone();
+                                    move |_| on_pause(uid.clone(), PauseAction::Resume)
+                                }
+                            >
+                                "Resume"
+                            </button>
+                        </div>
+                    }.into_any(),
+                    (true, None) => view! {
+                        <button
+                            type="button"
+                            class="btn-ghost btn-sm mt-1"
+                            on:click={
+                                let uid = route_id.clone();
+                                move |_| on_pause(uid.clone(), PauseAction::Snooze)
+                            }
+                        >
+                            "Snooze 24h"
+                        </button>
+                    }.into_any(),
+                    (false, _) => ().into_any(),
+                }}
+            </td>
             <td class="table-cell">
                 <div class="flex items-center justify-end gap-2">
                     <button
@@ -340,6 +474,58 @@ fn RouteRow(
     }
 }
 
+#[component]
+fn AlertDeliveriesTable(deliveries: Vec<AlertDeliveryDto>) -> impl IntoView {
+    view! {
+        <div>
+            <h3 class="text-sm font-medium text-surface-500 mb-2">"Recent Alerts"</h3>
+            <div class="table-container">
+                <table class="table">
+                    <thead class="table-header">
+                        <tr>
+                            <th class="table-header-cell">"Raised"</th>
+                            <th class="table-header-cell">"Stations"</th>
+                            <th class="table-header-cell">"Channel"</th>
+                            <th class="table-header-cell">"Delivery"</th>
+                        </tr>
+                    </thead>
+                    <tbody class="table-body">
+                        {deliveries
+                            .into_iter()
+                            .map(|delivery| {
+                                view! {
+                                    <tr class="table-row">
+                                        <td class="table-cell">
+                                            <div class="text-surface-900">{format_paused_until(&delivery.created_at)}</div>
+                                            <div class="text-sm text-surface-500">{delivery.bus_count} " buses"</div>
+                                        </td>
+                                        <td class="table-cell">
+                                            <div class="text-surface-900">{delivery.departure_station_name}</div>
+                                            <div class="text-sm text-surface-500">"→ " {delivery.arrival_station_name}</div>
+                                        </td>
+                                        <td class="table-cell">{delivery.channel_kind.label()}</td>
+                                        <td class="table-cell">
+                                            <span class=delivery_status_badge_class(delivery.status)>
+                                                {delivery.status.label()}
+                                            </span>
+                                            {(delivery.attempts > 1).then(|| view! {
+                                                <div class="text-sm text-surface-500 mt-1">{delivery.attempts} " attempts"</div>
+                                            })}
+                                            {delivery.last_error.map(|error| view! {
+                                                <div class="form-hint">{error}</div>
+                                            })}
+                                        </td>
+                                    </tr>
+                                }
+                            })
+                            .collect_view()}
+                    </tbody>
+                </table>
+            </div>
+        </div>
+    }
+}
+
 #[component]
 fn UserRouteFormModal(
     route: ReadSignal<Option<UserRouteWithPassengersDto>>,
@@ -356,8 +542,17 @@ fn UserRouteFormModal(
     let (arrival_station, set_arrival_station) = signal(initial.arrival_station);
     let (date_start, set_date_start) = signal(initial.date_start);
     let (date_end, set_date_end) = signal(initial.date_end);
+    let (date_window, set_date_window) = signal(initial.date_window);
     let (time_min, set_time_min) = signal(initial.time_min);
     let (time_max, set_time_max) = signal(initial.time_max);
+    let (max_price, set_max_price) = signal(initial.plan_filter.max_price);
+    let (seat_type, set_seat_type) = signal(initial.plan_filter.seat_type);
+    let (plan_ids_allow, set_plan_ids_allow) = signal(initial.plan_filter.plan_ids_allow);
+    let (plan_ids_deny, set_plan_ids_deny) = signal(initial.plan_filter.plan_ids_deny);
+    let (min_remaining_seats, set_min_remaining_seats) =
+        signal(initial.plan_filter.min_remaining_seats);
+    let (polling, set_polling) = signal(initial.polling);
+    let (notify_on_sold_out, set_notify_on_sold_out) = signal(initial.notify_on_sold_out);
 
     let (adult_men, set_adult_men) = signal(initial.passengers.adult_men);
     let (adult_women, set_adult_women) = signal(initial.passengers.adult_women);
@@ -373,11 +568,11 @@ fn UserRouteFormModal(
         signal(initial.passengers.handicap_child_women);
 
     let (is_saving, set_is_saving) = signal(false);
+    let (form_error, set_form_error) = signal(None::<String>);
 
     let user_id_clone = user_id.clone();
     let handle_submit = move |ev: leptos::ev::SubmitEvent| {
         ev.prevent_default();
-        set_is_saving.set(true);
 
         let passengers = PassengerCountData {
             adult_men: adult_men.get(),
@@ -390,7 +585,15 @@ fn UserRouteFormModal(
             handicap_child_women: handicap_child_women.get(),
         };
 
-        let form_data = build_user_route_form_dto(
+        let plan_filter = PlanFilterData {
+            max_price: max_price.get(),
+            seat_type: seat_type.get(),
+            plan_ids_allow: plan_ids_allow.get(),
+            plan_ids_deny: plan_ids_deny.get(),
+            min_remaining_seats: min_remaining_seats.get(),
+        };
+
+        let form_data = match build_user_route_form_dto(
             user_id_clone.clone(),
             area_id.get(),
             route_id_val.get(),
@@ -398,10 +601,22 @@ fn UserRouteFormModal(
             arrival_station.get(),
             date_start.get(),
             date_end.get(),
+            &date_window.get(),
             time_min.get(),
             time_max.get(),
+            &plan_filter,
+            &polling.get(),
+            notify_on_sold_out.get(),
             passengers,
-        );
+        ) {
+            Ok(form_data) => form_data,
+            Err(e) => {
+                set_form_error.set(Some(e));
+                return;
+            }
+        };
+        set_form_error.set(None);
+        set_is_saving.set(true);
 
         let route_uuid = route.get().as_ref().map(|r| r.id.clone());
         let is_edit_mode = route_uuid.is_some();
@@ -413,8 +628,9 @@ fn UserRouteFormModal(
                 create_user_route(form_data).await
             };
 
-            if result.is_ok() {
-                on_save();
+            match result {
+                Ok(_) => on_save(),
+                Err(e) => set_form_error.set(Some(e.to_string())),
             }
             set_is_saving.set(false);
         });
@@ -453,14 +669,36 @@ fn UserRouteFormModal(
                         />
 
                         <DateTimeSection
+                            route_id=route_id_val
+                            departure_station=departure_station
+                            arrival_station=arrival_station
                             date_start=date_start
                             set_date_start=set_date_start
                             date_end=date_end
                             set_date_end=set_date_end
+                            date_window=date_window
+                            set_date_window=set_date_window
                             time_min=time_min
                             set_time_min=set_time_min
                             time_max=time_max
                             set_time_max=set_time_max
+                            max_price=max_price
+                            set_max_price=set_max_price
+                            seat_type=seat_type
+                            set_seat_type=set_seat_type
+                            plan_ids_allow=plan_ids_allow
+                            set_plan_ids_allow=set_plan_ids_allow
+                            plan_ids_deny=plan_ids_deny
+                            set_plan_ids_deny=set_plan_ids_deny
+                            min_remaining_seats=min_remaining_seats
+                            set_min_remaining_seats=set_min_remaining_seats
+                        />
+
+                        <PollingSection polling=polling set_polling=set_polling />
+
+                        <AlertsSection
+                            notify_on_sold_out=notify_on_sold_out
+                            set_notify_on_sold_out=set_notify_on_sold_out
                         />
 
                         <PassengersSection
@@ -473,6 +711,10 @@ fn UserRouteFormModal(
                             handicap_child_men=handicap_child_men set_handicap_child_men=set_handicap_child_men
                             handicap_child_women=handicap_child_women set_handicap_child_women=set_handicap_child_women
                         />
+
+                        {move || form_error.get().map(|error| view! {
+                            <p class="text-danger-600">{error}</p>
+                        })}
                     </div>
 
                     <div class="modal-footer">
@@ -714,68 +956,428 @@ fn StationDropdown(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 #[component]
 fn DateTimeSection(
+    route_id: ReadSignal<String>,
+    departure_station: ReadSignal<String>,
+    arrival_station: ReadSignal<String>,
     date_start: ReadSignal<String>,
     set_date_start: WriteSignal<String>,
     date_end: ReadSignal<String>,
     set_date_end: WriteSignal<String>,
+    date_window: ReadSignal<DateWindowData>,
+    set_date_window: WriteSignal<DateWindowData>,
     time_min: ReadSignal<String>,
     set_time_min: WriteSignal<String>,
     time_max: ReadSignal<String>,
     set_time_max: WriteSignal<String>,
+    max_price: ReadSignal<String>,
+    set_max_price: WriteSignal<String>,
+    seat_type: ReadSignal<String>,
+    set_seat_type: WriteSignal<String>,
+    plan_ids_allow: ReadSignal<String>,
+    set_plan_ids_allow: WriteSignal<String>,
+    plan_ids_deny: ReadSignal<String>,
+    set_plan_ids_deny: WriteSignal<String>,
+    min_remaining_seats: ReadSignal<String>,
+    set_min_remaining_seats: WriteSignal<String>,
 ) -> impl IntoView {
+    // Sales calendar depends on the full route + station selection
+    let available_dates = Resource::new(
+        move || {
+            (
+                route_id.get(),
+                departure_station.get(),
+                arrival_station.get(),
+            )
+        },
+        |(rid, dep, arr)| async move {
+            if rid.is_empty() || dep.is_empty() || arr.is_empty() {
+                Ok(vec![])
+            } else {
+                get_available_dates(rid, dep, arr).await
+            }
+        },
+    );
+
     view! {
         <fieldset class="fieldset">
             <legend class="fieldset-legend">"Date & Time"</legend>
-            <div class="grid grid-cols-2 gap-4">
+            <div class="form-group">
+                <label class="form-label">"Dates"</label>
+                <select
+                    class="form-select"
+                    prop:value=move || date_window.get().mode
+                    on:change=move |ev| {
+                        let mode = event_target_value(&ev);
+                        set_date_window.update(|w| w.mode = mode);
+                    }
+                >
+                    <option value="fixed">"Fixed dates"</option>
+                    <option value="relative">"Days from today"</option>
+                    <option value="weekdays">"Weekdays over the next weeks"</option>
+                </select>
+                <p class="form-hint">"Rolling windows move forward every day"</p>
+            </div>
+            <Show when=move || date_window.get().mode == "relative">
+                <RelativeWindowFields date_window=date_window set_date_window=set_date_window />
+            </Show>
+            <Show when=move || date_window.get().mode == "weekdays">
+                <WeekdaysWindowFields date_window=date_window set_date_window=set_date_window />
+            </Show>
+            <Show when=move || date_window.get().is_fixed()>
+                <div class="grid grid-cols-2 gap-4 mt-4">
+                    <div class="form-group">
+                        <label class="form-label form-label-required">"Start Date"</label>
+                        <input
+                            type="date"
+                            class="form-input"
+                            required
+                            prop:value=date_start
+                            on:input=move |ev| set_date_start.set(event_target_value(&ev))
+                        />
+                    </div>
+                    <div class="form-group">
+                        <label class="form-label form-label-required">"End Date"</label>
+                        <input
+                            type="date"
+                            class="form-input"
+                            required
+                            prop:value=date_end
+                            on:input=move |ev| set_date_end.set(event_target_value(&ev))
+                        />
+                    </div>
+                </div>
+                <Suspense fallback=|| ()>
+                    {move || available_dates.get().map(|result| {
+                        match result {
+                            Ok(dates) => view! {
+                                <ServiceCalendar
+                                    dates=dates
+                                    date_start=date_start
+                                    set_date_start=set_date_start
+                                    date_end=date_end
+                                    set_date_end=set_date_end
+                                />
+                            }.into_any(),
+                            Err(_) => view! {
+                                <p class="form-hint mt-2">"Service calendar unavailable"</p>
+                            }.into_any(),
+                        }
+                    })}
+                </Suspense>
+            </Show>
+            <div class="grid grid-cols-2 gap-4 mt-4">
                 <div class="form-group">
-                    <label class="form-label form-label-required">"Start Date"</label>
+                    <label class="form-label">"Departure Time Min"</label>
                     <input
-                        type="date"
+                        type="time"
                         class="form-input"
-                        required
-                        prop:value=date_start
-                        on:input=move |ev| set_date_start.set(event_target_value(&ev))
+                        prop:value=time_min
+                        on:input=move |ev| set_time_min.set(event_target_value(&ev))
                     />
+                    <p class="form-hint">"Optional filter"</p>
                 </div>
                 <div class="form-group">
-                    <label class="form-label form-label-required">"End Date"</label>
+                    <label class="form-label">"Departure Time Max"</label>
                     <input
-                        type="date"
+                        type="time"
                         class="form-input"
-                        required
-                        prop:value=date_end
-                        on:input=move |ev| set_date_end.set(event_target_value(&ev))
+                        prop:value=time_max
+                        on:input=move |ev| set_time_max.set(event_target_value(&ev))
                     />
+                    <p class="form-hint">"Optional filter"</p>
                 </div>
             </div>
             <div class="grid grid-cols-2 gap-4 mt-4">
                 <div class="form-group">
-                    <label class="form-label">"Departure Time Min"</label>
+                    <label class="form-label">"Max Price (¥)"</label>
                     <input
-                        type="time"
+                        type="text"
+                        inputmode="numeric"
                         class="form-input"
-                        prop:value=time_min
-                        on:input=move |ev| set_time_min.set(event_target_value(&ev))
+                        placeholder="8,000"
+                        prop:value=max_price
+                        on:input=move |ev| set_max_price.set(event_target_value(&ev))
                     />
                     <p class="form-hint">"Optional filter"</p>
                 </div>
                 <div class="form-group">
-                    <label class="form-label">"Departure Time Max"</label>
+                    <label class="form-label">"Seat Type"</label>
+                    <select
+                        class="form-select"
+                        prop:value=seat_type
+                        on:change=move |ev| set_seat_type.set(event_target_value(&ev))
+                    >
+                        <option value="">"Any"</option>
+                        {SeatType::ALL
+                            .into_iter()
+                            .map(|s| view! {
+                                <option value=s.as_str()>{s.label()}</option>
+                            })
+                            .collect_view()}
+                    </select>
+                    <p class="form-hint">"Known once plan details are fetched"</p>
+                </div>
+                <div class="form-group">
+                    <label class="form-label">"Min Remaining Seats"</label>
                     <input
-                        type="time"
+                        type="number"
                         class="form-input"
-                        prop:value=time_max
-                        on:input=move |ev| set_time_max.set(event_target_value(&ev))
+                        min="1"
+                        prop:value=min_remaining_seats
+                        on:input=move |ev| set_min_remaining_seats.set(event_target_value(&ev))
                     />
-                    <p class="form-hint">"Optional filter"</p>
+                    <p class="form-hint">"Only when the site shows a count"</p>
+                </div>
+                <div class="form-group">
+                    <label class="form-label">"Only Plan IDs"</label>
+                    <input
+                        type="text"
+                        class="form-input"
+                        placeholder="12345, 67890"
+                        prop:value=plan_ids_allow
+                        on:input=move |ev| set_plan_ids_allow.set(event_target_value(&ev))
+                    />
+                    <p class="form-hint">"Leave empty for every plan"</p>
+                </div>
+                <div class="form-group">
+                    <label class="form-label">"Exclude Plan IDs"</label>
+                    <input
+                        type="text"
+                        class="form-input"
+                        placeholder="12345, 67890"
+                        prop:value=plan_ids_deny
+                        on:input=move |ev| set_plan_ids_deny.set(event_target_value(&ev))
+                    />
+                    <p class="form-hint">"Comma-separated"</p>
+                </div>
+            </div>
+        </fieldset>
+    }
+}
+
+#[component]
+fn PollingSection(
+    polling: ReadSignal<PollingData>,
+    set_polling: WriteSignal<PollingData>,
+) -> impl IntoView {
+    view! {
+        <fieldset class="fieldset">
+            <legend class="fieldset-legend">"Checks"</legend>
+            <div class="form-group">
+                <label class="form-label">"Check Interval"</label>
+                <div class="relative">
+                    <input
+                        type="number"
+                        class="form-input pr-16"
+                        min="60"
+                        max="3600"
+                        placeholder="User's interval"
+                        prop:value=move || polling.get().interval
+                        on:input=move |ev| {
+                            let interval = event_target_value(&ev);
+                            set_polling.update(|p| p.interval = interval);
+                        }
+                    />
+                    <span class="absolute right-3 top-1/2 -translate-y-1/2 text-sm text-surface-400">
+                        "seconds"
+                    </span>
                 </div>
+                <p class="form-hint">"Leave empty to use the user's scrape interval"</p>
             </div>
+            <label class="flex items-center gap-2 cursor-pointer mt-4">
+                <input
+                    type="checkbox"
+                    class="form-checkbox"
+                    prop:checked=move || polling.get().adaptive
+                    on:change=move |ev| {
+                        let adaptive = event_target_checked(&ev);
+                        set_polling.update(|p| p.adaptive = adaptive);
+                    }
+                />
+                <span class="text-sm text-surface-700">"Check more often as departure approaches"</span>
+            </label>
+        </fieldset>
+    }
+}
+
+#[component]
+fn AlertsSection(
+    notify_on_sold_out: ReadSignal<bool>,
+    set_notify_on_sold_out: WriteSignal<bool>,
+) -> impl IntoView {
+    view! {
+        <fieldset class="fieldset">
+            <legend class="fieldset-legend">"Alerts"</legend>
+            <label class="flex items-center gap-2 cursor-pointer">
+                <input
+                    type="checkbox"
+                    class="form-checkbox"
+                    prop:checked=move || notify_on_sold_out.get()
+                    on:change=move |ev| set_notify_on_sold_out.set(event_target_checked(&ev))
+                />
+                <span class="text-sm text-surface-700">"Notify when seats are no longer available"</span>
+            </label>
         </fieldset>
     }
 }
 
+#[component]
+fn RelativeWindowFields(
+    date_window: ReadSignal<DateWindowData>,
+    set_date_window: WriteSignal<DateWindowData>,
+) -> impl IntoView {
+    view! {
+        <div class="grid grid-cols-2 gap-4 mt-4">
+            <div class="form-group">
+                <label class="form-label form-label-required">"From (days from today)"</label>
+                <input
+                    type="number"
+                    class="form-input"
+                    min="0"
+                    required
+                    prop:value=move || date_window.get().start_days
+                    on:input=move |ev| {
+                        let value = event_target_value(&ev);
+                        set_date_window.update(|w| w.start_days = value);
+                    }
+                />
+            </div>
+            <div class="form-group">
+                <label class="form-label form-label-required">"To (days from today)"</label>
+                <input
+                    type="number"
+                    class="form-input"
+                    min="0"
+                    required
+                    prop:value=move || date_window.get().end_days
+                    on:input=move |ev| {
+                        let value = event_target_value(&ev);
+                        set_date_window.update(|w| w.end_days = value);
+                    }
+                />
+                <p class="form-hint">"0 is the day of the check"</p>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn WeekdaysWindowFields(
+    date_window: ReadSignal<DateWindowData>,
+    set_date_window: WriteSignal<DateWindowData>,
+) -> impl IntoView {
+    let weekdays = [
+        Weekday::Mon,
+        Weekday::Tue,
+        Weekday::Wed,
+        Weekday::Thu,
+        Weekday::Fri,
+        Weekday::Sat,
+        Weekday::Sun,
+    ];
+
+    view! {
+        <div class="grid grid-cols-2 gap-4 mt-4">
+            <div class="form-group">
+                <label class="form-label form-label-required">"Weekdays"</label>
+                <div class="flex flex-wrap gap-1">
+                    {weekdays.into_iter().map(|day| view! {
+                        <button
+                            type="button"
+                            class=move || {
+                                if date_window.get().weekdays.contains(&day) {
+                                    "btn-primary btn-sm w-12"
+                                } else {
+                                    "btn-ghost btn-sm w-12"
+                                }
+                            }
+                            on:click=move |_| set_date_window.update(|w| w.toggle_weekday(day))
+                        >
+                            {day.to_string()}
+                        </button>
+                    }).collect_view()}
+                </div>
+            </div>
+            <div class="form-group">
+                <label class="form-label form-label-required">"Weeks"</label>
+                <input
+                    type="number"
+                    class="form-input"
+                    min="1"
+                    required
+                    prop:value=move || date_window.get().weeks
+                    on:input=move |ev| {
+                        let value = event_target_value(&ev);
+                        set_date_window.update(|w| w.weeks = value);
+                    }
+                />
+                <p class="form-hint">"Counted from the day of the check"</p>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn ServiceCalendar(
+    dates: Vec<AvailableDateDto>,
+    date_start: ReadSignal<String>,
+    set_date_start: WriteSignal<String>,
+    date_end: ReadSignal<String>,
+    set_date_end: WriteSignal<String>,
+) -> impl IntoView {
+    let days = build_service_calendar(&dates);
+    if days.is_empty() {
+        return ().into_any();
+    }
+
+    view! {
+        <div class="form-group mt-4">
+            <label class="form-label">"Service Calendar"</label>
+            <div class="flex flex-wrap gap-1">
+                {days.into_iter().map(|day| {
+                    let date = day.date.clone();
+                    let date_check = day.date.clone();
+                    let title = if day.has_service {
+                        day.date.clone()
+                    } else {
+                        format!("{} - no service", day.date)
+                    };
+                    view! {
+                        <button
+                            type="button"
+                            title=title
+                            disabled=!day.has_service
+                            class=move || {
+                                let start = date_start.get();
+                                let end = date_end.get();
+                                if !day.has_service {
+                                    "btn-ghost btn-sm w-9 text-surface-300 line-through cursor-not-allowed"
+                                } else if date_check >= start && date_check <= end {
+                                    "btn-primary btn-sm w-9"
+                                } else {
+                                    "btn-ghost btn-sm w-9"
+                                }
+                            }
+                            on:click=move |_| {
+                                let (start, end) = pick_service_day(&date, &date_end.get());
+                                set_date_start.set(start);
+                                set_date_end.set(end);
+                            }
+                        >
+                            {day.day}
+                        </button>
+                    }
+                }).collect_view()}
+            </div>
+            <p class="form-hint">"Greyed out days have no service"</p>
+        </div>
+    }
+    .into_any()
+}
+
 #[allow(clippy::too_many_arguments)]
 #[component]
 fn PassengersSection(
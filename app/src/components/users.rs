@@ -1,8 +1,9 @@
 use crate::api::*;
 use crate::components_impl::{
-    build_user_form_dto, extract_user_form_state, notify_mode_badge_class, notify_mode_text,
-    user_status_badge_class, user_status_text,
+    build_user_form_dto, channel_target_placeholder, extract_user_form_state, new_channel_row,
+    notify_mode_badge_class, notify_mode_text, user_status_badge_class, user_status_text,
 };
+use crate::types::{ChannelKind, Locale};
 use leptos::prelude::*;
 
 #[cfg(target_arch = "wasm32")]
@@ -256,7 +257,9 @@ fn UserForm(
     let (enabled, set_enabled) = signal(initial.enabled);
     let (notify_on_change, set_notify_on_change) = signal(initial.notify_on_change_only);
     let (interval, set_interval) = signal(initial.interval);
-    let (webhook, set_webhook) = signal(initial.webhook);
+    let (quiet_hours, set_quiet_hours) = signal(initial.quiet_hours);
+    let (locale, set_locale) = signal(initial.locale);
+    let (channels, set_channels) = signal(initial.channels);
     let (is_saving, set_is_saving) = signal(false);
 
     let handle_submit = move |ev: leptos::ev::SubmitEvent| {
@@ -268,7 +271,9 @@ fn UserForm(
             enabled.get(),
             notify_on_change.get(),
             &interval.get(),
-            webhook.get(),
+            &quiet_hours.get(),
+            locale.get(),
+            channels.get(),
         );
 
         let user_id = user.get().as_ref().map(|u| u.id.clone());
@@ -362,14 +367,134 @@ fn UserForm(
                         </div>
 
                         <div class="form-group">
-                            <label class="form-label">"Discord Webhook URL"</label>
-                            <input
-                                type="url"
-                                class="form-input"
-                                placeholder="https://discord.com/api/webhooks/..."
-                                prop:value=webhook
-                                on:input=move |ev| set_webhook.set(event_target_value(&ev))
-                            />
+                            <label class="form-label">"Quiet Hours"</label>
+                            <div class="flex items-center gap-2">
+                                <input
+                                    type="time"
+                                    class="form-input w-32"
+                                    prop:value=move || quiet_hours.get().start
+                                    on:input=move |ev| {
+                                        let start = event_target_value(&ev);
+                                        set_quiet_hours.update(|q| q.start = start);
+                                    }
+                                />
+                                <span class="text-sm text-surface-500">"to"</span>
+                                <input
+                                    type="time"
+                                    class="form-input w-32"
+                                    prop:value=move || quiet_hours.get().end
+                                    on:input=move |ev| {
+                                        let end = event_target_value(&ev);
+                                        set_quiet_hours.update(|q| q.end = end);
+                                    }
+                                />
+                                <input
+                                    type="text"
+                                    class="form-input flex-1"
+                                    placeholder="Asia/Tokyo"
+                                    prop:value=move || quiet_hours.get().time_zone
+                                    on:input=move |ev| {
+                                        let time_zone = event_target_value(&ev);
+                                        set_quiet_hours.update(|q| q.time_zone = time_zone);
+                                    }
+                                />
+                            </div>
+                            <p class="form-hint">"Alerts are held during these hours and sent as one digest afterwards. Leave empty to always notify."</p>
+                        </div>
+
+                        <div class="form-group">
+                            <label class="form-label">"Notification Language"</label>
+                            <select
+                                class="form-select w-40"
+                                on:change=move |ev| {
+                                    if let Some(selected) = Locale::parse(&event_target_value(&ev)) {
+                                        set_locale.set(selected);
+                                    }
+                                }
+                            >
+                                {Locale::ALL
+                                    .into_iter()
+                                    .map(|option| view! {
+                                        <option value=option.as_str() selected=move || locale.get() == option>
+                                            {option.label()}
+                                        </option>
+                                    })
+                                    .collect_view()}
+                            </select>
+                        </div>
+
+                        <div class="form-group">
+                            <label class="form-label">"Notification Channels"</label>
+                            <div class="space-y-2">
+                                {move || {
+                                    channels
+                                        .get()
+                                        .into_iter()
+                                        .enumerate()
+                                        .map(|(index, channel)| {
+                                            view! {
+                                                <div class="flex items-center gap-2">
+                                                    <select
+                                                        class="form-select w-32"
+                                                        on:change=move |ev| {
+                                                            if let Some(kind) = ChannelKind::parse(&event_target_value(&ev)) {
+                                                                set_channels.update(|c| c[index].kind = kind);
+                                                            }
+                                                        }
+                                                    >
+                                                        {ChannelKind::ALL
+                                                            .into_iter()
+                                                            .map(|kind| view! {
+                                                                <option value=kind.as_str() selected=kind == channel.kind>
+                                                                    {kind.label()}
+                                                                </option>
+                                                            })
+                                                            .collect_view()}
+                                                    </select>
+                                                    <input
+                                                        type="text"
+                                                        class="form-input flex-1"
+                                                        placeholder=channel_target_placeholder(channel.kind)
+                                                        prop:value=channel.target.clone()
+                                                        on:change=move |ev| {
+                                                            let target = event_target_value(&ev);
+                                                            set_channels.update(|c| c[index].target = target);
+                                                        }
+                                                    />
+                                                    <label class="flex items-center gap-1 cursor-pointer">
+                                                        <input
+                                                            type="checkbox"
+                                                            class="form-checkbox"
+                                                            prop:checked=channel.enabled
+                                                            on:change=move |ev| {
+                                                                let checked = event_target_checked(&ev);
+                                                                set_channels.update(|c| c[index].enabled = checked);
+                                                            }
+                                                        />
+                                                        <span class="text-sm text-surface-700">"On"</span>
+                                                    </label>
+                                                    <button
+                                                        type="button"
+                                                        class="btn-ghost btn-sm text-danger-600"
+                                                        on:click=move |_| set_channels.update(|c| {
+                                                            c.remove(index);
+                                                        })
+                                                    >
+                                                        "Remove"
+                                                    </button>
+                                                </div>
+                                            }
+                                        })
+                                        .collect_view()
+                                }}
+                                <button
+                                    type="button"
+                                    class="btn-secondary btn-sm"
+                                    on:click=move |_| set_channels.update(|c| c.push(new_channel_row()))
+                                >
+                                    "Add Channel"
+                                </button>
+                            </div>
                             <p class="form-hint">"Optional - Leave empty to disable notifications"</p>
                         </div>
                     </div>
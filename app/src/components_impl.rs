@@ -5,7 +5,16 @@
 //! we can achieve better test coverage since tarpaulin cannot measure
 //! code inside procedural macros.
 
-use crate::api::{UserDto, UserFormDto, UserRouteFormDto, UserRouteWithPassengersDto};
+use crate::api::{
+    AvailableDateDto, NotificationChannelDto, UserDto, UserFormDto, UserRouteFormDto,
+    UserRouteWithPassengersDto,
+};
+use crate::types::{
+    ChannelKind, DEFAULT_TIME_ZONE, DateWindow, DeliveryStatus, Locale, MIN_ROUTE_INTERVAL_SECS,
+    PlanFilter, QuietHours, RouteStatus, SeatType, parse_weekdays,
+};
+use chrono::{NaiveDate, Weekday};
+use std::collections::HashSet;
 
 // === Passenger Calculations ===
 
@@ -34,7 +43,7 @@ pub fn calculate_total_passengers(
 // === String Helpers ===
 
 /// Convert an empty string to None, otherwise Some(string).
-/// Used for optional form fields like webhook URLs and time filters.
+/// Used for optional form fields like time filters.
 pub fn optional_string(s: String) -> Option<String> {
     if s.is_empty() { None } else { Some(s) }
 }
@@ -89,19 +98,94 @@ pub fn is_edit_mode<T>(item: &Option<T>) -> bool {
 // === Form Data Builders ===
 
 /// Build a [`UserFormDto`] from form field values.
+///
+/// Email channels left blank are sent to the user's own address.
+#[allow(clippy::too_many_arguments)]
 pub fn build_user_form_dto(
     email: String,
     enabled: bool,
     notify_on_change_only: bool,
     interval_str: &str,
-    webhook: String,
+    quiet_hours: &QuietHoursData,
+    locale: Locale,
+    channels: Vec<NotificationChannelDto>,
 ) -> UserFormDto {
+    let notification_channels = channels
+        .into_iter()
+        .map(|mut c| {
+            if c.kind == ChannelKind::Email && c.target.trim().is_empty() {
+                c.target.clone_from(&email);
+            }
+            c
+        })
+        .filter(|c| !c.target.trim().is_empty())
+        .collect();
+
     UserFormDto {
         email,
         enabled,
         notify_on_change_only,
         scrape_interval_secs: parse_interval(interval_str, 300),
-        discord_webhook_url: optional_string(webhook),
+        quiet_hours: quiet_hours.to_quiet_hours(),
+        locale,
+        notification_channels,
+    }
+}
+
+/// Quiet hours inputs as typed into the user form.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QuietHoursData {
+    /// `HH:MM`, empty when the user has no quiet hours
+    pub start: String,
+    pub end: String,
+    pub time_zone: String,
+}
+
+impl QuietHoursData {
+    pub fn from_quiet_hours(quiet_hours: Option<&QuietHours>) -> Self {
+        quiet_hours.map_or_else(
+            || Self {
+                time_zone: DEFAULT_TIME_ZONE.to_string(),
+                ..Self::default()
+            },
+            |q| Self {
+                start: q.start.clone(),
+                end: q.end.clone(),
+                time_zone: q.time_zone.clone(),
+            },
+        )
+    }
+
+    /// Convert to [`QuietHours`]; `None` unless both times are filled in.
+    pub fn to_quiet_hours(&self) -> Option<QuietHours> {
+        QuietHours::from_parts(
+            optional_str(&self.start),
+            optional_str(&self.end),
+            optional_str(&self.time_zone),
+        )
+    }
+}
+
+fn optional_str(s: &str) -> Option<&str> {
+    let s = s.trim();
+    (!s.is_empty()).then_some(s)
+}
+
+/// A blank, enabled channel row for the user form.
+pub fn new_channel_row() -> NotificationChannelDto {
+    NotificationChannelDto {
+        kind: ChannelKind::default(),
+        target: String::new(),
+        enabled: true,
+    }
+}
+
+/// Placeholder shown in the target input for a channel kind.
+pub fn channel_target_placeholder(kind: ChannelKind) -> &'static str {
+    match kind {
+        ChannelKind::Discord => "https://discord.com/api/webhooks/...",
+        ChannelKind::Slack => "https://hooks.slack.com/services/...",
+        ChannelKind::Email => "Defaults to the user's email",
     }
 }
 
@@ -134,7 +218,191 @@ impl PassengerCountData {
     }
 }
 
+/// Plan filter inputs as typed into the route form.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlanFilterData {
+    pub max_price: String,
+    /// [`SeatType::as_str`] value, empty for any seat type
+    pub seat_type: String,
+    pub plan_ids_allow: String,
+    pub plan_ids_deny: String,
+    pub min_remaining_seats: String,
+}
+
+impl PlanFilterData {
+    pub fn from_filter(filter: &PlanFilter) -> Self {
+        Self {
+            max_price: filter.max_price.map(|p| p.to_string()).unwrap_or_default(),
+            seat_type: filter
+                .seat_type
+                .map(|s| s.as_str().to_string())
+                .unwrap_or_default(),
+            plan_ids_allow: PlanFilter::format_plan_ids(&filter.plan_ids_allow),
+            plan_ids_deny: PlanFilter::format_plan_ids(&filter.plan_ids_deny),
+            min_remaining_seats: filter
+                .min_remaining_seats
+                .map(|n| n.to_string())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Convert to a [`PlanFilter`]; blank values are left unset, values that
+    /// do not parse are an error rather than a wider filter.
+    ///
+    /// Prices may be written with `¥` and thousands separators.
+    pub fn to_filter(&self) -> Result<PlanFilter, String> {
+        let price: String = self
+            .max_price
+            .chars()
+            .filter(|c| !matches!(c, ',' | '¥' | '￥'))
+            .collect();
+        Ok(PlanFilter {
+            max_price: parse_optional_field(&price, "Max Price")?,
+            seat_type: SeatType::parse(&self.seat_type),
+            plan_ids_allow: parse_plan_ids_field(&self.plan_ids_allow, "Only Plan IDs")?,
+            plan_ids_deny: parse_plan_ids_field(&self.plan_ids_deny, "Exclude Plan IDs")?,
+            min_remaining_seats: parse_optional_field(
+                &self.min_remaining_seats,
+                "Min Remaining Seats",
+            )?,
+        })
+    }
+}
+
+/// Date mode inputs as typed into the route form.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DateWindowData {
+    /// `fixed`, `relative` or `weekdays`; empty means `fixed`
+    pub mode: String,
+    pub start_days: String,
+    pub end_days: String,
+    pub weekdays: Vec<Weekday>,
+    pub weeks: String,
+}
+
+impl DateWindowData {
+    pub fn from_window(window: Option<&DateWindow>) -> Self {
+        match window {
+            None => Self {
+                mode: "fixed".to_string(),
+                start_days: "0".to_string(),
+                end_days: "30".to_string(),
+                weekdays: parse_weekdays("fri,sat"),
+                weeks: "8".to_string(),
+            },
+            Some(DateWindow::Relative {
+                start_days,
+                end_days,
+            }) => Self {
+                mode: "relative".to_string(),
+                start_days: start_days.to_string(),
+                end_days: end_days.to_string(),
+                ..Self::from_window(None)
+            },
+            Some(DateWindow::Weekdays { weekdays, weeks }) => Self {
+                mode: "weekdays".to_string(),
+                weekdays: weekdays.clone(),
+                weeks: weeks.to_string(),
+                ..Self::from_window(None)
+            },
+        }
+    }
+
+    pub fn is_fixed(&self) -> bool {
+        !matches!(self.mode.as_str(), "relative" | "weekdays")
+    }
+
+    /// Convert to a [`DateWindow`]; `None` in fixed mode, an error when a number does not parse.
+    pub fn to_window(&self) -> Result<Option<DateWindow>, String> {
+        match self.mode.as_str() {
+            "relative" => Ok(Some(DateWindow::Relative {
+                start_days: parse_field(&self.start_days, "From (days from today)")?,
+                end_days: parse_field(&self.end_days, "To (days from today)")?,
+            })),
+            "weekdays" => Ok(Some(DateWindow::Weekdays {
+                weekdays: self.weekdays.clone(),
+                weeks: parse_field(&self.weeks, "Weeks")?,
+            })),
+            _ => Ok(None),
+        }
+    }
+
+    /// Add `day` to the selected weekdays, or remove it if already selected.
+    pub fn toggle_weekday(&mut self, day: Weekday) {
+        if let Some(pos) = self.weekdays.iter().position(|d| *d == day) {
+            self.weekdays.remove(pos);
+        } else {
+            self.weekdays.push(day);
+            self.weekdays.sort_by_key(Weekday::num_days_from_monday);
+        }
+    }
+}
+
+/// Parse a required number field, naming it in the error.
+fn parse_field<T: std::str::FromStr>(value: &str, label: &str) -> Result<T, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("{label} must be a whole number, got \"{}\"", value.trim()))
+}
+
+/// Parse an optional number field: `None` when blank, an error naming it when not a number.
+fn parse_optional_field<T: std::str::FromStr>(
+    value: &str,
+    label: &str,
+) -> Result<Option<T>, String> {
+    if value.trim().is_empty() {
+        return Ok(None);
+    }
+    parse_field(value, label).map(Some)
+}
+
+/// Parse a list of plan ids, naming the field in the error.
+fn parse_plan_ids_field(value: &str, label: &str) -> Result<Vec<u32>, String> {
+    PlanFilter::parse_plan_ids(value).map_err(|_| {
+        format!(
+            "{label} must be plan numbers separated by commas, got \"{}\"",
+            value.trim()
+        )
+    })
+}
+
+/// Check interval inputs as typed into the route form.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PollingData {
+    /// Seconds between checks, empty to use the user's interval
+    pub interval: String,
+    pub adaptive: bool,
+}
+
+impl PollingData {
+    pub fn from_route(scrape_interval_secs: Option<i64>, adaptive: bool) -> Self {
+        Self {
+            interval: scrape_interval_secs
+                .map(|s| s.to_string())
+                .unwrap_or_default(),
+            adaptive,
+        }
+    }
+
+    /// The interval override; `None` when blank, an error when not a number
+    /// or below [`MIN_ROUTE_INTERVAL_SECS`].
+    pub fn interval_secs(&self) -> Result<Option<i64>, String> {
+        let interval = parse_optional_field(&self.interval, "Check Interval")?;
+        match interval {
+            Some(secs) if secs < MIN_ROUTE_INTERVAL_SECS => Err(format!(
+                "Check Interval must be at least {MIN_ROUTE_INTERVAL_SECS} seconds, got {secs}"
+            )),
+            _ => Ok(interval),
+        }
+    }
+}
+
 /// Build a [`UserRouteFormDto`] from form field values.
+///
+/// Fixed dates are dropped when a date window is chosen. A window, plan
+/// filter or check interval whose numbers do not parse is an error rather
+/// than a fallback.
 #[allow(clippy::too_many_arguments)]
 pub fn build_user_route_form_dto(
     user_id: String,
@@ -144,11 +412,22 @@ pub fn build_user_route_form_dto(
     arrival_station: String,
     date_start: String,
     date_end: String,
+    date_window: &DateWindowData,
     time_min: String,
     time_max: String,
+    plan_filter: &PlanFilterData,
+    polling: &PollingData,
+    notify_on_sold_out: bool,
     passengers: PassengerCountData,
-) -> UserRouteFormDto {
-    UserRouteFormDto {
+) -> Result<UserRouteFormDto, String> {
+    let date_window = date_window.to_window()?;
+    let (date_start, date_end) = if date_window.is_some() {
+        (String::new(), String::new())
+    } else {
+        (date_start, date_end)
+    };
+
+    Ok(UserRouteFormDto {
         user_id,
         area_id,
         route_id,
@@ -156,8 +435,13 @@ pub fn build_user_route_form_dto(
         arrival_station,
         date_start,
         date_end,
+        date_window,
         departure_time_min: optional_string(time_min),
         departure_time_max: optional_string(time_max),
+        plan_filter: plan_filter.to_filter()?,
+        scrape_interval_secs: polling.interval_secs()?,
+        adaptive_interval: polling.adaptive,
+        notify_on_sold_out,
         adult_men: passengers.adult_men,
         adult_women: passengers.adult_women,
         child_men: passengers.child_men,
@@ -166,6 +450,53 @@ pub fn build_user_route_form_dto(
         handicap_adult_women: passengers.handicap_adult_women,
         handicap_child_men: passengers.handicap_child_men,
         handicap_child_women: passengers.handicap_child_women,
+    })
+}
+
+/// Split routes into those still tracked and archived ones, keeping their order.
+pub fn partition_routes_by_status(
+    routes: Vec<UserRouteWithPassengersDto>,
+) -> (
+    Vec<UserRouteWithPassengersDto>,
+    Vec<UserRouteWithPassengersDto>,
+) {
+    routes
+        .into_iter()
+        .partition(|r| r.status == RouteStatus::Active)
+}
+
+// === Route Pausing ===
+
+/// Pause control clicked in a route row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PauseAction {
+    Enable,
+    Disable,
+    Snooze,
+    Resume,
+}
+
+/// Format the end of a route's pause for its badge (RFC 3339 → `YYYY-MM-DD HH:MM UTC`).
+pub fn format_paused_until(paused_until: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(paused_until).map_or_else(
+        |_| paused_until.to_string(),
+        |until| {
+            until
+                .with_timezone(&chrono::Utc)
+                .format("%Y-%m-%d %H:%M UTC")
+                .to_string()
+        },
+    )
+}
+
+// === Alert Deliveries ===
+
+/// Badge class for an alert's delivery status.
+pub const fn delivery_status_badge_class(status: DeliveryStatus) -> &'static str {
+    match status {
+        DeliveryStatus::Pending => "badge-info",
+        DeliveryStatus::Sent => "badge-success",
+        DeliveryStatus::Failed => "badge-danger",
     }
 }
 
@@ -185,6 +516,55 @@ pub fn parse_date_from_display(date: &str) -> String {
     date.replace('-', "")
 }
 
+// === Service Calendar ===
+
+/// One day of the service calendar shown in the route form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceDay {
+    /// Date in form format (YYYY-MM-DD)
+    pub date: String,
+    /// Day of month, used as the cell label
+    pub day: u32,
+    /// Whether the operator sells tickets for this day
+    pub has_service: bool,
+}
+
+/// Build a contiguous calendar spanning the operator's sales window.
+/// Days between the first and last sold date that are missing from
+/// `available` are marked without service so the form can grey them out.
+pub fn build_service_calendar(available: &[AvailableDateDto]) -> Vec<ServiceDay> {
+    let sold: HashSet<NaiveDate> = available
+        .iter()
+        .filter_map(|d| NaiveDate::parse_from_str(&d.date_id, "%Y%m%d").ok())
+        .collect();
+
+    let (Some(first), Some(last)) = (sold.iter().min(), sold.iter().max()) else {
+        return Vec::new();
+    };
+
+    first
+        .iter_days()
+        .take_while(|day| day <= last)
+        .map(|day| ServiceDay {
+            date: day.format("%Y-%m-%d").to_string(),
+            day: chrono::Datelike::day(&day),
+            has_service: sold.contains(&day),
+        })
+        .collect()
+}
+
+/// Compute the new (start, end) dates after picking a day in the calendar.
+/// The picked day becomes the start; the end follows it when it would
+/// otherwise be empty or earlier than the start.
+pub fn pick_service_day(picked: &str, date_end: &str) -> (String, String) {
+    let end = if date_end.is_empty() || date_end < picked {
+        picked.to_string()
+    } else {
+        date_end.to_string()
+    };
+    (picked.to_string(), end)
+}
+
 // === Form State Extraction ===
 
 /// Initial state for a User form (create or edit).
@@ -194,7 +574,9 @@ pub struct UserFormState {
     pub enabled: bool,
     pub notify_on_change_only: bool,
     pub interval: String,
-    pub webhook: String,
+    pub quiet_hours: QuietHoursData,
+    pub locale: Locale,
+    pub channels: Vec<NotificationChannelDto>,
 }
 
 /// Extract the initial form state from an optional [`UserDto`].
@@ -206,14 +588,18 @@ pub fn extract_user_form_state(user: Option<&UserDto>) -> UserFormState {
             enabled: u.enabled,
             notify_on_change_only: u.notify_on_change_only,
             interval: u.scrape_interval_secs.to_string(),
-            webhook: u.discord_webhook_url.clone().unwrap_or_default(),
+            quiet_hours: QuietHoursData::from_quiet_hours(u.quiet_hours.as_ref()),
+            locale: u.locale,
+            channels: u.notification_channels.clone(),
         },
         None => UserFormState {
             email: String::new(),
             enabled: true,
             notify_on_change_only: true,
             interval: "300".to_string(),
-            webhook: String::new(),
+            quiet_hours: QuietHoursData::from_quiet_hours(None),
+            locale: Locale::default(),
+            channels: Vec::new(),
         },
     }
 }
@@ -227,8 +613,12 @@ pub struct UserRouteFormState {
     pub arrival_station: String,
     pub date_start: String,
     pub date_end: String,
+    pub date_window: DateWindowData,
     pub time_min: String,
     pub time_max: String,
+    pub plan_filter: PlanFilterData,
+    pub polling: PollingData,
+    pub notify_on_sold_out: bool,
     pub passengers: PassengerCountData,
 }
 
@@ -245,8 +635,12 @@ pub fn extract_user_route_form_state(
             arrival_station: r.arrival_station.clone(),
             date_start: r.date_start.clone(),
             date_end: r.date_end.clone(),
+            date_window: DateWindowData::from_window(r.date_window.as_ref()),
             time_min: r.departure_time_min.clone().unwrap_or_default(),
             time_max: r.departure_time_max.clone().unwrap_or_default(),
+            plan_filter: PlanFilterData::from_filter(&r.plan_filter),
+            polling: PollingData::from_route(r.scrape_interval_secs, r.adaptive_interval),
+            notify_on_sold_out: r.notify_on_sold_out,
             passengers: PassengerCountData {
                 adult_men: r.adult_men,
                 adult_women: r.adult_women,
@@ -265,14 +659,19 @@ pub fn extract_user_route_form_state(
             arrival_station: String::new(),
             date_start: String::new(),
             date_end: String::new(),
+            date_window: DateWindowData::from_window(None),
             time_min: String::new(),
             time_max: String::new(),
+            plan_filter: PlanFilterData::default(),
+            polling: PollingData::default(),
+            notify_on_sold_out: false,
             passengers: PassengerCountData::default(),
         },
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
 
@@ -414,30 +813,57 @@ mod tests {
             true,
             false,
             "600",
-            "https://webhook.url".to_string(),
+            &QuietHoursData::default(),
+            Locale::En,
+            vec![NotificationChannelDto {
+                target: "https://webhook.url".to_string(),
+                ..new_channel_row()
+            }],
         );
 
         assert_eq!(dto.email, "test@example.com");
         assert!(dto.enabled);
         assert!(!dto.notify_on_change_only);
         assert_eq!(dto.scrape_interval_secs, 600);
-        assert_eq!(
-            dto.discord_webhook_url,
-            Some("https://webhook.url".to_string())
+        assert_eq!(dto.locale, Locale::En);
+        assert_eq!(dto.notification_channels.len(), 1);
+        assert_eq!(dto.notification_channels[0].kind, ChannelKind::Discord);
+        assert_eq!(dto.notification_channels[0].target, "https://webhook.url");
+    }
+
+    #[test]
+    fn test_build_user_form_dto_drops_blank_channels() {
+        let dto = build_user_form_dto(
+            "test@example.com".to_string(),
+            true,
+            true,
+            "300",
+            &QuietHoursData::default(),
+            Locale::En,
+            vec![new_channel_row()],
         );
+
+        assert!(dto.notification_channels.is_empty());
     }
 
     #[test]
-    fn test_build_user_form_dto_empty_webhook() {
+    fn test_build_user_form_dto_blank_email_channel_uses_user_email() {
         let dto = build_user_form_dto(
             "test@example.com".to_string(),
             true,
             true,
             "300",
-            String::new(),
+            &QuietHoursData::default(),
+            Locale::En,
+            vec![NotificationChannelDto {
+                kind: ChannelKind::Email,
+                target: String::new(),
+                enabled: true,
+            }],
         );
 
-        assert!(dto.discord_webhook_url.is_none());
+        assert_eq!(dto.notification_channels.len(), 1);
+        assert_eq!(dto.notification_channels[0].target, "test@example.com");
     }
 
     #[test]
@@ -461,12 +887,18 @@ mod tests {
             "064".to_string(),
             "20250101".to_string(),
             "20250107".to_string(),
+            &DateWindowData::default(),
             "08:00".to_string(),
             "18:00".to_string(),
+            &PlanFilterData::default(),
+            &PollingData::default(),
+            true,
             passengers,
-        );
+        )
+        .unwrap();
 
         assert_eq!(dto.user_id, "user-uuid");
+        assert!(dto.notify_on_sold_out);
         assert_eq!(dto.area_id, 100);
         assert_eq!(dto.route_id, "155");
         assert_eq!(dto.departure_time_min, Some("08:00".to_string()));
@@ -487,13 +919,307 @@ mod tests {
             "064".to_string(),
             "20250101".to_string(),
             "20250107".to_string(),
+            &DateWindowData::default(),
             String::new(),
             String::new(),
+            &PlanFilterData::default(),
+            &PollingData::default(),
+            false,
             passengers,
-        );
+        )
+        .unwrap();
 
         assert!(dto.departure_time_min.is_none());
         assert!(dto.departure_time_max.is_none());
+        assert!(dto.plan_filter.is_empty());
+    }
+
+    #[test]
+    fn test_build_user_route_form_dto_plan_filter() {
+        let plan_filter = PlanFilterData {
+            max_price: " 8000 ".to_string(),
+            seat_type: "three_row".to_string(),
+            plan_ids_allow: "12345, 678".to_string(),
+            plan_ids_deny: String::new(),
+            min_remaining_seats: "2".to_string(),
+        };
+
+        let dto = build_user_route_form_dto(
+            "user-uuid".to_string(),
+            100,
+            "155".to_string(),
+            "001".to_string(),
+            "064".to_string(),
+            "20250101".to_string(),
+            "20250107".to_string(),
+            &DateWindowData::default(),
+            String::new(),
+            String::new(),
+            &plan_filter,
+            &PollingData::default(),
+            false,
+            PassengerCountData::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dto.plan_filter,
+            PlanFilter {
+                max_price: Some(8000),
+                seat_type: Some(SeatType::ThreeRow),
+                plan_ids_allow: vec![12345, 678],
+                plan_ids_deny: Vec::new(),
+                min_remaining_seats: Some(2),
+            }
+        );
+    }
+
+    #[test]
+    fn test_plan_filter_data_accepts_price_formats() {
+        for price in ["8,000", "¥8000", "￥8,000", " 8000 "] {
+            let data = PlanFilterData {
+                max_price: price.to_string(),
+                ..PlanFilterData::default()
+            };
+            assert_eq!(data.to_filter().unwrap().max_price, Some(8000), "{price}");
+        }
+    }
+
+    #[test]
+    fn test_plan_filter_data_rejects_unparsable_values() {
+        let invalid = [
+            PlanFilterData {
+                max_price: "cheap".to_string(),
+                ..PlanFilterData::default()
+            },
+            PlanFilterData {
+                min_remaining_seats: "many".to_string(),
+                ..PlanFilterData::default()
+            },
+            PlanFilterData {
+                plan_ids_allow: "12345, abc".to_string(),
+                ..PlanFilterData::default()
+            },
+            PlanFilterData {
+                plan_ids_deny: "1;2".to_string(),
+                ..PlanFilterData::default()
+            },
+        ];
+
+        for data in invalid {
+            assert!(data.to_filter().is_err(), "{data:?}");
+        }
+        let error = PlanFilterData {
+            min_remaining_seats: "many".to_string(),
+            ..PlanFilterData::default()
+        }
+        .to_filter()
+        .unwrap_err();
+        assert!(error.contains("Min Remaining Seats"));
+    }
+
+    #[test]
+    fn test_plan_filter_data_round_trip() {
+        let filter = PlanFilter {
+            max_price: Some(8000),
+            seat_type: Some(SeatType::Wide),
+            plan_ids_allow: Vec::new(),
+            plan_ids_deny: vec![1, 2],
+            min_remaining_seats: Some(2),
+        };
+
+        let data = PlanFilterData::from_filter(&filter);
+        assert_eq!(data.max_price, "8000");
+        assert_eq!(data.seat_type, "wide");
+        assert_eq!(data.plan_ids_allow, "");
+        assert_eq!(data.plan_ids_deny, "1,2");
+        assert_eq!(data.to_filter().unwrap(), filter);
+    }
+
+    #[test]
+    fn test_build_user_route_form_dto_date_window() {
+        let date_window = DateWindowData {
+            mode: "relative".to_string(),
+            start_days: "3".to_string(),
+            end_days: " 30".to_string(),
+            ..DateWindowData::default()
+        };
+
+        let dto = build_user_route_form_dto(
+            "user-uuid".to_string(),
+            100,
+            "155".to_string(),
+            "001".to_string(),
+            "064".to_string(),
+            "20250101".to_string(),
+            "20250107".to_string(),
+            &date_window,
+            String::new(),
+            String::new(),
+            &PlanFilterData::default(),
+            &PollingData::default(),
+            false,
+            PassengerCountData::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dto.date_window,
+            Some(DateWindow::Relative {
+                start_days: 3,
+                end_days: 30,
+            })
+        );
+        assert!(dto.date_start.is_empty());
+        assert!(dto.date_end.is_empty());
+    }
+
+    #[test]
+    fn test_date_window_data_round_trip() {
+        let window = DateWindow::Weekdays {
+            weekdays: vec![Weekday::Fri, Weekday::Sat],
+            weeks: 8,
+        };
+
+        let data = DateWindowData::from_window(Some(&window));
+        assert_eq!(data.mode, "weekdays");
+        assert_eq!(data.weeks, "8");
+        assert_eq!(data.to_window(), Ok(Some(window)));
+
+        let fixed = DateWindowData::from_window(None);
+        assert!(fixed.is_fixed());
+        assert_eq!(fixed.to_window(), Ok(None));
+    }
+
+    #[test]
+    fn test_quiet_hours_data_round_trip() {
+        let quiet_hours = QuietHours {
+            start: "22:00".to_string(),
+            end: "07:00".to_string(),
+            time_zone: "Europe/Paris".to_string(),
+        };
+
+        let data = QuietHoursData::from_quiet_hours(Some(&quiet_hours));
+        assert_eq!(data.start, "22:00");
+        assert_eq!(data.to_quiet_hours(), Some(quiet_hours));
+
+        let none = QuietHoursData::from_quiet_hours(None);
+        assert_eq!(none.time_zone, DEFAULT_TIME_ZONE);
+        assert_eq!(none.to_quiet_hours(), None);
+    }
+
+    #[test]
+    fn test_quiet_hours_data_blank_zone_uses_default() {
+        let data = QuietHoursData {
+            start: "23:00".to_string(),
+            end: "06:30".to_string(),
+            time_zone: " ".to_string(),
+        };
+
+        let quiet_hours = data.to_quiet_hours();
+        assert_eq!(
+            quiet_hours.map(|q| q.time_zone).as_deref(),
+            Some(DEFAULT_TIME_ZONE)
+        );
+    }
+
+    #[test]
+    fn test_polling_data() {
+        let data = PollingData::from_route(Some(120), true);
+        assert_eq!(data.interval, "120");
+        assert_eq!(data.interval_secs(), Ok(Some(120)));
+
+        let blank = PollingData::from_route(None, false);
+        assert_eq!(blank.interval, "");
+        assert_eq!(blank.interval_secs(), Ok(None));
+
+        let invalid = PollingData {
+            interval: "soon".to_string(),
+            adaptive: false,
+        };
+        assert_eq!(
+            invalid.interval_secs(),
+            Err("Check Interval must be a whole number, got \"soon\"".to_string())
+        );
+
+        let too_fast = PollingData {
+            interval: "30".to_string(),
+            adaptive: false,
+        };
+        assert_eq!(
+            too_fast.interval_secs(),
+            Err("Check Interval must be at least 60 seconds, got 30".to_string())
+        );
+        assert_eq!(
+            PollingData::from_route(Some(60), false).interval_secs(),
+            Ok(Some(60))
+        );
+    }
+
+    #[test]
+    fn test_date_window_data_unparsable_number() {
+        let data = DateWindowData {
+            mode: "relative".to_string(),
+            start_days: "soon".to_string(),
+            end_days: "30".to_string(),
+            ..DateWindowData::default()
+        };
+
+        assert_eq!(
+            data.to_window(),
+            Err("From (days from today) must be a whole number, got \"soon\"".to_string())
+        );
+
+        let weeks = DateWindowData {
+            mode: "weekdays".to_string(),
+            weeks: String::new(),
+            ..DateWindowData::from_window(None)
+        };
+        assert!(weeks.to_window().is_err());
+    }
+
+    #[test]
+    fn test_build_user_route_form_dto_rejects_unparsable_window() {
+        let date_window = DateWindowData {
+            mode: "relative".to_string(),
+            start_days: "3".to_string(),
+            end_days: String::new(),
+            ..DateWindowData::default()
+        };
+
+        let result = build_user_route_form_dto(
+            "user-uuid".to_string(),
+            100,
+            "155".to_string(),
+            "001".to_string(),
+            "064".to_string(),
+            String::new(),
+            String::new(),
+            &date_window,
+            String::new(),
+            String::new(),
+            &PlanFilterData::default(),
+            &PollingData::default(),
+            false,
+            PassengerCountData::default(),
+        );
+
+        // Not saved as a route with empty fixed dates
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_date_window_data_toggle_weekday() {
+        let mut data = DateWindowData::from_window(None);
+
+        data.toggle_weekday(Weekday::Mon);
+        assert_eq!(
+            data.weekdays,
+            vec![Weekday::Mon, Weekday::Fri, Weekday::Sat]
+        );
+
+        data.toggle_weekday(Weekday::Fri);
+        assert_eq!(data.weekdays, vec![Weekday::Mon, Weekday::Sat]);
     }
 
     // === Passenger Count Data Tests ===
@@ -520,6 +1246,79 @@ mod tests {
         assert_eq!(passengers.total(), 0);
     }
 
+    #[test]
+    fn test_delivery_status_badge_class() {
+        assert_eq!(
+            delivery_status_badge_class(DeliveryStatus::Pending),
+            "badge-info"
+        );
+        assert_eq!(
+            delivery_status_badge_class(DeliveryStatus::Sent),
+            "badge-success"
+        );
+        assert_eq!(
+            delivery_status_badge_class(DeliveryStatus::Failed),
+            "badge-danger"
+        );
+    }
+
+    #[test]
+    fn test_format_paused_until() {
+        assert_eq!(
+            format_paused_until("2026-10-18T14:30:00+00:00"),
+            "2026-10-18 14:30 UTC"
+        );
+        assert_eq!(
+            format_paused_until("2026-10-18T23:30:00+09:00"),
+            "2026-10-18 14:30 UTC"
+        );
+        assert_eq!(format_paused_until("soon"), "soon");
+    }
+
+    #[test]
+    fn test_partition_routes_by_status() {
+        let route = |id: &str, status| UserRouteWithPassengersDto {
+            id: id.to_string(),
+            user_id: "user-uuid".to_string(),
+            area_id: 1,
+            route_id: "155".to_string(),
+            departure_station: "001".to_string(),
+            arrival_station: "064".to_string(),
+            date_start: "20250101".to_string(),
+            date_end: "20250107".to_string(),
+            date_window: None,
+            departure_time_min: None,
+            departure_time_max: None,
+            plan_filter: PlanFilter::default(),
+            scrape_interval_secs: None,
+            adaptive_interval: false,
+            notify_on_sold_out: false,
+            status,
+            adult_men: 1,
+            adult_women: 0,
+            child_men: 0,
+            child_women: 0,
+            handicap_adult_men: 0,
+            handicap_adult_women: 0,
+            handicap_child_men: 0,
+            handicap_child_women: 0,
+            enabled: true,
+            paused_until: None,
+        };
+
+        let (active, archived) = partition_routes_by_status(vec![
+            route("a", RouteStatus::Active),
+            route("b", RouteStatus::Archived),
+            route("c", RouteStatus::Active),
+        ]);
+
+        let ids = |routes: &[UserRouteWithPassengersDto]| {
+            routes.iter().map(|r| r.id.clone()).collect::<Vec<_>>()
+        };
+        assert_eq!(ids(&active), vec!["a", "c"]);
+        assert_eq!(ids(&archived), vec!["b"]);
+    }
+
     // === Date Formatting Tests ===
 
     #[test]
@@ -542,6 +1341,80 @@ mod tests {
         assert_eq!(parse_date_from_display("20250115"), "20250115");
     }
 
+    // === Service Calendar Tests ===
+
+    fn available_date(date_id: &str) -> AvailableDateDto {
+        AvailableDateDto {
+            date_id: date_id.to_string(),
+            display_name: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_build_service_calendar_empty() {
+        assert!(build_service_calendar(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_build_service_calendar_marks_gaps_without_service() {
+        let available = vec![
+            available_date("20251030"),
+            available_date("20251102"),
+            available_date("20251031"),
+        ];
+
+        let calendar = build_service_calendar(&available);
+
+        let summary: Vec<(&str, bool)> = calendar
+            .iter()
+            .map(|d| (d.date.as_str(), d.has_service))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("2025-10-30", true),
+                ("2025-10-31", true),
+                ("2025-11-01", false),
+                ("2025-11-02", true),
+            ]
+        );
+        assert_eq!(calendar[2].day, 1);
+    }
+
+    #[test]
+    fn test_build_service_calendar_ignores_invalid_dates() {
+        let available = vec![available_date("not-a-date"), available_date("20251012")];
+
+        let calendar = build_service_calendar(&available);
+
+        assert_eq!(calendar.len(), 1);
+        assert_eq!(calendar[0].date, "2025-10-12");
+    }
+
+    #[test]
+    fn test_pick_service_day_fills_empty_end() {
+        assert_eq!(
+            pick_service_day("2025-10-12", ""),
+            ("2025-10-12".to_string(), "2025-10-12".to_string())
+        );
+    }
+
+    #[test]
+    fn test_pick_service_day_moves_end_before_start() {
+        assert_eq!(
+            pick_service_day("2025-10-12", "2025-10-01"),
+            ("2025-10-12".to_string(), "2025-10-12".to_string())
+        );
+    }
+
+    #[test]
+    fn test_pick_service_day_keeps_later_end() {
+        assert_eq!(
+            pick_service_day("2025-10-12", "2025-10-20"),
+            ("2025-10-12".to_string(), "2025-10-20".to_string())
+        );
+    }
+
     // === Form State Extraction Tests ===
 
     #[test]
@@ -551,7 +1424,7 @@ mod tests {
         assert!(state.enabled);
         assert!(state.notify_on_change_only);
         assert_eq!(state.interval, "300");
-        assert_eq!(state.webhook, "");
+        assert!(state.channels.is_empty());
     }
 
     #[test]
@@ -562,7 +1435,16 @@ mod tests {
             enabled: false,
             notify_on_change_only: false,
             scrape_interval_secs: 600,
-            discord_webhook_url: Some("https://webhook.url".to_string()),
+            quiet_hours: Some(QuietHours {
+                start: "22:00".to_string(),
+                end: "07:00".to_string(),
+                time_zone: DEFAULT_TIME_ZONE.to_string(),
+            }),
+            locale: Locale::default(),
+            notification_channels: vec![NotificationChannelDto {
+                target: "https://webhook.url".to_string(),
+                ..new_channel_row()
+            }],
             created_at: "2025-01-01".to_string(),
         };
 
@@ -571,23 +1453,27 @@ mod tests {
         assert!(!state.enabled);
         assert!(!state.notify_on_change_only);
         assert_eq!(state.interval, "600");
-        assert_eq!(state.webhook, "https://webhook.url");
+        assert_eq!(state.quiet_hours.start, "22:00");
+        assert_eq!(state.channels.len(), 1);
+        assert_eq!(state.channels[0].target, "https://webhook.url");
     }
 
     #[test]
-    fn test_extract_user_form_state_some_no_webhook() {
+    fn test_extract_user_form_state_some_no_channels() {
         let user = UserDto {
             id: "uuid".to_string(),
             email: "test@example.com".to_string(),
             enabled: true,
             notify_on_change_only: true,
             scrape_interval_secs: 300,
-            discord_webhook_url: None,
+            quiet_hours: None,
+            locale: Locale::default(),
+            notification_channels: Vec::new(),
             created_at: "2025-01-01".to_string(),
         };
 
         let state = extract_user_form_state(Some(&user));
-        assert_eq!(state.webhook, "");
+        assert!(state.channels.is_empty());
     }
 
     #[test]
@@ -615,8 +1501,14 @@ mod tests {
             arrival_station: "064".to_string(),
             date_start: "20250101".to_string(),
             date_end: "20250107".to_string(),
+            date_window: None,
             departure_time_min: Some("08:00".to_string()),
             departure_time_max: Some("18:00".to_string()),
+            plan_filter: PlanFilter::default(),
+            scrape_interval_secs: None,
+            adaptive_interval: false,
+            notify_on_sold_out: false,
+            status: RouteStatus::Active,
             adult_men: 2,
             adult_women: 1,
             child_men: 0,
@@ -625,6 +1517,8 @@ mod tests {
             handicap_adult_women: 0,
             handicap_child_men: 0,
             handicap_child_women: 0,
+            enabled: true,
+            paused_until: None,
         };
 
         let state = extract_user_route_form_state(Some(&route));
@@ -652,8 +1546,14 @@ mod tests {
             arrival_station: "064".to_string(),
             date_start: "20250101".to_string(),
             date_end: "20250107".to_string(),
+            date_window: None,
             departure_time_min: None,
             departure_time_max: None,
+            plan_filter: PlanFilter::default(),
+            scrape_interval_secs: None,
+            adaptive_interval: false,
+            notify_on_sold_out: false,
+            status: RouteStatus::Active,
             adult_men: 1,
             adult_women: 0,
             child_men: 0,
@@ -662,6 +1562,8 @@ mod tests {
             handicap_adult_women: 0,
             handicap_child_men: 0,
             handicap_child_women: 0,
+            enabled: true,
+            paused_until: None,
         };
 
         let state = extract_user_route_form_state(Some(&route));
@@ -676,7 +1578,7 @@ mod tests {
         assert!(!state.enabled);
         assert!(!state.notify_on_change_only);
         assert_eq!(state.interval, "");
-        assert_eq!(state.webhook, "");
+        assert!(state.channels.is_empty());
     }
 
     #[test]
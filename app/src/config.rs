@@ -66,6 +66,7 @@ impl Config {
         let date_range = DateRange {
             start: date_start,
             end: date_end,
+            window: None,
         };
 
         let passengers = PassengerCount {
@@ -117,6 +118,7 @@ impl Config {
                 date_range,
                 passengers,
                 time_filter,
+                plan_filter: None,
             },
         })
     }
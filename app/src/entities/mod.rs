@@ -2,6 +2,9 @@
 
 pub mod prelude;
 
+pub mod availability_snapshots;
+pub mod notification_channels;
+pub mod notification_outbox;
 pub mod route_states;
 pub mod user_passengers;
 pub mod user_routes;
@@ -1,5 +1,8 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.17
 
+pub use super::availability_snapshots::Entity as AvailabilitySnapshots;
+pub use super::notification_channels::Entity as NotificationChannels;
+pub use super::notification_outbox::Entity as NotificationOutbox;
 pub use super::route_states::Entity as RouteStates;
 pub use super::user_passengers::Entity as UserPassengers;
 pub use super::user_routes::Entity as UserRoutes;
@@ -11,6 +11,8 @@ pub struct Model {
     pub last_check: Option<DateTimeUtc>,
     pub total_checks: i64,
     pub total_alerts: i64,
+    /// JSON list of the schedules seen by the last check
+    pub last_snapshot: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -17,10 +17,26 @@ pub struct Model {
     pub departure_time_min: Option<String>,
     pub departure_time_max: Option<String>,
     pub created_at: DateTimeUtc,
+    pub max_price: Option<i32>,
+    pub seat_type: Option<String>,
+    pub plan_ids_allow: Option<String>,
+    pub plan_ids_deny: Option<String>,
+    pub min_remaining_seats: Option<i32>,
+    pub date_window: Option<String>,
+    pub status: String,
+    pub enabled: bool,
+    pub paused_until: Option<DateTimeUtc>,
+    pub scrape_interval_secs: Option<i64>,
+    pub adaptive_interval: bool,
+    pub notify_on_sold_out: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::availability_snapshots::Entity")]
+    AvailabilitySnapshots,
+    #[sea_orm(has_many = "super::notification_outbox::Entity")]
+    NotificationOutbox,
     #[sea_orm(has_one = "super::route_states::Entity")]
     RouteStates,
     #[sea_orm(has_one = "super::user_passengers::Entity")]
@@ -35,6 +51,18 @@ pub enum Relation {
     Users,
 }
 
+impl Related<super::availability_snapshots::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::AvailabilitySnapshots.def()
+    }
+}
+
+impl Related<super::notification_outbox::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::NotificationOutbox.def()
+    }
+}
+
 impl Related<super::route_states::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::RouteStates.def()
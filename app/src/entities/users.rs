@@ -12,16 +12,27 @@ pub struct Model {
     pub enabled: bool,
     pub notify_on_change_only: bool,
     pub scrape_interval_secs: i64,
-    pub discord_webhook_url: Option<String>,
     pub created_at: DateTimeUtc,
+    pub quiet_hours_start: Option<String>,
+    pub quiet_hours_end: Option<String>,
+    pub quiet_hours_tz: Option<String>,
+    pub locale: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::notification_channels::Entity")]
+    NotificationChannels,
     #[sea_orm(has_many = "super::user_routes::Entity")]
     UserRoutes,
 }
 
+impl Related<super::notification_channels::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::NotificationChannels.def()
+    }
+}
+
 impl Related<super::user_routes::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::UserRoutes.def()
@@ -1,4 +1,5 @@
 use std::fmt;
+use std::time::Duration;
 
 #[derive(Debug)]
 pub enum ScraperError {
@@ -9,6 +10,15 @@ pub enum ScraperError {
     #[cfg(feature = "ssr")]
     Database(String),
     ServiceUnavailable,
+    /// 429 or 5xx from the site, with the delay it asked for in `Retry-After`
+    Upstream {
+        status: u16,
+        retry_after: Option<Duration>,
+    },
+    /// Not sent: the site failed too often recently (see [`crate::circuit_breaker`])
+    CircuitOpen {
+        retry_in: Duration,
+    },
     InvalidResponse(String),
     NotFound(String),
 }
@@ -23,6 +33,12 @@ impl fmt::Display for ScraperError {
             #[cfg(feature = "ssr")]
             Self::Database(msg) => write!(f, "Database error: {msg}"),
             Self::ServiceUnavailable => write!(f, "Service temporarily unavailable (503)"),
+            Self::Upstream { status, .. } => write!(f, "Upstream unavailable: HTTP {status}"),
+            Self::CircuitOpen { retry_in } => write!(
+                f,
+                "Upstream circuit open, next attempt in {}s",
+                retry_in.as_secs()
+            ),
             Self::InvalidResponse(msg) => write!(f, "Invalid response: {msg}"),
             Self::NotFound(msg) => write!(f, "Not found: {msg}"),
         }
@@ -31,6 +47,37 @@ impl fmt::Display for ScraperError {
 
 impl std::error::Error for ScraperError {}
 
+impl ScraperError {
+    /// Whether the same request may succeed if sent again later:
+    /// rate limiting, server-side failures and timeouts
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            #[cfg(feature = "ssr")]
+            Self::Http(e) => e.is_timeout(),
+            Self::ServiceUnavailable | Self::Upstream { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// Whether the site itself is failing, as opposed to answering with an error
+    pub fn is_upstream_failure(&self) -> bool {
+        match self {
+            #[cfg(feature = "ssr")]
+            Self::Http(e) => e.is_timeout() || e.is_connect(),
+            Self::ServiceUnavailable | Self::Upstream { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// Delay the site asked for before the next attempt
+    pub const fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::Upstream { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+}
+
 #[cfg(feature = "ssr")]
 impl From<reqwest::Error> for ScraperError {
     fn from(e: reqwest::Error) -> Self {
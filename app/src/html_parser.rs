@@ -45,6 +45,7 @@ fn parse_single_bus(
         arrival_time,
         way_no: 0,
         available_plans,
+        booking_url: None,
     })
 }
 
@@ -82,14 +83,10 @@ fn extract_plans_from_bus(bus_element: ElementRef) -> Result<Vec<PricingPlan>> {
         .map_err(|e| ScraperError::Parse(format!("Invalid selector: {e:?}")))?;
 
     for form in bus_element.select(&form_selector) {
-        if let Some(seat_input) = form.select(&seat_selector).next() {
-            let seat_value = extract_value_attribute(seat_input).unwrap_or(2);
-
-            if seat_value == 1
-                && let Ok(plan) = extract_plan_from_form(form)
-            {
-                plans.push(plan);
-            }
+        if form.select(&seat_selector).next().is_some()
+            && let Ok(plan) = extract_plan_from_form(form)
+        {
+            plans.push(plan);
         }
     }
 
@@ -124,33 +121,34 @@ fn extract_plan_from_form(form: ElementRef) -> Result<PricingPlan> {
         .map(|btn| btn.text().collect::<String>().trim().to_string())
         .unwrap_or_default();
 
-    let remaining = parse_remaining_seats(&button_text);
-
     let price = extract_price_from_form(form).unwrap_or(0);
+    let plan_name = extract_plan_name_from_form(form).unwrap_or_default();
 
     let seat_selector = Selector::parse("input[type='hidden'][class*='seat_']")
         .map_err(|e| ScraperError::Parse(format!("Invalid selector: {e:?}")))?;
 
-    let plan_index = form
-        .select(&seat_selector)
-        .next()
+    let seat_input = form.select(&seat_selector).next();
+
+    let plan_index = seat_input
         .and_then(|input| input.value().attr("data-index"))
         .and_then(|v| v.parse().ok())
         .unwrap_or(0);
 
+    let seat_value = seat_input.and_then(|input| extract_value_attribute(input).ok());
+    let availability = parse_seat_availability(seat_value, &button_text);
+
     Ok(PricingPlan {
         plan_id,
         plan_index,
-        plan_name: String::new(),
+        plan_name,
         price,
         display_price: if price > 0 {
             format!("{price}円")
         } else {
             String::new()
         },
-        availability: SeatAvailability::Available {
-            remaining_seats: remaining,
-        },
+        availability,
+        details: None,
     })
 }
 
@@ -161,26 +159,43 @@ fn extract_price_from_form(form: ElementRef) -> Result<u32> {
     let re = Regex::new(r"(\d+,?\d*)円")
         .map_err(|e| ScraperError::Parse(format!("Regex error: {e}")))?;
 
-    let mut current = form.parent();
-    while let Some(parent) = current {
-        if let Some(parent_elem) = ElementRef::wrap(parent)
-            && let Some(price_elem) = parent_elem.select(&price_selector).next()
-        {
+    ancestors_matching(form, &price_selector)
+        .find_map(|price_elem| {
             let price_text = price_elem.text().collect::<String>();
-
-            if let Some(price) = re
-                .captures(&price_text)
+            re.captures(&price_text)
                 .and_then(|caps| caps.get(1))
                 .map(|m| m.as_str().replace(',', ""))
                 .and_then(|s| s.parse().ok())
-            {
-                return Ok(price);
-            }
-        }
-        current = parent.parent();
-    }
+        })
+        .ok_or_else(|| ScraperError::Parse("Price element not found".to_string()))
+}
 
-    Err(ScraperError::Parse("Price element not found".to_string()))
+/// Name shown above the booking button of a plan, e.g. `通常運賃（窓口・コンビニ購入用）`
+fn extract_plan_name_from_form(form: ElementRef) -> Result<String> {
+    let name_selector = Selector::parse("p.planName")
+        .map_err(|e| ScraperError::Parse(format!("Invalid selector: {e:?}")))?;
+
+    ancestors_matching(form, &name_selector)
+        .map(|name_elem| {
+            name_elem
+                .text()
+                .collect::<String>()
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .find(|name| !name.is_empty())
+        .ok_or_else(|| ScraperError::Parse("Plan name element not found".to_string()))
+}
+
+/// First element matching `selector` inside each ancestor of `form`, closest ancestor first
+fn ancestors_matching<'a>(
+    form: ElementRef<'a>,
+    selector: &'a Selector,
+) -> impl Iterator<Item = ElementRef<'a>> {
+    form.ancestors()
+        .filter_map(ElementRef::wrap)
+        .filter_map(|ancestor| ancestor.select(selector).next())
 }
 
 pub fn parse_remaining_seats(button_text: &str) -> Option<u32> {
@@ -190,6 +205,31 @@ pub fn parse_remaining_seats(button_text: &str) -> Option<u32> {
         .and_then(|m| m.as_str().parse().ok())
 }
 
+/// Classify a plan from its `seat_N` hidden input (`1` = on sale, `2` = full)
+/// and the booking button label. Explicit sold-out or waitlist labels take
+/// precedence over the seat marker.
+pub fn parse_seat_availability(seat_value: Option<u8>, button_text: &str) -> SeatAvailability {
+    let text: String = button_text.split_whitespace().collect();
+
+    if text.contains("キャンセル待ち") {
+        return SeatAvailability::Waitlist;
+    }
+    if text.contains("満席") {
+        return SeatAvailability::SoldOut;
+    }
+    if text.contains("残りわずか") {
+        return SeatAvailability::FewLeft;
+    }
+
+    let remaining_seats = parse_remaining_seats(&text);
+    match seat_value {
+        Some(1) => SeatAvailability::Available { remaining_seats },
+        Some(2) => SeatAvailability::SoldOut,
+        _ if remaining_seats.is_some() => SeatAvailability::Available { remaining_seats },
+        _ => SeatAvailability::Unknown,
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
@@ -313,6 +353,9 @@ mod tests {
                         <li class="arr"><p class="time">8:30 着</p></li>
                     </ul>
                     <div class="planArea">
+                        <p class="planName">
+                            通常運賃（窓口・コンビニ購入用）
+                        </p>
                         <p class="price">12,000円</p>
                         <form name="selectPlan">
                             <input type="hidden" class="seat_0" value="1" data-index="0">
@@ -326,6 +369,10 @@ mod tests {
 
         let schedules = parse_schedules_html(html, "20251029").unwrap();
         assert_eq!(schedules.len(), 1);
+        assert_eq!(
+            schedules[0].available_plans[0].plan_name,
+            "通常運賃（窓口・コンビニ購入用）"
+        );
         assert_eq!(schedules[0].departure_time, "6:45");
         assert_eq!(schedules[0].arrival_time, "8:30");
         assert_eq!(schedules[0].departure_date, "20251029");
@@ -384,7 +431,7 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_schedules_html_sold_out_excluded() {
+    fn test_parse_schedules_html_sold_out_kept() {
         let html = r#"
             <html><body>
                 <section class="busSvclistItem">
@@ -406,8 +453,13 @@ mod tests {
 
         let schedules = parse_schedules_html(html, "20251029").unwrap();
         assert_eq!(schedules.len(), 1);
-        // Sold out plans (seat_0 value="2") are excluded
-        assert!(schedules[0].available_plans.is_empty());
+        // Sold out plans (seat_0 value="2") are kept but not bookable
+        assert_eq!(schedules[0].available_plans.len(), 1);
+        assert_eq!(
+            schedules[0].available_plans[0].availability,
+            SeatAvailability::SoldOut
+        );
+        assert!(!schedules[0].has_bookable_plans());
     }
 
     // === extract_plans_from_bus TESTS ===
@@ -434,15 +486,16 @@ mod tests {
         assert_eq!(plans.len(), 1);
         assert_eq!(plans[0].plan_id, 12345);
         assert_eq!(plans[0].price, 12000);
-        match &plans[0].availability {
-            SeatAvailability::Available { remaining_seats } => {
-                assert_eq!(*remaining_seats, Some(3));
+        assert_eq!(
+            plans[0].availability,
+            SeatAvailability::Available {
+                remaining_seats: Some(3)
             }
-        }
+        );
     }
 
     #[test]
-    fn test_extract_plans_from_bus_sold_out_excluded() {
+    fn test_extract_plans_from_bus_sold_out_kept() {
         let html = r#"
             <section class="busSvclistItem">
                 <div class="planArea">
@@ -460,7 +513,120 @@ mod tests {
         let element = document.select(&selector).next().unwrap();
 
         let plans = extract_plans_from_bus(element).unwrap();
-        assert!(plans.is_empty());
+        assert_eq!(plans.len(), 1);
+        assert_eq!(plans[0].plan_id, 12347);
+        assert_eq!(plans[0].availability, SeatAvailability::SoldOut);
+    }
+
+    #[test]
+    fn test_extract_plans_from_bus_mixed_availability() {
+        let html = r#"
+            <section class="busSvclistItem">
+                <div class="planArea">
+                    <p class="price">9,800円</p>
+                    <form name="selectPlan">
+                        <input type="hidden" class="seat_0" value="2" data-index="0">
+                        <input type="hidden" name="discntPlanNo" value="100">
+                        <button>キャンセル待ち</button>
+                    </form>
+                    <form name="selectPlan">
+                        <input type="hidden" class="seat_1" value="1" data-index="1">
+                        <input type="hidden" name="discntPlanNo" value="200">
+                        <button>残りわずか</button>
+                    </form>
+                </div>
+            </section>
+        "#;
+        let document = Html::parse_fragment(html);
+        let selector = Selector::parse("section.busSvclistItem").unwrap();
+        let element = document.select(&selector).next().unwrap();
+
+        let plans = extract_plans_from_bus(element).unwrap();
+        assert_eq!(plans.len(), 2);
+        assert_eq!(plans[0].availability, SeatAvailability::Waitlist);
+        assert_eq!(plans[1].availability, SeatAvailability::FewLeft);
+        assert_eq!(plans[1].plan_index, 1);
+    }
+
+    // === parse_seat_availability TESTS ===
+
+    #[test]
+    fn test_parse_seat_availability_available_with_count() {
+        assert_eq!(
+            parse_seat_availability(Some(1), "残り3席"),
+            SeatAvailability::Available {
+                remaining_seats: Some(3)
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_seat_availability_available_without_count() {
+        assert_eq!(
+            parse_seat_availability(Some(1), "予約"),
+            SeatAvailability::Available {
+                remaining_seats: None
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_seat_availability_sold_out() {
+        assert_eq!(
+            parse_seat_availability(Some(2), "満席"),
+            SeatAvailability::SoldOut
+        );
+        assert_eq!(
+            parse_seat_availability(Some(2), ""),
+            SeatAvailability::SoldOut
+        );
+        // Full-width space between characters
+        assert_eq!(
+            parse_seat_availability(None, "満　席"),
+            SeatAvailability::SoldOut
+        );
+    }
+
+    #[test]
+    fn test_parse_seat_availability_text_overrides_seat_marker() {
+        assert_eq!(
+            parse_seat_availability(Some(1), "満席"),
+            SeatAvailability::SoldOut
+        );
+        assert_eq!(
+            parse_seat_availability(Some(2), "キャンセル待ち"),
+            SeatAvailability::Waitlist
+        );
+    }
+
+    #[test]
+    fn test_parse_seat_availability_few_left() {
+        assert_eq!(
+            parse_seat_availability(Some(1), "残りわずか"),
+            SeatAvailability::FewLeft
+        );
+    }
+
+    #[test]
+    fn test_parse_seat_availability_unknown() {
+        assert_eq!(
+            parse_seat_availability(None, "予約"),
+            SeatAvailability::Unknown
+        );
+        assert_eq!(
+            parse_seat_availability(Some(9), ""),
+            SeatAvailability::Unknown
+        );
+    }
+
+    #[test]
+    fn test_parse_seat_availability_count_without_marker() {
+        assert_eq!(
+            parse_seat_availability(None, "残り2席"),
+            SeatAvailability::Available {
+                remaining_seats: Some(2)
+            }
+        );
     }
 
     // === extract_value_attribute TESTS ===
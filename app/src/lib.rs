@@ -1,6 +1,10 @@
 #[cfg(feature = "ssr")]
 pub mod api_impl;
 #[cfg(feature = "ssr")]
+pub mod availability_diff;
+#[cfg(feature = "ssr")]
+pub mod circuit_breaker;
+#[cfg(feature = "ssr")]
 pub mod config;
 #[cfg(feature = "ssr")]
 pub mod db;
@@ -12,12 +16,22 @@ pub mod html_parser;
 #[cfg(feature = "ssr")]
 pub mod notifier;
 #[cfg(feature = "ssr")]
+pub mod rate_limiter;
+#[cfg(feature = "ssr")]
 pub mod repositories;
 #[cfg(feature = "ssr")]
+pub mod response_cache;
+#[cfg(feature = "ssr")]
+pub mod retry;
+#[cfg(feature = "ssr")]
+pub mod route_events;
+#[cfg(feature = "ssr")]
 pub mod scraper;
 #[cfg(feature = "ssr")]
 pub mod seed;
 #[cfg(feature = "ssr")]
+pub mod state_hash;
+#[cfg(feature = "ssr")]
 pub mod translations;
 pub mod types;
 
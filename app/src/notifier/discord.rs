@@ -0,0 +1,763 @@
+use super::templates::messages;
+use super::{
+    NotificationContext, Notifier, PartialDelivery, QueuedAlert, UpstreamEvent, alert_entries,
+    alert_title, archived_text, digest_locale, footer_text, format_date, plan_features, plan_title,
+    route_text, seats_label, summary_text, truncate,
+};
+use crate::availability_diff::AvailabilityChange;
+use crate::error::{Result, ScraperError};
+use crate::retry::parse_retry_after;
+use crate::types::{BusSchedule, Locale};
+use async_trait::async_trait;
+use reqwest::{Client, StatusCode, header::RETRY_AFTER};
+use serde_json::json;
+use std::fmt::Write;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Discord rejects messages with more than 10 embeds
+const MAX_EMBEDS: usize = 10;
+/// Discord rejects embeds with more than 25 fields
+const MAX_FIELDS: usize = 25;
+/// Discord rejects messages whose embeds add up to more than 6000 characters
+const MAX_MESSAGE_CHARS: usize = 6000;
+const MAX_TITLE_CHARS: usize = 256;
+const MAX_DESCRIPTION_CHARS: usize = 4096;
+const MAX_FIELD_NAME_CHARS: usize = 256;
+const MAX_FIELD_VALUE_CHARS: usize = 1024;
+/// Attempts after a 429 before the message is reported as failed
+const MAX_RATE_LIMIT_RETRIES: u32 = 3;
+/// Wait when a 429 does not say how long to back off
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(1);
+/// Longer rate limits are reported instead of waited out
+const MAX_RETRY_AFTER: Duration = Duration::from_mins(1);
+
+pub struct DiscordNotifier {
+    client: Client,
+}
+
+impl DiscordNotifier {
+    pub fn new() -> Self {
+        Self {
+            client: Client::new(),
+        }
+    }
+
+    /// The alert as one or more embeds, each within Discord's field and size limits.
+    ///
+    /// The first embed carries the title and summary, the last one the footer.
+    fn build_embeds(
+        &self,
+        schedules: &[BusSchedule],
+        changes: &[AvailabilityChange],
+        context: &NotificationContext,
+    ) -> Vec<serde_json::Value> {
+        let locale = context.locale;
+        let m = messages(locale);
+        let mut fields = Vec::new();
+
+        for entry in alert_entries(schedules, changes, locale) {
+            let (schedule, plan) = (entry.schedule, entry.plan);
+
+            let mut bus_info = entry
+                .change
+                .map_or_else(String::new, |change| format!("**{change}**\n"));
+            let _ = write!(
+                bus_info,
+                "📅 **{}**{}**{}**\n🕐 {}{}{}\n💺 {}\n💰 {}",
+                format_date(&schedule.departure_date, locale),
+                m.at,
+                schedule.departure_time,
+                m.arrival,
+                m.colon,
+                schedule.arrival_time,
+                seats_label(&plan.availability, locale),
+                plan.display_price
+            );
+            if let Some(features) = plan_features(plan, locale) {
+                let _ = write!(bus_info, "\n✨ {features}");
+            }
+            if let Some(url) = &schedule.booking_url {
+                let _ = write!(bus_info, "\n🔗 [{}]({url})", m.book);
+            }
+
+            fields.push(json!({
+                "name": truncate(
+                    &format!("🚌 Bus {} - {}", schedule.bus_number, plan_title(plan, locale)),
+                    MAX_FIELD_NAME_CHARS
+                ),
+                "value": truncate(&bus_info, MAX_FIELD_VALUE_CHARS),
+                "inline": false
+            }));
+        }
+
+        let mut description = format!(
+            "**{}**\n📍 {}\n📆 {} — {}",
+            summary_text(schedules, changes, locale),
+            route_text(context),
+            format_date(&context.date_range.0, locale),
+            format_date(&context.date_range.1, locale)
+        );
+        if let Some(url) = &context.availability_url {
+            let _ = write!(description, "\n[{}]({url})", m.availability_label);
+        }
+        if let Some(url) = &context.snooze_url {
+            let _ = write!(description, "\n[{}]({url})", m.snooze_label);
+        }
+
+        let footer = json!({ "text": footer_text(context) });
+        // Room kept in every embed so the footer fits whichever one ends up last
+        let footer_chars = embed_chars(&json!({ "footer": footer }));
+
+        let mut embeds = Vec::new();
+        let mut embed = json!({
+            "title": truncate(alert_title(schedules, changes, locale), MAX_TITLE_CHARS),
+            "description": truncate(&description, MAX_DESCRIPTION_CHARS),
+            "color": 3066993,
+            "fields": []
+        });
+        let mut chars = embed_chars(&embed) + footer_chars;
+
+        for field in fields {
+            let field_chars = field_chars(&field);
+            let field_count = embed["fields"].as_array().map_or(0, Vec::len);
+            if field_count == MAX_FIELDS || chars + field_chars > MAX_MESSAGE_CHARS {
+                embeds.push(embed);
+                embed = json!({ "color": 3066993, "fields": [] });
+                chars = footer_chars;
+            }
+            chars += field_chars;
+            if let Some(embed_fields) = embed["fields"].as_array_mut() {
+                embed_fields.push(field);
+            }
+        }
+
+        embed["footer"] = footer;
+        embed["timestamp"] = json!(chrono::Utc::now().to_rfc3339());
+        embeds.push(embed);
+        embeds
+    }
+
+    /// Messages for one alert, split as needed to stay within Discord's limits
+    fn build_alert_payloads(
+        &self,
+        schedules: &[BusSchedule],
+        changes: &[AvailabilityChange],
+        context: &NotificationContext,
+    ) -> Vec<serde_json::Value> {
+        pack_embeds(self.build_embeds(schedules, changes, context))
+            .into_iter()
+            .map(|embeds| json!({ "embeds": embeds }))
+            .collect()
+    }
+
+    /// Every alert's embeds, packed into as few messages as Discord allows
+    fn build_digest_payloads(&self, alerts: &[QueuedAlert]) -> Vec<serde_json::Value> {
+        let embeds = alerts
+            .iter()
+            .flat_map(|alert| self.build_embeds(&alert.schedules, &alert.changes, &alert.context))
+            .collect();
+
+        let title = messages(digest_locale(alerts)).digest_title;
+        pack_embeds(embeds)
+            .into_iter()
+            .map(|embeds| json!({ "content": title, "embeds": embeds }))
+            .collect()
+    }
+
+    /// Post `payloads` in order from the one after the first `sent`, reporting
+    /// how many were delivered in total when one fails
+    async fn post_from(
+        &self,
+        webhook_url: &str,
+        payloads: &[serde_json::Value],
+        sent: u32,
+    ) -> std::result::Result<(), PartialDelivery> {
+        for (delivered, payload) in (sent..).zip(payloads.iter().skip(sent as usize)) {
+            self.post(webhook_url, payload)
+                .await
+                .map_err(|error| PartialDelivery {
+                    sent: delivered,
+                    error,
+                })?;
+        }
+        Ok(())
+    }
+
+    /// Post one message, waiting out rate limits, and fail on any other error status
+    async fn post(&self, webhook_url: &str, payload: &serde_json::Value) -> Result<()> {
+        let mut retries = 0;
+
+        loop {
+            let response = self.client.post(webhook_url).json(payload).send().await?;
+            let status = response.status();
+            if status.is_success() {
+                return Ok(());
+            }
+            if status != StatusCode::TOO_MANY_REQUESTS {
+                return Err(ScraperError::InvalidResponse(format!(
+                    "Discord webhook returned HTTP {status}"
+                )));
+            }
+
+            let wait = rate_limit_delay(response).await;
+            if retries == MAX_RATE_LIMIT_RETRIES || wait > MAX_RETRY_AFTER {
+                return Err(ScraperError::InvalidResponse(format!(
+                    "Discord webhook still rate limited, retry after {}s",
+                    wait.as_secs_f64()
+                )));
+            }
+            retries += 1;
+            warn!(
+                "Discord webhook rate limited, retrying in {}ms",
+                wait.as_millis()
+            );
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// How long a 429 asked to wait: Discord's `retry_after` body (in seconds,
+/// fractional), else the `Retry-After` header, else [`DEFAULT_RETRY_AFTER`]
+async fn rate_limit_delay(response: reqwest::Response) -> Duration {
+    let header = response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_retry_after);
+    let body = response
+        .json::<serde_json::Value>()
+        .await
+        .ok()
+        .and_then(|body| body["retry_after"].as_f64())
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok());
+
+    body.or(header).unwrap_or(DEFAULT_RETRY_AFTER)
+}
+
+/// Group embeds into messages of at most [`MAX_EMBEDS`] and [`MAX_MESSAGE_CHARS`]
+fn pack_embeds(embeds: Vec<serde_json::Value>) -> Vec<Vec<serde_json::Value>> {
+    let mut messages: Vec<Vec<serde_json::Value>> = Vec::new();
+    let mut chars = 0;
+
+    for embed in embeds {
+        let size = embed_chars(&embed);
+        match messages.last_mut() {
+            Some(message) if message.len() < MAX_EMBEDS && chars + size <= MAX_MESSAGE_CHARS => {
+                message.push(embed);
+                chars += size;
+            }
+            _ => {
+                messages.push(vec![embed]);
+                chars = size;
+            }
+        }
+    }
+
+    messages
+}
+
+/// Characters Discord counts towards the per-message limit
+fn embed_chars(embed: &serde_json::Value) -> usize {
+    let text = |value: &serde_json::Value| value.as_str().map_or(0, |s| s.chars().count());
+
+    text(&embed["title"])
+        + text(&embed["description"])
+        + text(&embed["footer"]["text"])
+        + embed["fields"]
+            .as_array()
+            .map_or(0, |fields| fields.iter().map(field_chars).sum())
+}
+
+fn field_chars(field: &serde_json::Value) -> usize {
+    [&field["name"], &field["value"]]
+        .into_iter()
+        .filter_map(serde_json::Value::as_str)
+        .map(|s| s.chars().count())
+        .sum()
+}
+
+#[async_trait]
+impl Notifier for DiscordNotifier {
+    async fn send_startup_notification(
+        &self,
+        webhook_url: &str,
+        user_count: usize,
+        route_count: usize,
+        locale: Locale,
+    ) -> Result<()> {
+        let m = messages(locale);
+        let embed = json!({
+            "title": m.startup_title,
+            "description": (m.startup_text)(
+                &format!("**{user_count}**"),
+                &format!("**{route_count}**")
+            ),
+            "color": 5763719,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        });
+
+        self.post(webhook_url, &json!({ "embeds": [embed] }))
+            .await?;
+
+        info!("Startup notification sent successfully");
+        Ok(())
+    }
+
+    async fn send_availability_alert(
+        &self,
+        webhook_url: &str,
+        schedules: &[BusSchedule],
+        changes: &[AvailabilityChange],
+        context: &NotificationContext,
+    ) -> Result<()> {
+        if schedules.is_empty() && changes.is_empty() {
+            return Ok(());
+        }
+
+        let payloads = self.build_alert_payloads(schedules, changes, context);
+        self.post_from(webhook_url, &payloads, 0)
+            .await
+            .map_err(|failure| failure.error)?;
+
+        info!(
+            "Discord notification sent successfully ({} message(s))",
+            payloads.len()
+        );
+        Ok(())
+    }
+
+    async fn resume_availability_alert(
+        &self,
+        webhook_url: &str,
+        alert: &QueuedAlert,
+        sent: u32,
+    ) -> std::result::Result<(), PartialDelivery> {
+        if alert.schedules.is_empty() && alert.changes.is_empty() {
+            return Ok(());
+        }
+
+        let payloads = self.build_alert_payloads(&alert.schedules, &alert.changes, &alert.context);
+        self.post_from(webhook_url, &payloads, sent).await?;
+
+        info!(
+            "Discord notification sent successfully ({} message(s), {} already sent)",
+            payloads.len(),
+            sent
+        );
+        Ok(())
+    }
+
+    async fn send_upstream_status(
+        &self,
+        webhook_url: &str,
+        event: UpstreamEvent,
+        locale: Locale,
+    ) -> Result<()> {
+        let color = match event {
+            UpstreamEvent::Down => 15_548_997,
+            UpstreamEvent::Recovered => 5_763_719,
+        };
+        let embed = json!({
+            "title": event.title(locale),
+            "description": event.description(locale),
+            "color": color,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        });
+
+        self.post(webhook_url, &json!({ "embeds": [embed] }))
+            .await?;
+
+        info!("Upstream status notification sent successfully");
+        Ok(())
+    }
+
+    async fn send_route_archived(
+        &self,
+        webhook_url: &str,
+        context: &NotificationContext,
+    ) -> Result<()> {
+        let embed = json!({
+            "title": messages(context.locale).archived_title,
+            "description": archived_text(context),
+            "color": 9_807_270,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        });
+
+        self.post(webhook_url, &json!({ "embeds": [embed] }))
+            .await?;
+
+        info!("Route archived notification sent successfully");
+        Ok(())
+    }
+
+    async fn send_digest(&self, webhook_url: &str, alerts: &[QueuedAlert]) -> Result<()> {
+        self.resume_digest(webhook_url, alerts, 0)
+            .await
+            .map_err(|failure| failure.error)
+    }
+
+    async fn resume_digest(
+        &self,
+        webhook_url: &str,
+        alerts: &[QueuedAlert],
+        sent: u32,
+    ) -> std::result::Result<(), PartialDelivery> {
+        let payloads = self.build_digest_payloads(alerts);
+        self.post_from(webhook_url, &payloads, sent).await?;
+
+        info!("Discord digest sent successfully");
+        Ok(())
+    }
+}
+
+impl Default for DiscordNotifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::types::{PricingPlan, SeatAvailability};
+
+    #[test]
+    fn test_build_embed() {
+        let notifier = DiscordNotifier::new();
+
+        let schedules = vec![BusSchedule {
+            bus_number: "Bus_1".to_string(),
+            route_name: String::new(),
+            departure_station: String::new(),
+            departure_date: "20251029".to_string(),
+            departure_time: "22:25".to_string(),
+            arrival_station: String::new(),
+            arrival_date: "20251030".to_string(),
+            arrival_time: "5:20".to_string(),
+            way_no: 0,
+            available_plans: vec![PricingPlan {
+                plan_id: 12345,
+                plan_index: 1,
+                plan_name: String::new(),
+                price: 12000,
+                display_price: "12,000円".to_string(),
+                availability: SeatAvailability::Available {
+                    remaining_seats: Some(3),
+                },
+                details: None,
+            }],
+            booking_url: Some("https://www.highwaybus.com/gp/reservation/rsvPlanList?mode=search&bordingDate=20251029".to_string()),
+        }];
+
+        let context = NotificationContext {
+            departure_station_name: "Shinjuku".to_string(),
+            arrival_station_name: "Kamikochi".to_string(),
+            route_name: String::new(),
+            date_range: ("20251029".to_string(), "20251105".to_string()),
+            passenger_count: 2,
+            time_filter: Some(("20:00".to_string(), "23:59".to_string())),
+            snooze_url: None,
+            availability_url: None,
+            locale: Locale::default(),
+        };
+
+        let embed = &notifier.build_embeds(&schedules, &[], &context)[0];
+
+        assert_eq!(embed["title"], "🚌 Bus disponibles !");
+        assert_eq!(embed["color"], 3066993);
+        assert!(embed["fields"].is_array());
+        assert!(!embed["fields"].as_array().unwrap().is_empty());
+        assert!(
+            embed["fields"][0]["value"]
+                .as_str()
+                .unwrap()
+                .contains("🔗 [Réserver](https://www.highwaybus.com/gp/reservation/rsvPlanList?mode=search&bordingDate=20251029)")
+        );
+    }
+
+    #[test]
+    fn test_build_embed_lists_only_changes() {
+        use crate::availability_diff::ChangeKind;
+
+        let schedule = |time: &str, remaining_seats| BusSchedule {
+            bus_number: "Bus_1".to_string(),
+            route_name: String::new(),
+            departure_station: String::new(),
+            departure_date: "20251029".to_string(),
+            departure_time: time.to_string(),
+            arrival_station: String::new(),
+            arrival_date: "20251030".to_string(),
+            arrival_time: "5:20".to_string(),
+            way_no: 0,
+            available_plans: vec![PricingPlan {
+                plan_id: 12345,
+                plan_index: 0,
+                plan_name: String::new(),
+                price: 12000,
+                display_price: "12,000円".to_string(),
+                availability: SeatAvailability::Available { remaining_seats },
+                details: None,
+            }],
+            booking_url: None,
+        };
+        let changed = schedule("23:00", Some(4));
+        let changes = [AvailabilityChange {
+            plan: changed.available_plans[0].clone(),
+            schedule: changed.clone(),
+            kind: ChangeKind::SeatsIncreased {
+                from: SeatAvailability::Available {
+                    remaining_seats: Some(1),
+                },
+            },
+        }];
+        let context = NotificationContext {
+            departure_station_name: "Shinjuku".to_string(),
+            arrival_station_name: "Kamikochi".to_string(),
+            route_name: String::new(),
+            date_range: ("20251029".to_string(), "20251105".to_string()),
+            passenger_count: 1,
+            time_filter: None,
+            snooze_url: None,
+            availability_url: Some("https://bus.example.com/routes/r1/availability".to_string()),
+            locale: Locale::default(),
+        };
+
+        let embeds = DiscordNotifier::new().build_embeds(
+            &[schedule("22:25", Some(3)), changed],
+            &changes,
+            &context,
+        );
+        let embed = &embeds[0];
+
+        let fields = embed["fields"].as_array().unwrap();
+        assert_eq!(fields.len(), 1);
+        assert!(
+            fields[0]["value"]
+                .as_str()
+                .unwrap()
+                .starts_with("**📈 Places : 1 sièges → 4 sièges**\n📅 **29/10/2025** à **23:00**")
+        );
+        let description = embed["description"].as_str().unwrap();
+        assert!(description.starts_with("**1 changement(s) · 2 bus avec places disponibles**"));
+        assert!(
+            description
+                .contains("[📋 Toutes les places](https://bus.example.com/routes/r1/availability)")
+        );
+    }
+
+    #[test]
+    fn test_build_embed_empty() {
+        let notifier = DiscordNotifier::new();
+        let schedules = vec![];
+
+        let context = NotificationContext {
+            departure_station_name: "Shinjuku".to_string(),
+            arrival_station_name: "Kamikochi".to_string(),
+            route_name: String::new(),
+            date_range: ("20251029".to_string(), "20251105".to_string()),
+            passenger_count: 2,
+            time_filter: None,
+            snooze_url: None,
+            availability_url: None,
+            locale: Locale::default(),
+        };
+
+        let embed = &notifier.build_embeds(&schedules, &[], &context)[0];
+
+        assert_eq!(embed["title"], "🚌 Bus disponibles !");
+        let desc = embed["description"].as_str().unwrap();
+        assert!(desc.contains("Shinjuku"));
+        assert!(desc.contains("Kamikochi"));
+        assert!(!desc.contains("Pause"));
+    }
+
+    #[test]
+    fn test_build_digest_payloads_split_by_embed_limit() {
+        let alert = QueuedAlert {
+            schedules: Vec::new(),
+            changes: Vec::new(),
+            context: NotificationContext {
+                departure_station_name: "Shinjuku".to_string(),
+                arrival_station_name: "Kamikochi".to_string(),
+                route_name: String::new(),
+                date_range: ("20251029".to_string(), "20251105".to_string()),
+                passenger_count: 1,
+                time_filter: None,
+                snooze_url: None,
+                availability_url: None,
+                locale: Locale::default(),
+            },
+        };
+
+        let payloads = DiscordNotifier::new().build_digest_payloads(&vec![alert; 12]);
+
+        assert_eq!(payloads.len(), 2);
+        assert_eq!(payloads[0]["content"], messages(Locale::Fr).digest_title);
+        assert_eq!(payloads[0]["embeds"].as_array().unwrap().len(), 10);
+        assert_eq!(payloads[1]["embeds"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_build_embed_snooze_link() {
+        let context = NotificationContext {
+            departure_station_name: "Shinjuku".to_string(),
+            arrival_station_name: "Kamikochi".to_string(),
+            route_name: String::new(),
+            date_range: ("20251029".to_string(), "20251105".to_string()),
+            passenger_count: 1,
+            time_filter: None,
+            snooze_url: Some("https://bus.example.com/routes/r1/snooze".to_string()),
+            availability_url: None,
+            locale: Locale::default(),
+        };
+
+        let embed = &DiscordNotifier::new().build_embeds(&[], &[], &context)[0];
+
+        assert!(
+            embed["description"]
+                .as_str()
+                .unwrap()
+                .ends_with("\n[⏸️ Pause 24 h](https://bus.example.com/routes/r1/snooze)")
+        );
+    }
+
+    #[test]
+    fn test_build_embed_in_user_locale() {
+        let context = NotificationContext {
+            departure_station_name: "バスタ新宿（南口）".to_string(),
+            arrival_station_name: "Kamikochi".to_string(),
+            route_name: "新宿～富士五湖線".to_string(),
+            date_range: ("20251029".to_string(), "20251105".to_string()),
+            passenger_count: 1,
+            time_filter: None,
+            snooze_url: None,
+            availability_url: None,
+            locale: Locale::En,
+        };
+
+        let embed = &DiscordNotifier::new().build_embeds(&many_schedules(1, ""), &[], &context)[0];
+
+        assert_eq!(embed["title"], "🚌 Buses available!");
+        let description = embed["description"].as_str().unwrap();
+        assert!(
+            description.contains(
+                "📍 Shinjuku - Fuji Five Lakes (Shinjuku Expressway Bus Terminal (South Exit) → Kamikochi)"
+            )
+        );
+        assert!(description.contains("📆 2025-10-29 — 2025-11-05"));
+        let field = embed["fields"][0]["value"].as_str().unwrap();
+        assert!(field.contains("**2025-10-29** at **22:25**"));
+        assert!(field.contains("🕐 Arrival: 5:20"));
+        assert!(field.contains("💺 3 seats"));
+        assert_eq!(embed["footer"]["text"], "1 passenger(s) | Any time");
+
+        let context = NotificationContext {
+            locale: Locale::Ja,
+            ..context
+        };
+        let embed = &DiscordNotifier::new().build_embeds(&many_schedules(1, ""), &[], &context)[0];
+
+        assert_eq!(embed["title"], "🚌 空席があります！");
+        assert!(
+            embed["description"]
+                .as_str()
+                .unwrap()
+                .contains("📍 新宿～富士五湖線 (バスタ新宿（南口） → Kamikochi)")
+        );
+        assert!(
+            embed["fields"][0]["value"]
+                .as_str()
+                .unwrap()
+                .contains("💺 残り3席")
+        );
+    }
+
+    fn many_schedules(count: u32, plan_name: &str) -> Vec<BusSchedule> {
+        (0..count)
+            .map(|i| BusSchedule {
+                bus_number: format!("Bus_{i}"),
+                route_name: String::new(),
+                departure_station: String::new(),
+                departure_date: "20251029".to_string(),
+                departure_time: "22:25".to_string(),
+                arrival_station: String::new(),
+                arrival_date: "20251030".to_string(),
+                arrival_time: "5:20".to_string(),
+                way_no: 0,
+                available_plans: vec![PricingPlan {
+                    plan_id: i,
+                    plan_index: 0,
+                    plan_name: plan_name.to_string(),
+                    price: 12000,
+                    display_price: "12,000円".to_string(),
+                    availability: SeatAvailability::Available {
+                        remaining_seats: Some(3),
+                    },
+                    details: None,
+                }],
+                booking_url: None,
+            })
+            .collect()
+    }
+
+    fn long_context() -> NotificationContext {
+        NotificationContext {
+            departure_station_name: "Shinjuku".to_string(),
+            arrival_station_name: "Kamikochi".to_string(),
+            route_name: String::new(),
+            date_range: ("20251029".to_string(), "20260105".to_string()),
+            passenger_count: 1,
+            time_filter: None,
+            snooze_url: None,
+            availability_url: None,
+            locale: Locale::default(),
+        }
+    }
+
+    #[test]
+    fn test_build_embeds_split_at_field_limit() {
+        let embeds =
+            DiscordNotifier::new().build_embeds(&many_schedules(60, ""), &[], &long_context());
+
+        let field_counts: Vec<_> = embeds
+            .iter()
+            .map(|embed| embed["fields"].as_array().unwrap().len())
+            .collect();
+        assert_eq!(field_counts, vec![25, 25, 10]);
+        assert_eq!(embeds[0]["title"], "🚌 Bus disponibles !");
+        assert!(embeds[1].get("title").is_none());
+        assert!(embeds[0].get("footer").is_none());
+        assert_eq!(embeds[2]["footer"]["text"], "1 passager(s) | Tous horaires");
+    }
+
+    #[test]
+    fn test_build_alert_payloads_stay_under_size_limit() {
+        let schedules = many_schedules(60, &"Plan ".repeat(60));
+        let payloads =
+            DiscordNotifier::new().build_alert_payloads(&schedules, &[], &long_context());
+
+        assert!(payloads.len() > 1);
+        let mut fields = 0;
+        for payload in &payloads {
+            let embeds = payload["embeds"].as_array().unwrap();
+            assert!(embeds.len() <= MAX_EMBEDS);
+            assert!(embeds.iter().map(embed_chars).sum::<usize>() <= MAX_MESSAGE_CHARS);
+            for embed in embeds {
+                for field in embed["fields"].as_array().unwrap() {
+                    assert!(field["name"].as_str().unwrap().chars().count() <= 256);
+                    fields += 1;
+                }
+            }
+        }
+        assert_eq!(fields, 60);
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("Shinjuku", 8), "Shinjuku");
+        assert_eq!(truncate("Shinjuku", 5), "Shin…");
+        assert_eq!(truncate("新宿バスタ", 3), "新宿…");
+    }
+}
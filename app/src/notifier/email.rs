@@ -0,0 +1,718 @@
+use super::templates::messages;
+use super::{
+    NotificationContext, Notifier, QueuedAlert, UpstreamEvent, alert_entries, alert_title,
+    archived_text, digest_heading, digest_lines, digest_locale, footer_text, format_date,
+    plan_features, plan_title, route_text, seats_label, summary_text,
+};
+use crate::availability_diff::AvailabilityChange;
+use crate::error::{Result, ScraperError};
+use crate::types::{BusSchedule, Locale};
+use async_trait::async_trait;
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use std::fmt::Write;
+use tracing::info;
+
+/// How the connection to the SMTP relay is secured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SmtpSecurity {
+    /// Plain connection upgraded with STARTTLS (port 587)
+    #[default]
+    StartTls,
+    /// Implicit TLS (port 465)
+    Tls,
+    /// Unencrypted; only meant for local relays and tests
+    None,
+}
+
+impl SmtpSecurity {
+    pub const fn default_port(self) -> u16 {
+        match self {
+            Self::StartTls => 587,
+            Self::Tls => 465,
+            Self::None => 25,
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "starttls" => Some(Self::StartTls),
+            "tls" => Some(Self::Tls),
+            "none" => Some(Self::None),
+            _ => None,
+        }
+    }
+}
+
+/// SMTP relay used to deliver email notifications
+#[derive(Debug, Clone)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub security: SmtpSecurity,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Sender address, e.g. `Bus Scraper <bus@example.com>`
+    pub from: String,
+}
+
+impl SmtpConfig {
+    /// Read the relay from `SMTP_*` variables.
+    ///
+    /// Returns `None` when `SMTP_HOST` is not set, i.e. email is disabled.
+    pub fn from_env() -> Result<Option<Self>> {
+        let Some(host) = env_non_empty("SMTP_HOST") else {
+            return Ok(None);
+        };
+
+        let security = match env_non_empty("SMTP_SECURITY") {
+            Some(value) => SmtpSecurity::parse(&value)
+                .ok_or_else(|| ScraperError::Config("Invalid SMTP_SECURITY".to_string()))?,
+            None => SmtpSecurity::default(),
+        };
+
+        let port = match env_non_empty("SMTP_PORT") {
+            Some(value) => value
+                .parse::<u16>()
+                .map_err(|_| ScraperError::Config("Invalid SMTP_PORT".to_string()))?,
+            None => security.default_port(),
+        };
+
+        let from = env_non_empty("SMTP_FROM").ok_or_else(|| {
+            ScraperError::Config("SMTP_FROM is required when SMTP_HOST is set".to_string())
+        })?;
+
+        Ok(Some(Self {
+            host,
+            port,
+            security,
+            username: env_non_empty("SMTP_USERNAME"),
+            password: env_non_empty("SMTP_PASSWORD"),
+            from,
+        }))
+    }
+}
+
+fn env_non_empty(key: &str) -> Option<String> {
+    dotenvy::var(key).ok().filter(|s| !s.is_empty())
+}
+
+pub struct EmailNotifier {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl EmailNotifier {
+    pub fn new(config: &SmtpConfig) -> Result<Self> {
+        let builder = match config.security {
+            SmtpSecurity::StartTls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)
+                    .map_err(|e| ScraperError::Config(format!("Invalid SMTP relay: {e}")))?
+            }
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host)
+                .map_err(|e| ScraperError::Config(format!("Invalid SMTP relay: {e}")))?,
+            SmtpSecurity::None => {
+                AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host)
+            }
+        };
+
+        let mut builder = builder.port(config.port);
+        if let Some(username) = &config.username {
+            builder = builder.credentials(Credentials::new(
+                username.clone(),
+                config.password.clone().unwrap_or_default(),
+            ));
+        }
+
+        let from = config
+            .from
+            .parse::<Mailbox>()
+            .map_err(|e| ScraperError::Config(format!("Invalid SMTP_FROM: {e}")))?;
+
+        Ok(Self {
+            transport: builder.build(),
+            from,
+        })
+    }
+
+    fn subject(
+        &self,
+        schedules: &[BusSchedule],
+        changes: &[AvailabilityChange],
+        context: &NotificationContext,
+    ) -> String {
+        format!(
+            "🚌 {} — {}",
+            summary_text(schedules, changes, context.locale),
+            route_text(context)
+        )
+    }
+
+    fn build_text_body(
+        &self,
+        schedules: &[BusSchedule],
+        changes: &[AvailabilityChange],
+        context: &NotificationContext,
+    ) -> String {
+        let locale = context.locale;
+        let m = messages(locale);
+        let mut body = format!(
+            "{}\n\n📍 {}\n📆 {} — {}\n",
+            alert_title(schedules, changes, locale),
+            route_text(context),
+            format_date(&context.date_range.0, locale),
+            format_date(&context.date_range.1, locale)
+        );
+
+        for entry in alert_entries(schedules, changes, locale) {
+            let (schedule, plan) = (entry.schedule, entry.plan);
+
+            let _ = write!(
+                body,
+                "\nBus {} - {}\n",
+                schedule.bus_number,
+                plan_title(plan, locale)
+            );
+            if let Some(change) = &entry.change {
+                let _ = writeln!(body, "  {change}");
+            }
+            let colon = m.colon;
+            let _ = write!(
+                body,
+                "  {}{colon}{}{}{}\n  {}{colon}{}\n  {}{colon}{}\n  {}{colon}{}\n",
+                m.departure,
+                format_date(&schedule.departure_date, locale),
+                m.at,
+                schedule.departure_time,
+                m.arrival,
+                schedule.arrival_time,
+                m.seats,
+                seats_label(&plan.availability, locale),
+                m.price,
+                plan.display_price
+            );
+            if let Some(features) = plan_features(plan, locale) {
+                let _ = writeln!(body, "  {}{colon}{features}", m.features);
+            }
+            if let Some(url) = &schedule.booking_url {
+                let _ = writeln!(body, "  {}{colon}{url}", m.book);
+            }
+        }
+
+        let _ = write!(body, "\n{}\n", footer_text(context));
+        if let Some(url) = &context.availability_url {
+            let _ = writeln!(body, "{}{}{url}", m.availability_label, m.colon);
+        }
+        if let Some(url) = &context.snooze_url {
+            let _ = writeln!(body, "{}{}{url}", m.snooze_label, m.colon);
+        }
+        body
+    }
+
+    fn build_html_body(
+        &self,
+        schedules: &[BusSchedule],
+        changes: &[AvailabilityChange],
+        context: &NotificationContext,
+    ) -> String {
+        let locale = context.locale;
+        let m = messages(locale);
+        let mut rows = String::new();
+        let mut links = String::new();
+        if let Some(url) = &context.availability_url {
+            let _ = write!(
+                links,
+                "<p><a href=\"{}\">{}</a></p>",
+                escape_html(url),
+                m.availability_label
+            );
+        }
+        if let Some(url) = &context.snooze_url {
+            let _ = write!(
+                links,
+                "<p><a href=\"{}\">{}</a></p>",
+                escape_html(url),
+                m.snooze_label
+            );
+        }
+
+        let entries = alert_entries(schedules, changes, locale);
+        let with_changes = entries.iter().any(|entry| entry.change.is_some());
+        for entry in &entries {
+            let (schedule, plan) = (entry.schedule, entry.plan);
+
+            let booking_link = schedule
+                .booking_url
+                .as_ref()
+                .map_or_else(String::new, |url| {
+                    format!("<a href=\"{}\">{}</a>", escape_html(url), m.book)
+                });
+            let plan_cell = match plan_features(plan, locale) {
+                Some(features) => format!(
+                    "{}<br><small>{}</small>",
+                    escape_html(&plan_title(plan, locale)),
+                    escape_html(&features)
+                ),
+                None => escape_html(&plan_title(plan, locale)),
+            };
+            let change_cell = match &entry.change {
+                Some(change) => format!("<td><strong>{}</strong></td>", escape_html(change)),
+                None => String::new(),
+            };
+            let _ = write!(
+                rows,
+                "<tr>{}<td>{}</td><td>{}</td><td>{} {}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                change_cell,
+                escape_html(&schedule.bus_number),
+                plan_cell,
+                format_date(&schedule.departure_date, locale),
+                escape_html(&schedule.departure_time),
+                escape_html(&schedule.arrival_time),
+                escape_html(&seats_label(&plan.availability, locale)),
+                escape_html(&plan.display_price),
+                booking_link
+            );
+        }
+        let change_header = if with_changes {
+            format!("<th>{}</th>", m.change)
+        } else {
+            String::new()
+        };
+
+        format!(
+            "<!DOCTYPE html>\
+             <html><body style=\"font-family: sans-serif;\">\
+             <h2>{}</h2>\
+             <p>📍 {}<br>📆 {} — {}</p>\
+             <table border=\"1\" cellpadding=\"6\" cellspacing=\"0\" style=\"border-collapse: collapse;\">\
+             <thead><tr>{}<th>Bus</th><th>{}</th><th>{}</th><th>{}</th><th>{}</th><th>{}</th><th></th></tr></thead>\
+             <tbody>{}</tbody></table>\
+             <p style=\"color: #666;\">{}</p>{}\
+             </body></html>",
+            alert_title(schedules, changes, locale),
+            escape_html(&route_text(context)),
+            format_date(&context.date_range.0, locale),
+            format_date(&context.date_range.1, locale),
+            change_header,
+            m.plan,
+            m.departure,
+            m.arrival,
+            m.seats,
+            m.price,
+            rows,
+            escape_html(&footer_text(context)),
+            links
+        )
+    }
+
+    /// Plain text and HTML bodies of a digest: one section per alert
+    fn build_digest_bodies(&self, alerts: &[QueuedAlert]) -> (String, String) {
+        let title = messages(digest_locale(alerts)).digest_title;
+        let mut text = format!("{title}\n");
+        let mut html = format!(
+            "<!DOCTYPE html><html><body style=\"font-family: sans-serif;\"><h2>{title}</h2>"
+        );
+
+        for alert in alerts {
+            let heading = digest_heading(&alert.context);
+            let lines = digest_lines(alert);
+
+            let _ = writeln!(text, "\n{heading}");
+            let _ = write!(html, "<h3>{}</h3><ul>", escape_html(&heading));
+            for line in &lines {
+                let _ = writeln!(text, "  • {line}");
+                let _ = write!(html, "<li>{}</li>", escape_html(line));
+            }
+            html.push_str("</ul>");
+
+            if let Some(url) = &alert.context.snooze_url {
+                let m = messages(alert.context.locale);
+                let _ = writeln!(text, "  {}{}{url}", m.snooze_label, m.colon);
+                let _ = write!(
+                    html,
+                    "<p><a href=\"{}\">{}</a></p>",
+                    escape_html(url),
+                    m.snooze_label
+                );
+            }
+        }
+
+        html.push_str("</body></html>");
+        (text, html)
+    }
+
+    async fn send(&self, to: &str, subject: String, text: String, html: String) -> Result<()> {
+        let to = to
+            .parse::<Mailbox>()
+            .map_err(|e| ScraperError::Config(format!("Invalid email address {to}: {e}")))?;
+
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(subject)
+            .multipart(MultiPart::alternative_plain_html(text, html))
+            .map_err(|e| ScraperError::Config(format!("Failed to build email: {e}")))?;
+
+        self.transport
+            .send(message)
+            .await
+            .map_err(|e| ScraperError::InvalidResponse(format!("SMTP delivery failed: {e}")))?;
+
+        info!("Email notification sent successfully");
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Notifier for EmailNotifier {
+    async fn send_startup_notification(
+        &self,
+        address: &str,
+        user_count: usize,
+        route_count: usize,
+        locale: Locale,
+    ) -> Result<()> {
+        let m = messages(locale);
+        let text = format!(
+            "{}\n",
+            (m.startup_text)(&user_count.to_string(), &route_count.to_string())
+        );
+        let html = format!(
+            "<html><body style=\"font-family: sans-serif;\"><h2>{}</h2><p>{}</p></body></html>",
+            m.startup_title,
+            (m.startup_text)(
+                &format!("<b>{user_count}</b>"),
+                &format!("<b>{route_count}</b>")
+            )
+        );
+
+        self.send(address, m.startup_title.to_string(), text, html)
+            .await
+    }
+
+    async fn send_availability_alert(
+        &self,
+        address: &str,
+        schedules: &[BusSchedule],
+        changes: &[AvailabilityChange],
+        context: &NotificationContext,
+    ) -> Result<()> {
+        if schedules.is_empty() && changes.is_empty() {
+            return Ok(());
+        }
+
+        self.send(
+            address,
+            self.subject(schedules, changes, context),
+            self.build_text_body(schedules, changes, context),
+            self.build_html_body(schedules, changes, context),
+        )
+        .await
+    }
+
+    async fn send_upstream_status(
+        &self,
+        address: &str,
+        event: UpstreamEvent,
+        locale: Locale,
+    ) -> Result<()> {
+        let text = format!("{}\n", event.description(locale));
+        let html = format!(
+            "<html><body style=\"font-family: sans-serif;\"><h2>{}</h2><p>{}</p></body></html>",
+            event.title(locale),
+            event.description(locale)
+        );
+
+        self.send(address, event.title(locale).to_string(), text, html)
+            .await
+    }
+
+    async fn send_route_archived(
+        &self,
+        address: &str,
+        context: &NotificationContext,
+    ) -> Result<()> {
+        let title = messages(context.locale).archived_title;
+        let body = archived_text(context);
+        let html = format!(
+            "<html><body style=\"font-family: sans-serif;\"><h2>{title}</h2><p>{}</p></body></html>",
+            escape_html(&body)
+        );
+
+        self.send(address, title.to_string(), format!("{body}\n"), html)
+            .await
+    }
+
+    async fn send_digest(&self, address: &str, alerts: &[QueuedAlert]) -> Result<()> {
+        if alerts.is_empty() {
+            return Ok(());
+        }
+
+        let (text, html) = self.build_digest_bodies(alerts);
+        let title = messages(digest_locale(alerts)).digest_title;
+        self.send(address, title.to_string(), text, html).await
+    }
+}
+
+pub(super) fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::availability_diff::ChangeKind;
+    use crate::types::{PricingPlan, SeatAvailability};
+    use serial_test::serial;
+
+    fn notifier() -> EmailNotifier {
+        EmailNotifier::new(&SmtpConfig {
+            host: "localhost".to_string(),
+            port: 2525,
+            security: SmtpSecurity::None,
+            username: None,
+            password: None,
+            from: "Bus Scraper <bus@example.com>".to_string(),
+        })
+        .unwrap()
+    }
+
+    fn schedule() -> BusSchedule {
+        BusSchedule {
+            bus_number: "Bus_1".to_string(),
+            route_name: String::new(),
+            departure_station: String::new(),
+            departure_date: "20251029".to_string(),
+            departure_time: "22:25".to_string(),
+            arrival_station: String::new(),
+            arrival_date: "20251030".to_string(),
+            arrival_time: "5:20".to_string(),
+            way_no: 0,
+            available_plans: vec![PricingPlan {
+                plan_id: 12345,
+                plan_index: 0,
+                plan_name: String::new(),
+                price: 12000,
+                display_price: "12,000円".to_string(),
+                availability: SeatAvailability::Available {
+                    remaining_seats: Some(3),
+                },
+                details: None,
+            }],
+            booking_url: Some("https://www.highwaybus.com/gp/reservation/rsvPlanList?mode=search&bordingDate=20251029".to_string()),
+        }
+    }
+
+    fn context() -> NotificationContext {
+        NotificationContext {
+            departure_station_name: "Shinjuku".to_string(),
+            arrival_station_name: "Kamikochi <BT>".to_string(),
+            route_name: String::new(),
+            date_range: ("20251029".to_string(), "20251105".to_string()),
+            passenger_count: 2,
+            time_filter: None,
+            snooze_url: None,
+            availability_url: None,
+            locale: Locale::default(),
+        }
+    }
+
+    #[test]
+    fn test_build_text_body() {
+        let body = notifier().build_text_body(&[schedule()], &[], &context());
+
+        assert!(body.contains("📍 Shinjuku → Kamikochi <BT>"));
+        assert!(body.contains("Bus Bus_1 - Plan 12345"));
+        assert!(body.contains("Départ : 29/10/2025 à 22:25"));
+        assert!(body.contains("Places : 3 sièges"));
+        assert!(body.contains("Prix : 12,000円"));
+        assert!(body.contains(
+            "Réserver : https://www.highwaybus.com/gp/reservation/rsvPlanList?mode=search&bordingDate=20251029"
+        ));
+        assert!(body.contains("2 passager(s) | Tous horaires"));
+    }
+
+    #[test]
+    fn test_build_html_body_escapes_values() {
+        let body = notifier().build_html_body(&[schedule()], &[], &context());
+
+        assert!(body.contains("Kamikochi &lt;BT&gt;"));
+        assert!(body.contains("<td>Bus_1</td><td>Plan 12345</td><td>29/10/2025 22:25</td>"));
+        assert!(body.contains("<td>3 sièges</td><td>12,000円</td>"));
+        assert!(body.contains(
+            "<a href=\"https://www.highwaybus.com/gp/reservation/rsvPlanList?mode=search&amp;bordingDate=20251029\">Réserver</a>"
+        ));
+    }
+
+    #[test]
+    fn test_snooze_link_in_both_bodies() {
+        let notifier = notifier();
+        assert!(
+            !notifier
+                .build_text_body(&[schedule()], &[], &context())
+                .contains("Pause")
+        );
+
+        let context = NotificationContext {
+            snooze_url: Some("https://bus.example.com/routes/r1/snooze".to_string()),
+            ..context()
+        };
+
+        assert!(
+            notifier
+                .build_text_body(&[schedule()], &[], &context)
+                .contains("⏸️ Pause 24 h : https://bus.example.com/routes/r1/snooze")
+        );
+        assert!(
+            notifier
+                .build_html_body(&[schedule()], &[], &context)
+                .contains("<a href=\"https://bus.example.com/routes/r1/snooze\">⏸️ Pause 24 h</a>")
+        );
+    }
+
+    #[test]
+    fn test_build_digest_bodies() {
+        let alerts = [
+            QueuedAlert {
+                schedules: vec![schedule()],
+                changes: Vec::new(),
+                context: context(),
+            },
+            QueuedAlert {
+                schedules: vec![schedule()],
+                changes: Vec::new(),
+                context: NotificationContext {
+                    arrival_station_name: "Kawaguchiko".to_string(),
+                    ..context()
+                },
+            },
+        ];
+
+        let (text, html) = notifier().build_digest_bodies(&alerts);
+
+        assert!(text.starts_with(messages(Locale::Fr).digest_title));
+        assert!(text.contains("📍 Shinjuku → Kamikochi <BT> (29/10/2025 — 05/11/2025)"));
+        assert!(text.contains("📍 Shinjuku → Kawaguchiko"));
+        assert!(
+            text.contains("  • 29/10/2025 à 22:25 · Bus Bus_1 - Plan 12345 · 3 sièges · 12,000円")
+        );
+        assert!(
+            html.contains("<h3>📍 Shinjuku → Kamikochi &lt;BT&gt; (29/10/2025 — 05/11/2025)</h3>")
+        );
+        assert_eq!(html.matches("<li>").count(), 2);
+    }
+
+    #[test]
+    fn test_bodies_list_only_changes() {
+        let notifier = notifier();
+        let mut sold_out = schedule();
+        sold_out.departure_time = "23:00".to_string();
+        sold_out.available_plans[0].availability = SeatAvailability::SoldOut;
+        let changes = [AvailabilityChange {
+            schedule: sold_out.clone(),
+            plan: sold_out.available_plans[0].clone(),
+            kind: ChangeKind::SoldOut,
+        }];
+        let context = NotificationContext {
+            availability_url: Some("https://bus.example.com/routes/r1/availability".to_string()),
+            ..context()
+        };
+        let schedules = [schedule(), sold_out];
+
+        let text = notifier.build_text_body(&schedules, &changes, &context);
+        assert!(text.contains("  ⛔ Complet\n  Départ : 29/10/2025 à 23:00"));
+        assert!(!text.contains("à 22:25"));
+        assert!(
+            text.contains("📋 Toutes les places : https://bus.example.com/routes/r1/availability")
+        );
+
+        let html = notifier.build_html_body(&schedules, &changes, &context);
+        assert!(html.contains("<th>Changement</th>"));
+        assert!(html.contains("<tr><td><strong>⛔ Complet</strong></td><td>Bus_1</td>"));
+
+        assert!(
+            notifier
+                .subject(&schedules, &changes, &context)
+                .starts_with("🚌 1 changement(s) · 1 bus avec places disponibles")
+        );
+    }
+
+    #[test]
+    fn test_subject_counts_bookable_schedules() {
+        let mut sold_out = schedule();
+        sold_out.available_plans[0].availability = SeatAvailability::SoldOut;
+
+        let subject = notifier().subject(&[schedule(), sold_out], &[], &context());
+
+        assert!(subject.starts_with("🚌 1 bus avec places disponibles"));
+    }
+
+    #[test]
+    fn test_new_rejects_invalid_from() {
+        let result = EmailNotifier::new(&SmtpConfig {
+            host: "localhost".to_string(),
+            port: 25,
+            security: SmtpSecurity::None,
+            username: None,
+            password: None,
+            from: "not an address".to_string(),
+        });
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    #[serial]
+    fn test_smtp_config_disabled_without_host() {
+        temp_env::with_var_unset("SMTP_HOST", || {
+            assert!(SmtpConfig::from_env().unwrap().is_none());
+        });
+    }
+
+    #[test]
+    #[serial]
+    fn test_smtp_config_from_env() {
+        temp_env::with_vars(
+            [
+                ("SMTP_HOST", Some("smtp.example.com")),
+                ("SMTP_PORT", None),
+                ("SMTP_SECURITY", Some("tls")),
+                ("SMTP_USERNAME", Some("bot")),
+                ("SMTP_PASSWORD", Some("secret")),
+                ("SMTP_FROM", Some("bus@example.com")),
+            ],
+            || {
+                let config = SmtpConfig::from_env().unwrap().unwrap();
+                assert_eq!(config.host, "smtp.example.com");
+                assert_eq!(config.security, SmtpSecurity::Tls);
+                assert_eq!(config.port, 465);
+                assert_eq!(config.username.as_deref(), Some("bot"));
+                assert_eq!(config.from, "bus@example.com");
+            },
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_smtp_config_requires_from() {
+        temp_env::with_vars(
+            [("SMTP_HOST", Some("smtp.example.com")), ("SMTP_FROM", None)],
+            || {
+                assert!(SmtpConfig::from_env().is_err());
+            },
+        );
+    }
+}
@@ -0,0 +1,1178 @@
+//! Notification channels.
+//!
+//! Each channel implements [`Notifier`]; [`NotificationDispatcher`] picks the
+//! implementation matching each configured [`NotificationChannel`].
+
+mod discord;
+mod email;
+mod slack;
+mod templates;
+
+pub use discord::DiscordNotifier;
+pub use email::{EmailNotifier, SmtpConfig, SmtpSecurity};
+pub use slack::SlackNotifier;
+pub use templates::{route_name, station_name};
+
+use crate::availability_diff::{AvailabilityChange, ChangeKind};
+use crate::error::{Result, ScraperError};
+use crate::types::{
+    Amenity, BusSchedule, ChannelKind, Locale, NotificationChannel, PricingPlan, SeatAvailability,
+    SeatType,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use templates::messages;
+use tracing::error;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationContext {
+    pub departure_station_name: String,
+    pub arrival_station_name: String,
+    /// Name of the route in the operator's catalog, empty when it could not be fetched
+    #[serde(default)]
+    pub route_name: String,
+    pub date_range: (String, String),
+    pub passenger_count: u8,
+    pub time_filter: Option<(String, String)>,
+    /// Page pausing the route for [`SNOOZE_HOURS`], when the app has a public URL
+    pub snooze_url: Option<String>,
+    /// Page listing every bus of the last check, when the app has a public URL
+    pub availability_url: Option<String>,
+    /// Language the notification is written in; alerts stored before it existed were French
+    #[serde(default)]
+    pub locale: Locale,
+}
+
+/// An availability alert stored in the outbox until a channel accepts it;
+/// one raised during the user's quiet hours waits for them to end
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedAlert {
+    pub schedules: Vec<BusSchedule>,
+    /// What changed since the previous check; empty when the alert repeats the full list
+    pub changes: Vec<AvailabilityChange>,
+    pub context: NotificationContext,
+}
+
+/// How long the link in availability alerts pauses a route
+pub const SNOOZE_HOURS: i64 = 24;
+
+/// Address of the page snoozing a route, served under `public_url`
+pub fn snooze_url(public_url: &str, user_route_id: Uuid) -> String {
+    format!(
+        "{}/routes/{user_route_id}/snooze",
+        public_url.trim_end_matches('/')
+    )
+}
+
+/// Address of the page listing the last check of a route, served under `public_url`
+pub fn availability_url(public_url: &str, user_route_id: Uuid) -> String {
+    format!(
+        "{}/routes/{user_route_id}/availability",
+        public_url.trim_end_matches('/')
+    )
+}
+
+/// Content of a page behind the links of notifications
+#[derive(Debug, Clone, Copy)]
+pub enum LinkPage<'a> {
+    RouteNotFound,
+    /// Form confirming a pause of [`SNOOZE_HOURS`]
+    SnoozeForm,
+    /// Route paused, until the given time when it is known
+    Snoozed(Option<DateTime<Utc>>),
+    SnoozeFailed,
+    /// Buses of the last check, `None` when the route was never checked
+    Availability(Option<&'a [BusSchedule]>),
+    AvailabilityFailed,
+}
+
+/// Full HTML document of `page`, worded for the route owner's `locale`
+pub fn link_page_html(page: LinkPage<'_>, locale: Locale) -> String {
+    let m = messages(locale);
+    let body = match page {
+        LinkPage::RouteNotFound => format!("<p>{}</p>", m.route_not_found),
+        LinkPage::SnoozeForm => format!(
+            "<p>{}</p><form method=\"post\"><button type=\"submit\">{}</button></form>",
+            (m.snooze_question)(SNOOZE_HOURS),
+            m.snooze_button
+        ),
+        LinkPage::Snoozed(until) => {
+            let until = until.map_or_else(String::new, |until| {
+                format!(
+                    "{}{}{} UTC",
+                    format_date(&until.format("%Y%m%d").to_string(), locale),
+                    m.at,
+                    until.format("%H:%M")
+                )
+            });
+            format!("<p>{}</p>", (m.snoozed_until)(&until))
+        }
+        LinkPage::SnoozeFailed => format!("<p>{}</p>", m.snooze_failed),
+        LinkPage::Availability(Some(schedules)) => availability_table_html(schedules, locale),
+        LinkPage::Availability(None) => format!("<p>{}</p>", m.no_check_recorded),
+        LinkPage::AvailabilityFailed => format!("<p>{}</p>", m.availability_failed),
+    };
+
+    format!(
+        "<!DOCTYPE html><html lang=\"{}\"><head><meta charset=\"utf-8\">\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\
+         <title>Bus Scraper</title></head><body>{body}</body></html>",
+        locale.as_str()
+    )
+}
+
+/// Table of every plan of `schedules`, for the page behind [`availability_url`]
+pub fn availability_table_html(schedules: &[BusSchedule], locale: Locale) -> String {
+    use email::escape_html;
+    use std::fmt::Write;
+
+    let m = messages(locale);
+
+    if schedules.is_empty() {
+        return format!("<p>{}</p>", m.no_buses);
+    }
+
+    let mut rows = String::new();
+    for schedule in schedules {
+        for plan in &schedule.available_plans {
+            let booking_link = schedule
+                .booking_url
+                .as_ref()
+                .filter(|_| plan.availability.is_bookable())
+                .map_or_else(String::new, |url| {
+                    format!("<a href=\"{}\">{}</a>", escape_html(url), m.book)
+                });
+            let _ = write!(
+                rows,
+                "<tr><td>{}</td><td>{}</td><td>{} {}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape_html(&schedule.bus_number),
+                escape_html(&plan_title(plan, locale)),
+                format_date(&schedule.departure_date, locale),
+                escape_html(&schedule.departure_time),
+                escape_html(&schedule.arrival_time),
+                escape_html(&seats_label(&plan.availability, locale)),
+                escape_html(&plan.display_price),
+                booking_link
+            );
+        }
+    }
+
+    format!(
+        "<table border=\"1\" cellpadding=\"6\" cellspacing=\"0\" style=\"border-collapse: collapse;\">\
+         <thead><tr><th>{}</th><th>{}</th><th>{}</th><th>{}</th><th>{}</th><th>{}</th><th></th></tr></thead>\
+         <tbody>{rows}</tbody></table>",
+        m.bus, m.plan, m.departure, m.arrival, m.seats, m.price
+    )
+}
+
+/// Change in reachability of the bus operator's site, reported once per outage
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpstreamEvent {
+    Down,
+    Recovered,
+}
+
+impl UpstreamEvent {
+    pub fn title(self, locale: Locale) -> &'static str {
+        let m = messages(locale);
+        match self {
+            Self::Down => m.upstream_down_title,
+            Self::Recovered => m.upstream_recovered_title,
+        }
+    }
+
+    pub fn description(self, locale: Locale) -> &'static str {
+        let m = messages(locale);
+        match self {
+            Self::Down => m.upstream_down_text,
+            Self::Recovered => m.upstream_recovered_text,
+        }
+    }
+}
+
+/// A delivery that failed after its first `sent` messages were accepted
+#[derive(Debug)]
+pub struct PartialDelivery {
+    pub sent: u32,
+    pub error: ScraperError,
+}
+
+/// A way of reaching a user. `target` is the channel-specific destination
+/// (webhook URL, email address, ...).
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn send_startup_notification(
+        &self,
+        target: &str,
+        user_count: usize,
+        route_count: usize,
+        locale: Locale,
+    ) -> Result<()>;
+
+    /// Report `changes` when there are any, every bookable plan of `schedules` otherwise
+    async fn send_availability_alert(
+        &self,
+        target: &str,
+        schedules: &[BusSchedule],
+        changes: &[AvailabilityChange],
+        context: &NotificationContext,
+    ) -> Result<()>;
+
+    async fn send_upstream_status(
+        &self,
+        target: &str,
+        event: UpstreamEvent,
+        locale: Locale,
+    ) -> Result<()>;
+
+    /// Tell the owner that the dates of a route have passed and it is no longer checked
+    async fn send_route_archived(&self, target: &str, context: &NotificationContext) -> Result<()>;
+
+    /// Deliver the alerts held back during quiet hours, in one message where the channel allows
+    async fn send_digest(&self, target: &str, alerts: &[QueuedAlert]) -> Result<()>;
+
+    /// [`Self::send_availability_alert`], skipping the first `sent` messages
+    /// that an earlier attempt already delivered.
+    ///
+    /// Channels that send an alert as one message keep this default.
+    async fn resume_availability_alert(
+        &self,
+        target: &str,
+        alert: &QueuedAlert,
+        sent: u32,
+    ) -> std::result::Result<(), PartialDelivery> {
+        self.send_availability_alert(target, &alert.schedules, &alert.changes, &alert.context)
+            .await
+            .map_err(|error| PartialDelivery { sent, error })
+    }
+
+    /// [`Self::send_digest`], skipping the first `sent` messages that an
+    /// earlier attempt already delivered.
+    async fn resume_digest(
+        &self,
+        target: &str,
+        alerts: &[QueuedAlert],
+        sent: u32,
+    ) -> std::result::Result<(), PartialDelivery> {
+        self.send_digest(target, alerts)
+            .await
+            .map_err(|error| PartialDelivery { sent, error })
+    }
+}
+
+/// Routes notifications to the [`Notifier`] registered for each channel kind.
+#[derive(Clone)]
+pub struct NotificationDispatcher {
+    notifiers: HashMap<ChannelKind, Arc<dyn Notifier>>,
+}
+
+impl NotificationDispatcher {
+    /// A dispatcher with no channels registered
+    pub fn empty() -> Self {
+        Self {
+            notifiers: HashMap::new(),
+        }
+    }
+
+    /// Webhook-based channels, which need no configuration
+    pub fn new() -> Self {
+        Self::empty()
+            .with(ChannelKind::Discord, DiscordNotifier::new())
+            .with(ChannelKind::Slack, SlackNotifier::new())
+    }
+
+    /// [`Self::new`] plus email when an SMTP relay is configured (see [`SmtpConfig::from_env`])
+    pub fn from_env() -> Result<Self> {
+        let dispatcher = Self::new();
+
+        match SmtpConfig::from_env()? {
+            Some(smtp) => Ok(dispatcher.with(ChannelKind::Email, EmailNotifier::new(&smtp)?)),
+            None => Ok(dispatcher),
+        }
+    }
+
+    /// Register (or replace) the notifier used for `kind`
+    pub fn with(mut self, kind: ChannelKind, notifier: impl Notifier + 'static) -> Self {
+        self.notifiers.insert(kind, Arc::new(notifier));
+        self
+    }
+
+    pub fn notifier(&self, kind: ChannelKind) -> Option<&dyn Notifier> {
+        self.notifiers.get(&kind).map(AsRef::as_ref)
+    }
+
+    /// Send the startup notice to one channel
+    pub async fn send_startup_notification(
+        &self,
+        channel: &NotificationChannel,
+        user_count: usize,
+        route_count: usize,
+        locale: Locale,
+    ) -> Result<()> {
+        let Some(notifier) = self.notifier(channel.kind) else {
+            error!(
+                "No notifier registered for {} channel",
+                channel.kind.label()
+            );
+            return Ok(());
+        };
+
+        notifier
+            .send_startup_notification(&channel.target, user_count, route_count, locale)
+            .await
+    }
+
+    /// Tell one channel that the site went down or came back
+    pub async fn send_upstream_status(
+        &self,
+        channel: &NotificationChannel,
+        event: UpstreamEvent,
+        locale: Locale,
+    ) -> Result<()> {
+        let Some(notifier) = self.notifier(channel.kind) else {
+            error!(
+                "No notifier registered for {} channel",
+                channel.kind.label()
+            );
+            return Ok(());
+        };
+
+        notifier
+            .send_upstream_status(&channel.target, event, locale)
+            .await
+    }
+
+    /// Tell every channel of a route's owner that the route was archived.
+    ///
+    /// A failing channel is logged and does not prevent delivery to the others.
+    pub async fn send_route_archived(
+        &self,
+        channels: &[NotificationChannel],
+        context: &NotificationContext,
+    ) -> Result<()> {
+        for channel in channels {
+            let Some(notifier) = self.notifier(channel.kind) else {
+                error!(
+                    "No notifier registered for {} channel",
+                    channel.kind.label()
+                );
+                continue;
+            };
+
+            if let Err(e) = notifier.send_route_archived(&channel.target, context).await {
+                error!(
+                    "Failed to send {} route archived notice: {}",
+                    channel.kind.label(),
+                    e
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Send an availability alert to every channel.
+    ///
+    /// A failing channel is logged and does not prevent delivery to the others;
+    /// the last failure is then returned so the caller can try again later.
+    pub async fn send_availability_alert(
+        &self,
+        channels: &[NotificationChannel],
+        schedules: &[BusSchedule],
+        changes: &[AvailabilityChange],
+        context: &NotificationContext,
+    ) -> Result<()> {
+        let mut failure = None;
+
+        for channel in channels {
+            let Some(notifier) = self.notifier(channel.kind) else {
+                error!(
+                    "No notifier registered for {} channel",
+                    channel.kind.label()
+                );
+                continue;
+            };
+
+            if let Err(e) = notifier
+                .send_availability_alert(&channel.target, schedules, changes, context)
+                .await
+            {
+                error!(
+                    "Failed to send {} availability alert: {}",
+                    channel.kind.label(),
+                    e
+                );
+                failure = Some(e);
+            }
+        }
+
+        failure.map_or(Ok(()), Err)
+    }
+}
+
+impl Default for NotificationDispatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Format a `YYYYMMDD` date the way `locale` writes dates, leaving other input untouched
+pub(crate) fn format_date(date_yyyymmdd: &str, locale: Locale) -> String {
+    if date_yyyymmdd.len() == 8 {
+        (messages(locale).date)(
+            &date_yyyymmdd[0..4],
+            &date_yyyymmdd[4..6],
+            &date_yyyymmdd[6..8],
+        )
+    } else {
+        date_yyyymmdd.to_string()
+    }
+}
+
+/// Name and stations of a route, e.g. `Shinjuku - Fuji Five Lakes (Shinjuku → Kawaguchiko)`,
+/// or only the stations when the name is unknown
+pub(crate) fn route_text(context: &NotificationContext) -> String {
+    let stations = format!(
+        "{} → {}",
+        station_name(&context.departure_station_name, context.locale),
+        station_name(&context.arrival_station_name, context.locale)
+    );
+    if context.route_name.is_empty() {
+        stations
+    } else {
+        format!(
+            "{} ({stations})",
+            route_name(&context.route_name, context.locale)
+        )
+    }
+}
+
+/// Human-readable seat status of a plan
+pub(crate) fn seats_label(availability: &SeatAvailability, locale: Locale) -> String {
+    let m = messages(locale);
+    match availability {
+        SeatAvailability::Available { remaining_seats } => match remaining_seats {
+            Some(n) => (m.remaining_seats)(*n),
+            None => m.seats_available.to_string(),
+        },
+        SeatAvailability::FewLeft => m.few_left.to_string(),
+        SeatAvailability::SoldOut => m.sold_out.to_string(),
+        SeatAvailability::Waitlist => m.waitlist.to_string(),
+        SeatAvailability::Unknown => m.unknown.to_string(),
+    }
+}
+
+/// Plan name from its details, or its id when the name is unknown
+pub(crate) fn plan_title(plan: &PricingPlan, locale: Locale) -> String {
+    if plan.plan_name.is_empty() {
+        format!("{} {}", messages(locale).plan, plan.plan_id)
+    } else {
+        plan.plan_name.clone()
+    }
+}
+
+/// Seat type, women-only flag and amenities of a plan, if any are known
+pub(crate) fn plan_features(plan: &PricingPlan, locale: Locale) -> Option<String> {
+    let details = plan.details.as_ref()?;
+    let m = messages(locale);
+
+    let seat = match details.seat_type {
+        SeatType::Standard => None,
+        SeatType::ThreeRow => Some(m.three_row),
+        SeatType::Relax => Some(m.relax_seat),
+        SeatType::Wide => Some(m.wide_seat),
+    };
+    let lady_only = details.lady_only.then_some(m.lady_only);
+    let amenities = details.amenities.iter().map(|amenity| match amenity {
+        Amenity::FreeWifi => m.free_wifi,
+        Amenity::PaidWifi => m.paid_wifi,
+        Amenity::Tv => m.tv,
+        Amenity::PowerPlug => m.power_plug,
+        Amenity::Blanket => m.blanket,
+        Amenity::Slippers => m.slippers,
+        Amenity::Pillow => m.pillow,
+        Amenity::Drink => m.drink,
+    });
+
+    let features: Vec<&str> = lady_only.into_iter().chain(seat).chain(amenities).collect();
+    (!features.is_empty()).then(|| features.join(m.list_separator))
+}
+
+/// Body of the notice sent when a route is archived
+pub(crate) fn archived_text(context: &NotificationContext) -> String {
+    (messages(context.locale).archived_text)(
+        &route_text(context),
+        &format_date(&context.date_range.0, context.locale),
+        &format_date(&context.date_range.1, context.locale),
+    )
+}
+
+/// Cut `text` to `max` characters, marking the cut with an ellipsis
+pub(crate) fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+
+    let mut cut: String = text.chars().take(max - 1).collect();
+    cut.push('…');
+    cut
+}
+
+/// Language of a digest, the one its alerts were written in
+pub(crate) fn digest_locale(alerts: &[QueuedAlert]) -> Locale {
+    alerts
+        .first()
+        .map_or_else(Locale::default, |alert| alert.context.locale)
+}
+
+/// Route and dates of one alert in a digest
+pub(crate) fn digest_heading(context: &NotificationContext) -> String {
+    format!(
+        "📍 {} ({} — {})",
+        route_text(context),
+        format_date(&context.date_range.0, context.locale),
+        format_date(&context.date_range.1, context.locale)
+    )
+}
+
+/// A plan shown in an availability alert
+pub(crate) struct AlertEntry<'a> {
+    pub schedule: &'a BusSchedule,
+    pub plan: &'a PricingPlan,
+    /// What changed on the plan, `None` when the alert repeats the full list
+    pub change: Option<String>,
+}
+
+/// Plans of an alert: one per changed plan, or every bookable plan without changes
+pub(crate) fn alert_entries<'a>(
+    schedules: &'a [BusSchedule],
+    changes: &'a [AvailabilityChange],
+    locale: Locale,
+) -> Vec<AlertEntry<'a>> {
+    if changes.is_empty() {
+        return schedules
+            .iter()
+            .flat_map(|schedule| {
+                schedule.bookable_plans().map(move |plan| AlertEntry {
+                    schedule,
+                    plan,
+                    change: None,
+                })
+            })
+            .collect();
+    }
+
+    // Changes of the same plan follow each other and share an entry
+    let mut entries: Vec<AlertEntry<'a>> = Vec::new();
+    for change in changes {
+        let label = change_label(change, locale);
+        match entries.last_mut() {
+            Some(entry)
+                if entry.schedule.departure_date == change.schedule.departure_date
+                    && entry.schedule.departure_time == change.schedule.departure_time
+                    && entry.plan.plan_id == change.plan.plan_id =>
+            {
+                if let Some(text) = &mut entry.change {
+                    text.push_str(" · ");
+                    text.push_str(&label);
+                }
+            }
+            _ => entries.push(AlertEntry {
+                schedule: &change.schedule,
+                plan: &change.plan,
+                change: Some(label),
+            }),
+        }
+    }
+    entries
+}
+
+/// What changed on a plan, e.g. `📉 Places : 5 sièges → 2 sièges`
+pub(crate) fn change_label(change: &AvailabilityChange, locale: Locale) -> String {
+    let m = messages(locale);
+    let now = &change.plan.availability;
+    match &change.kind {
+        ChangeKind::NowAvailable => format!("🆕 {}", m.now_on_sale),
+        ChangeKind::SeatsIncreased { from } => format!(
+            "📈 {}{}{} → {}",
+            m.seats,
+            m.colon,
+            seats_label(from, locale),
+            seats_label(now, locale)
+        ),
+        ChangeKind::SeatsDecreased { from } => format!(
+            "📉 {}{}{} → {}",
+            m.seats,
+            m.colon,
+            seats_label(from, locale),
+            seats_label(now, locale)
+        ),
+        ChangeKind::PriceChanged { from } => format!(
+            "💱 {}{}{} → {}",
+            m.price,
+            m.colon,
+            format_yen(*from),
+            change.plan.display_price
+        ),
+        ChangeKind::SoldOut => format!("⛔ {}", seats_label(now, locale)),
+    }
+}
+
+/// Price in yen the way the operator displays it, e.g. `12,000円`
+pub(crate) fn format_yen(price: u32) -> String {
+    let digits = price.to_string();
+    let mut grouped = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    format!("{grouped}円")
+}
+
+/// Title of an availability alert; an alert can also report that the last seats went
+pub(crate) fn alert_title(
+    schedules: &[BusSchedule],
+    changes: &[AvailabilityChange],
+    locale: Locale,
+) -> &'static str {
+    let m = messages(locale);
+    if !changes.is_empty() && !schedules.iter().any(BusSchedule::has_bookable_plans) {
+        m.sold_out_title
+    } else {
+        m.available_title
+    }
+}
+
+/// Changes and bookable plans counted in the summary of an alert
+pub(crate) fn summary_text(
+    schedules: &[BusSchedule],
+    changes: &[AvailabilityChange],
+    locale: Locale,
+) -> String {
+    let m = messages(locale);
+    let count = schedules.iter().filter(|s| s.has_bookable_plans()).count();
+    if changes.is_empty() {
+        (m.buses_available)(count)
+    } else {
+        format!(
+            "{} · {}",
+            (m.changes)(alert_entries(schedules, changes, locale).len()),
+            (m.buses_available)(count)
+        )
+    }
+}
+
+/// One line per plan of an alert in a digest
+pub(crate) fn digest_lines(alert: &QueuedAlert) -> Vec<String> {
+    let locale = alert.context.locale;
+    let m = messages(locale);
+    alert_entries(&alert.schedules, &alert.changes, locale)
+        .into_iter()
+        .map(|entry| {
+            let line = format!(
+                "{}{}{} · Bus {} - {} · {} · {}",
+                format_date(&entry.schedule.departure_date, locale),
+                m.at,
+                entry.schedule.departure_time,
+                entry.schedule.bus_number,
+                plan_title(entry.plan, locale),
+                seats_label(&entry.plan.availability, locale),
+                entry.plan.display_price
+            );
+            match entry.change {
+                Some(change) => format!("{line} · {change}"),
+                None => line,
+            }
+        })
+        .collect()
+}
+
+/// Passenger count and departure time window, shown at the bottom of alerts
+pub(crate) fn footer_text(context: &NotificationContext) -> String {
+    let m = messages(context.locale);
+    let passengers = (m.passengers)(context.passenger_count);
+    if let Some((min, max)) = &context.time_filter {
+        format!(
+            "{passengers} | {}{}{} - {}",
+            m.departures, m.colon, min, max
+        )
+    } else {
+        format!("{passengers} | {}", m.any_time)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::error::ScraperError;
+    use crate::types::PlanDetails;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingNotifier {
+        targets: Arc<Mutex<Vec<String>>>,
+        /// Alerts to this target fail after being recorded
+        failing_target: Option<String>,
+    }
+
+    #[async_trait]
+    impl Notifier for RecordingNotifier {
+        async fn send_startup_notification(
+            &self,
+            target: &str,
+            _user_count: usize,
+            _route_count: usize,
+            _locale: Locale,
+        ) -> Result<()> {
+            self.targets.lock().unwrap().push(target.to_string());
+            Ok(())
+        }
+
+        async fn send_availability_alert(
+            &self,
+            target: &str,
+            _schedules: &[BusSchedule],
+            _changes: &[AvailabilityChange],
+            _context: &NotificationContext,
+        ) -> Result<()> {
+            self.targets.lock().unwrap().push(target.to_string());
+            if self.failing_target.as_deref() == Some(target) {
+                return Err(ScraperError::InvalidResponse("HTTP 500".to_string()));
+            }
+            Ok(())
+        }
+
+        async fn send_upstream_status(
+            &self,
+            target: &str,
+            _event: UpstreamEvent,
+            _locale: Locale,
+        ) -> Result<()> {
+            self.targets.lock().unwrap().push(target.to_string());
+            Ok(())
+        }
+
+        async fn send_route_archived(
+            &self,
+            target: &str,
+            _context: &NotificationContext,
+        ) -> Result<()> {
+            self.targets.lock().unwrap().push(target.to_string());
+            Ok(())
+        }
+
+        async fn send_digest(&self, target: &str, _alerts: &[QueuedAlert]) -> Result<()> {
+            self.targets.lock().unwrap().push(target.to_string());
+            Ok(())
+        }
+    }
+
+    fn context() -> NotificationContext {
+        NotificationContext {
+            departure_station_name: "Shinjuku".to_string(),
+            arrival_station_name: "Kamikochi".to_string(),
+            route_name: String::new(),
+            date_range: ("20251029".to_string(), "20251105".to_string()),
+            passenger_count: 1,
+            time_filter: None,
+            snooze_url: None,
+            availability_url: None,
+            locale: Locale::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dispatcher_sends_to_every_channel() {
+        let targets = Arc::new(Mutex::new(Vec::new()));
+        let dispatcher = NotificationDispatcher::empty().with(
+            ChannelKind::Discord,
+            RecordingNotifier {
+                targets: Arc::clone(&targets),
+                failing_target: None,
+            },
+        );
+        let channels = vec![
+            NotificationChannel {
+                kind: ChannelKind::Discord,
+                target: "https://a".to_string(),
+            },
+            NotificationChannel {
+                kind: ChannelKind::Discord,
+                target: "https://b".to_string(),
+            },
+        ];
+
+        dispatcher
+            .send_availability_alert(&channels, &[], &[], &context())
+            .await
+            .unwrap();
+
+        assert_eq!(*targets.lock().unwrap(), vec!["https://a", "https://b"]);
+    }
+
+    #[tokio::test]
+    async fn test_dispatcher_reports_failure_after_trying_every_channel() {
+        let targets = Arc::new(Mutex::new(Vec::new()));
+        let dispatcher = NotificationDispatcher::empty().with(
+            ChannelKind::Discord,
+            RecordingNotifier {
+                targets: Arc::clone(&targets),
+                failing_target: Some("https://a".to_string()),
+            },
+        );
+        let channels = vec![
+            NotificationChannel {
+                kind: ChannelKind::Discord,
+                target: "https://a".to_string(),
+            },
+            NotificationChannel {
+                kind: ChannelKind::Discord,
+                target: "https://b".to_string(),
+            },
+        ];
+
+        let result = dispatcher
+            .send_availability_alert(&channels, &[], &[], &context())
+            .await;
+
+        assert!(matches!(result, Err(ScraperError::InvalidResponse(_))));
+        assert_eq!(*targets.lock().unwrap(), vec!["https://a", "https://b"]);
+    }
+
+    #[tokio::test]
+    async fn test_dispatcher_skips_unregistered_kind() {
+        let dispatcher = NotificationDispatcher::empty();
+        let channel = NotificationChannel {
+            kind: ChannelKind::Discord,
+            target: "https://a".to_string(),
+        };
+
+        assert!(dispatcher.notifier(ChannelKind::Discord).is_none());
+        assert!(
+            dispatcher
+                .send_startup_notification(&channel, 1, 1, Locale::Fr)
+                .await
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_plan_title_and_features() {
+        let mut plan = PricingPlan {
+            plan_id: 12345,
+            plan_index: 0,
+            plan_name: String::new(),
+            price: 12000,
+            display_price: "12,000円".to_string(),
+            availability: SeatAvailability::Available {
+                remaining_seats: Some(3),
+            },
+            details: None,
+        };
+
+        assert_eq!(plan_title(&plan, Locale::Fr), "Plan 12345");
+        assert_eq!(plan_title(&plan, Locale::Ja), "プラン 12345");
+        assert_eq!(plan_features(&plan, Locale::Fr), None);
+
+        plan.plan_name = "3列独立シート".to_string();
+        plan.details = Some(PlanDetails {
+            lady_only: true,
+            seat_type: SeatType::ThreeRow,
+            amenities: vec![Amenity::FreeWifi, Amenity::PowerPlug],
+            ..PlanDetails::default()
+        });
+
+        assert_eq!(plan_title(&plan, Locale::Fr), "3列独立シート");
+        assert_eq!(
+            plan_features(&plan, Locale::Fr).as_deref(),
+            Some("Femmes uniquement, 3 rangées, Wi-Fi gratuit, Prise")
+        );
+        assert_eq!(
+            plan_features(&plan, Locale::En).as_deref(),
+            Some("Women only, 3 rows, Free Wi-Fi, Power outlet")
+        );
+        assert_eq!(
+            plan_features(&plan, Locale::Ja).as_deref(),
+            Some("女性専用、3列シート、無料Wi-Fi、コンセント")
+        );
+
+        plan.details = Some(PlanDetails::default());
+        assert_eq!(plan_features(&plan, Locale::Fr), None);
+    }
+
+    #[test]
+    fn test_archived_text() {
+        assert_eq!(
+            archived_text(&context()),
+            "Shinjuku → Kamikochi : les dates du 29/10/2025 au 05/11/2025 sont passées, \
+             la route est archivée et n'est plus vérifiée"
+        );
+        let context = NotificationContext {
+            locale: Locale::En,
+            ..context()
+        };
+        assert_eq!(
+            archived_text(&context),
+            "Shinjuku → Kamikochi: the dates from 2025-10-29 to 2025-11-05 have passed, \
+             the route is archived and no longer checked"
+        );
+    }
+
+    #[test]
+    fn test_headings_show_route_name() {
+        let context = NotificationContext {
+            route_name: "新宿～富士五湖線".to_string(),
+            locale: Locale::En,
+            ..context()
+        };
+        assert_eq!(
+            route_text(&context),
+            "Shinjuku - Fuji Five Lakes (Shinjuku → Kamikochi)"
+        );
+        assert!(
+            archived_text(&context)
+                .starts_with("Shinjuku - Fuji Five Lakes (Shinjuku → Kamikochi):")
+        );
+        assert_eq!(
+            digest_heading(&context),
+            "📍 Shinjuku - Fuji Five Lakes (Shinjuku → Kamikochi) (2025-10-29 — 2025-11-05)"
+        );
+
+        let context = NotificationContext {
+            locale: Locale::Ja,
+            ..context
+        };
+        assert_eq!(
+            route_text(&context),
+            "新宿～富士五湖線 (Shinjuku → Kamikochi)"
+        );
+    }
+
+    #[test]
+    fn test_snooze_url() {
+        let id = Uuid::nil();
+        let expected = "https://bus.example.com/routes/00000000-0000-0000-0000-000000000000/snooze";
+
+        assert_eq!(snooze_url("https://bus.example.com", id), expected);
+        assert_eq!(snooze_url("https://bus.example.com/", id), expected);
+    }
+
+    #[test]
+    fn test_availability_url() {
+        assert_eq!(
+            availability_url("https://bus.example.com/", Uuid::nil()),
+            "https://bus.example.com/routes/00000000-0000-0000-0000-000000000000/availability"
+        );
+    }
+
+    #[test]
+    fn test_availability_table_html() {
+        assert!(availability_table_html(&[], Locale::Fr).starts_with("<p>Aucun bus"));
+
+        let schedule = BusSchedule {
+            bus_number: "Bus_1".to_string(),
+            route_name: String::new(),
+            departure_station: String::new(),
+            departure_date: "20251029".to_string(),
+            departure_time: "22:25".to_string(),
+            arrival_station: String::new(),
+            arrival_date: "20251030".to_string(),
+            arrival_time: "5:20".to_string(),
+            way_no: 0,
+            available_plans: vec![PricingPlan {
+                plan_id: 12345,
+                plan_index: 0,
+                plan_name: String::new(),
+                price: 12000,
+                display_price: "12,000円".to_string(),
+                availability: SeatAvailability::SoldOut,
+                details: None,
+            }],
+            booking_url: Some("https://www.highwaybus.com/?a=1&b=2".to_string()),
+        };
+
+        let html = availability_table_html(&[schedule], Locale::Fr);
+        // Sold-out plans are listed, without a booking link
+        assert!(html.contains(
+            "<tr><td>Bus_1</td><td>Plan 12345</td><td>29/10/2025 22:25</td><td>5:20</td><td>Complet</td><td>12,000円</td><td></td></tr>"
+        ));
+    }
+
+    #[test]
+    fn test_link_page_html_follows_locale() {
+        let page = link_page_html(LinkPage::SnoozeForm, Locale::En);
+        assert!(page.contains("<html lang=\"en\">"));
+        assert!(page.contains("Pause the alerts of this route for 24 h?"));
+
+        let until = DateTime::parse_from_rfc3339("2025-10-29T22:25:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let page = link_page_html(LinkPage::Snoozed(Some(until)), Locale::Ja);
+        assert!(page.contains("<html lang=\"ja\">"));
+        assert!(page.contains("2025/10/29 22:25 UTCまでアラートを停止しました。"));
+
+        let page = link_page_html(LinkPage::Snoozed(Some(until)), Locale::Fr);
+        assert!(page.contains("Alertes suspendues jusqu'au 29/10/2025 à 22:25 UTC."));
+
+        let page = link_page_html(LinkPage::Availability(Some(&[])), Locale::En);
+        assert!(page.contains("<p>No bus found during the last check.</p>"));
+        let page = link_page_html(LinkPage::Availability(None), Locale::Ja);
+        assert!(page.contains("このルートの確認記録はまだありません。"));
+        let page = link_page_html(LinkPage::RouteNotFound, Locale::default());
+        assert!(page.contains("<html lang=\"fr\">"));
+        assert!(page.contains("Trajet introuvable."));
+    }
+
+    #[test]
+    fn test_format_yen() {
+        assert_eq!(format_yen(800), "800円");
+        assert_eq!(format_yen(2100), "2,100円");
+        assert_eq!(format_yen(123_456), "123,456円");
+    }
+
+    #[test]
+    fn test_alert_entries_group_changes_of_a_plan() {
+        let schedule = BusSchedule {
+            bus_number: "Bus_1".to_string(),
+            route_name: String::new(),
+            departure_station: String::new(),
+            departure_date: "20251029".to_string(),
+            departure_time: "22:25".to_string(),
+            arrival_station: String::new(),
+            arrival_date: "20251030".to_string(),
+            arrival_time: "5:20".to_string(),
+            way_no: 0,
+            available_plans: vec![PricingPlan {
+                plan_id: 12345,
+                plan_index: 0,
+                plan_name: String::new(),
+                price: 12000,
+                display_price: "12,000円".to_string(),
+                availability: SeatAvailability::Available {
+                    remaining_seats: Some(2),
+                },
+                details: None,
+            }],
+            booking_url: None,
+        };
+        let other = BusSchedule {
+            departure_time: "23:00".to_string(),
+            ..schedule.clone()
+        };
+        let change = |schedule: &BusSchedule, kind| AvailabilityChange {
+            schedule: schedule.clone(),
+            plan: schedule.available_plans[0].clone(),
+            kind,
+        };
+        let changes = vec![
+            change(
+                &schedule,
+                ChangeKind::SeatsDecreased {
+                    from: SeatAvailability::Available {
+                        remaining_seats: Some(5),
+                    },
+                },
+            ),
+            change(&schedule, ChangeKind::PriceChanged { from: 10000 }),
+            change(&other, ChangeKind::NowAvailable),
+        ];
+        let schedules = [schedule.clone(), other.clone()];
+
+        let entries = alert_entries(&schedules, &changes, Locale::Fr);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries[0].change.as_deref(),
+            Some("📉 Places : 5 sièges → 2 sièges · 💱 Prix : 10,000円 → 12,000円")
+        );
+        assert_eq!(entries[1].change.as_deref(), Some("🆕 Places en vente"));
+        assert_eq!(
+            summary_text(&schedules, &changes, Locale::Fr),
+            "2 changement(s) · 2 bus avec places disponibles"
+        );
+        let entries = alert_entries(&schedules, &changes, Locale::En);
+        assert_eq!(
+            entries[0].change.as_deref(),
+            Some("📉 Seats: 5 seats → 2 seats · 💱 Price: 10,000円 → 12,000円")
+        );
+        assert_eq!(
+            summary_text(&schedules, &changes, Locale::Ja),
+            "2件の変更 · 空席のあるバス 2便"
+        );
+
+        // Without changes every bookable plan is listed
+        let entries = alert_entries(&schedules, &[], Locale::Fr);
+        assert_eq!(entries.len(), 2);
+        assert!(entries.iter().all(|entry| entry.change.is_none()));
+        assert_eq!(
+            summary_text(&schedules, &[], Locale::Fr),
+            "2 bus avec places disponibles"
+        );
+        assert_eq!(
+            alert_title(&schedules, &changes, Locale::Fr),
+            "🚌 Bus disponibles !"
+        );
+        assert_eq!(
+            alert_title(&[], &changes, Locale::Fr),
+            "⛔ Plus de places disponibles"
+        );
+        assert_eq!(alert_title(&[], &[], Locale::Fr), "🚌 Bus disponibles !");
+        assert_eq!(
+            alert_title(&[], &changes, Locale::En),
+            "⛔ No more seats available"
+        );
+    }
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(format_date("20251029", Locale::Fr), "29/10/2025");
+        assert_eq!(format_date("20250101", Locale::Fr), "01/01/2025");
+        assert_eq!(format_date("20251029", Locale::En), "2025-10-29");
+        assert_eq!(format_date("20251029", Locale::Ja), "2025/10/29");
+        assert_eq!(format_date("invalid", Locale::En), "invalid");
+    }
+
+    #[test]
+    fn test_footer_text() {
+        let context = NotificationContext {
+            passenger_count: 2,
+            time_filter: Some(("20:00".to_string(), "23:59".to_string())),
+            locale: Locale::En,
+            ..context()
+        };
+        assert_eq!(
+            footer_text(&context),
+            "2 passenger(s) | Departures: 20:00 - 23:59"
+        );
+        assert_eq!(
+            footer_text(&NotificationContext {
+                time_filter: None,
+                locale: Locale::Ja,
+                ..context
+            }),
+            "乗客2名 | 全時間帯"
+        );
+    }
+
+    #[test]
+    fn test_context_without_locale_defaults_to_french() {
+        let payload = r#"{
+            "departure_station_name": "Shinjuku",
+            "arrival_station_name": "Kamikochi",
+            "date_range": ["20251029", "20251105"],
+            "passenger_count": 1,
+            "time_filter": null
+        }"#;
+
+        let context: NotificationContext = serde_json::from_str(payload).unwrap();
+
+        assert_eq!(context.locale, Locale::Fr);
+        assert_eq!(context.route_name, "");
+    }
+}
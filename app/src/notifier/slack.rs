@@ -0,0 +1,510 @@
+use super::templates::messages;
+use super::{
+    NotificationContext, Notifier, QueuedAlert, UpstreamEvent, alert_entries, alert_title,
+    archived_text, digest_heading, digest_lines, digest_locale, footer_text, format_date,
+    plan_features, plan_title, route_text, seats_label, summary_text, truncate,
+};
+use crate::availability_diff::AvailabilityChange;
+use crate::error::{Result, ScraperError};
+use crate::types::{BusSchedule, Locale};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::json;
+use std::fmt::Write;
+use tracing::info;
+
+/// Slack rejects messages with more than 50 blocks
+const MAX_BLOCKS: usize = 50;
+/// Header, summary, divider, footer and the "more" notice
+const FIXED_BLOCKS: usize = 5;
+/// Slack rejects section text longer than 3000 characters
+const MAX_SECTION_TEXT: usize = 3000;
+
+pub struct SlackNotifier {
+    client: Client,
+}
+
+impl SlackNotifier {
+    pub fn new() -> Self {
+        Self {
+            client: Client::new(),
+        }
+    }
+
+    /// Block Kit message with the same content as the Discord embed:
+    /// one section per plan between a summary and a footer.
+    fn build_blocks(
+        &self,
+        schedules: &[BusSchedule],
+        changes: &[AvailabilityChange],
+        context: &NotificationContext,
+    ) -> serde_json::Value {
+        let locale = context.locale;
+        let m = messages(locale);
+        let mut plan_sections = Vec::new();
+
+        for entry in alert_entries(schedules, changes, locale) {
+            let (schedule, plan) = (entry.schedule, entry.plan);
+
+            let mut text = format!(
+                "*🚌 Bus {} - {}*",
+                schedule.bus_number,
+                escape_mrkdwn(&plan_title(plan, locale))
+            );
+            if let Some(change) = &entry.change {
+                let _ = write!(text, "\n*{}*", escape_mrkdwn(change));
+            }
+            let _ = write!(
+                text,
+                "\n📅 *{}*{}*{}*\n🕐 {}{}{}\n💺 {}\n💰 {}",
+                format_date(&schedule.departure_date, locale),
+                m.at,
+                schedule.departure_time,
+                m.arrival,
+                m.colon,
+                schedule.arrival_time,
+                seats_label(&plan.availability, locale),
+                plan.display_price
+            );
+            if let Some(features) = plan_features(plan, locale) {
+                let _ = write!(text, "\n✨ {features}");
+            }
+            if let Some(url) = &schedule.booking_url {
+                let _ = write!(text, "\n🔗 <{url}|{}>", m.book);
+            }
+
+            plan_sections.push(json!({
+                "type": "section",
+                "text": { "type": "mrkdwn", "text": text }
+            }));
+        }
+
+        let summary_line = summary_text(schedules, changes, locale);
+        let mut summary = format!(
+            "*{}*\n📍 {}\n📆 {} — {}",
+            summary_line,
+            escape_mrkdwn(&route_text(context)),
+            format_date(&context.date_range.0, locale),
+            format_date(&context.date_range.1, locale)
+        );
+        if let Some(url) = &context.availability_url {
+            let _ = write!(summary, "\n<{url}|{}>", m.availability_label);
+        }
+        if let Some(url) = &context.snooze_url {
+            let _ = write!(summary, "\n<{url}|{}>", m.snooze_label);
+        }
+
+        let mut blocks = vec![
+            json!({
+                "type": "header",
+                "text": { "type": "plain_text", "text": alert_title(schedules, changes, locale), "emoji": true }
+            }),
+            json!({
+                "type": "section",
+                "text": { "type": "mrkdwn", "text": summary }
+            }),
+            json!({ "type": "divider" }),
+        ];
+
+        let room = MAX_BLOCKS - FIXED_BLOCKS;
+        let hidden = plan_sections.len().saturating_sub(room);
+        blocks.extend(plan_sections.into_iter().take(room));
+        if hidden > 0 {
+            blocks.push(json!({
+                "type": "section",
+                "text": { "type": "mrkdwn", "text": format!("_{}_", (m.more_plans)(hidden)) }
+            }));
+        }
+
+        blocks.push(json!({
+            "type": "context",
+            "elements": [{ "type": "mrkdwn", "text": footer_text(context) }]
+        }));
+
+        json!({
+            "text": format!("🚌 {summary_line}"),
+            "blocks": blocks
+        })
+    }
+
+    /// Header then the sections of each alert, listing its bookable plans;
+    /// an alert whose sections no longer fit is left for the "more" notice
+    fn build_digest_blocks(&self, alerts: &[QueuedAlert]) -> serde_json::Value {
+        let m = messages(digest_locale(alerts));
+        let mut blocks = vec![json!({
+            "type": "header",
+            "text": { "type": "plain_text", "text": m.digest_title, "emoji": true }
+        })];
+
+        // Header and the "more" notice
+        let room = MAX_BLOCKS - 2;
+        let mut sections = Vec::new();
+        let mut shown = 0;
+        for alert in alerts {
+            let alert_sections = digest_sections(alert);
+            if sections.len() + alert_sections.len() > room {
+                // A lone alert too long for the message still shows its first plans
+                if shown == 0 {
+                    sections.extend(alert_sections.into_iter().take(room));
+                    shown = 1;
+                }
+                break;
+            }
+            sections.extend(alert_sections);
+            shown += 1;
+        }
+
+        blocks.extend(sections.into_iter().map(|text| {
+            json!({
+                "type": "section",
+                "text": { "type": "mrkdwn", "text": text }
+            })
+        }));
+
+        let hidden = alerts.len() - shown;
+        if hidden > 0 {
+            blocks.push(json!({
+                "type": "section",
+                "text": { "type": "mrkdwn", "text": format!("_{}_", (m.more_routes)(hidden)) }
+            }));
+        }
+
+        json!({ "text": m.digest_title, "blocks": blocks })
+    }
+
+    async fn post(&self, webhook_url: &str, payload: &serde_json::Value, what: &str) -> Result<()> {
+        let response = self.client.post(webhook_url).json(payload).send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(ScraperError::InvalidResponse(format!(
+                "Slack webhook returned HTTP {status}"
+            )));
+        }
+
+        info!("Slack {} sent successfully", what);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Notifier for SlackNotifier {
+    async fn send_startup_notification(
+        &self,
+        webhook_url: &str,
+        user_count: usize,
+        route_count: usize,
+        locale: Locale,
+    ) -> Result<()> {
+        let m = messages(locale);
+        let description = (m.startup_text)(&format!("*{user_count}*"), &format!("*{route_count}*"));
+        let payload = json!({
+            "text": m.startup_title,
+            "blocks": [
+                {
+                    "type": "header",
+                    "text": { "type": "plain_text", "text": m.startup_title, "emoji": true }
+                },
+                {
+                    "type": "section",
+                    "text": { "type": "mrkdwn", "text": description }
+                }
+            ]
+        });
+
+        self.post(webhook_url, &payload, "startup notification")
+            .await
+    }
+
+    async fn send_availability_alert(
+        &self,
+        webhook_url: &str,
+        schedules: &[BusSchedule],
+        changes: &[AvailabilityChange],
+        context: &NotificationContext,
+    ) -> Result<()> {
+        if schedules.is_empty() && changes.is_empty() {
+            return Ok(());
+        }
+
+        let payload = self.build_blocks(schedules, changes, context);
+        self.post(webhook_url, &payload, "notification").await
+    }
+
+    async fn send_upstream_status(
+        &self,
+        webhook_url: &str,
+        event: UpstreamEvent,
+        locale: Locale,
+    ) -> Result<()> {
+        let payload = json!({
+            "text": event.title(locale),
+            "blocks": [
+                {
+                    "type": "header",
+                    "text": { "type": "plain_text", "text": event.title(locale), "emoji": true }
+                },
+                {
+                    "type": "section",
+                    "text": { "type": "mrkdwn", "text": event.description(locale) }
+                }
+            ]
+        });
+
+        self.post(webhook_url, &payload, "upstream status notification")
+            .await
+    }
+
+    async fn send_route_archived(
+        &self,
+        webhook_url: &str,
+        context: &NotificationContext,
+    ) -> Result<()> {
+        let title = messages(context.locale).archived_title;
+        let payload = json!({
+            "text": title,
+            "blocks": [
+                {
+                    "type": "header",
+                    "text": { "type": "plain_text", "text": title, "emoji": true }
+                },
+                {
+                    "type": "section",
+                    "text": { "type": "mrkdwn", "text": escape_mrkdwn(&archived_text(context)) }
+                }
+            ]
+        });
+
+        self.post(webhook_url, &payload, "route archived notification")
+            .await
+    }
+
+    async fn send_digest(&self, webhook_url: &str, alerts: &[QueuedAlert]) -> Result<()> {
+        let payload = self.build_digest_blocks(alerts);
+        self.post(webhook_url, &payload, "digest").await
+    }
+}
+
+/// Text of one alert in a digest, split into sections Slack accepts
+fn digest_sections(alert: &QueuedAlert) -> Vec<String> {
+    let mut lines = vec![format!(
+        "*{}*",
+        escape_mrkdwn(&digest_heading(&alert.context))
+    )];
+    lines.extend(
+        digest_lines(alert)
+            .iter()
+            .map(|line| format!("• {}", escape_mrkdwn(line))),
+    );
+    if let Some(url) = &alert.context.snooze_url {
+        let snooze_label = messages(alert.context.locale).snooze_label;
+        lines.push(format!("<{url}|{snooze_label}>"));
+    }
+
+    let mut sections = Vec::new();
+    let mut text = String::new();
+    for line in lines {
+        let line = truncate(&line, MAX_SECTION_TEXT);
+        if !text.is_empty() && text.chars().count() + 1 + line.chars().count() > MAX_SECTION_TEXT {
+            sections.push(std::mem::take(&mut text));
+        }
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        text.push_str(&line);
+    }
+    if !text.is_empty() {
+        sections.push(text);
+    }
+    sections
+}
+
+/// Escape the characters mrkdwn reads as markup, so names show as typed
+fn escape_mrkdwn(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+impl Default for SlackNotifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::types::{PricingPlan, SeatAvailability};
+
+    fn schedule(plan_count: u32) -> BusSchedule {
+        BusSchedule {
+            bus_number: "Bus_1".to_string(),
+            route_name: String::new(),
+            departure_station: String::new(),
+            departure_date: "20251029".to_string(),
+            departure_time: "22:25".to_string(),
+            arrival_station: String::new(),
+            arrival_date: "20251030".to_string(),
+            arrival_time: "5:20".to_string(),
+            way_no: 0,
+            available_plans: (0..plan_count)
+                .map(|i| PricingPlan {
+                    plan_id: 12345 + i,
+                    plan_index: i,
+                    plan_name: String::new(),
+                    price: 12000,
+                    display_price: "12,000円".to_string(),
+                    availability: SeatAvailability::Available {
+                        remaining_seats: Some(3),
+                    },
+                    details: None,
+                })
+                .collect(),
+            booking_url: Some("https://www.highwaybus.com/gp/reservation/rsvPlanList?mode=search&bordingDate=20251029".to_string()),
+        }
+    }
+
+    fn context() -> NotificationContext {
+        NotificationContext {
+            departure_station_name: "Shinjuku".to_string(),
+            arrival_station_name: "Kamikochi".to_string(),
+            route_name: String::new(),
+            date_range: ("20251029".to_string(), "20251105".to_string()),
+            passenger_count: 2,
+            time_filter: Some(("20:00".to_string(), "23:59".to_string())),
+            snooze_url: None,
+            availability_url: None,
+            locale: Locale::default(),
+        }
+    }
+
+    #[test]
+    fn test_build_blocks() {
+        let notifier = SlackNotifier::new();
+        let payload = notifier.build_blocks(&[schedule(1)], &[], &context());
+
+        let blocks = payload["blocks"].as_array().unwrap();
+        assert_eq!(blocks[0]["type"], "header");
+        assert_eq!(blocks[0]["text"]["text"], "🚌 Bus disponibles !");
+
+        let summary = blocks[1]["text"]["text"].as_str().unwrap();
+        assert!(summary.contains("Shinjuku → Kamikochi"));
+        assert!(summary.contains("29/10/2025 — 05/11/2025"));
+
+        let plan = blocks[3]["text"]["text"].as_str().unwrap();
+        assert!(plan.contains("Plan 12345"));
+        assert!(plan.contains("3 sièges"));
+        assert!(plan.contains("12,000円"));
+        assert!(plan.contains("🔗 <https://www.highwaybus.com/gp/reservation/rsvPlanList?mode=search&bordingDate=20251029|Réserver>"));
+
+        let footer = blocks.last().unwrap();
+        assert_eq!(footer["type"], "context");
+        assert_eq!(
+            footer["elements"][0]["text"],
+            "2 passager(s) | Horaires : 20:00 - 23:59"
+        );
+    }
+
+    #[test]
+    fn test_build_digest_blocks() {
+        let notifier = SlackNotifier::new();
+        let alert = QueuedAlert {
+            schedules: vec![schedule(2)],
+            changes: Vec::new(),
+            context: context(),
+        };
+
+        let payload = notifier.build_digest_blocks(&[alert.clone(), alert]);
+
+        let blocks = payload["blocks"].as_array().unwrap();
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0]["text"]["text"], messages(Locale::Fr).digest_title);
+        let text = blocks[1]["text"]["text"].as_str().unwrap();
+        assert!(text.starts_with("*📍 Shinjuku → Kamikochi (29/10/2025 — 05/11/2025)*"));
+        assert!(
+            text.contains("\n• 29/10/2025 à 22:25 · Bus Bus_1 - Plan 12345 · 3 sièges · 12,000円")
+        );
+        assert!(text.contains("Plan 12346"));
+    }
+
+    #[test]
+    fn test_build_blocks_caps_block_count() {
+        let notifier = SlackNotifier::new();
+        let payload = notifier.build_blocks(&[schedule(60)], &[], &context());
+
+        let blocks = payload["blocks"].as_array().unwrap();
+        assert_eq!(blocks.len(), MAX_BLOCKS);
+        let notice = blocks[blocks.len() - 2]["text"]["text"].as_str().unwrap();
+        assert!(notice.contains("15 autre(s)"));
+    }
+
+    #[test]
+    fn test_build_digest_blocks_splits_long_alerts() {
+        let notifier = SlackNotifier::new();
+        let alert = QueuedAlert {
+            schedules: vec![schedule(200)],
+            changes: Vec::new(),
+            context: context(),
+        };
+
+        let payload = notifier.build_digest_blocks(std::slice::from_ref(&alert));
+        let blocks = payload["blocks"].as_array().unwrap();
+        let texts: Vec<&str> = blocks[1..]
+            .iter()
+            .map(|block| block["text"]["text"].as_str().unwrap())
+            .collect();
+        assert!(texts.len() > 1);
+        assert!(
+            texts
+                .iter()
+                .all(|text| text.chars().count() <= MAX_SECTION_TEXT)
+        );
+        assert!(texts[0].starts_with("*📍 Shinjuku → Kamikochi"));
+        assert!(texts.last().unwrap().contains("Plan 12544"));
+
+        // Alerts that no longer fit are counted in the notice
+        let payload = notifier.build_digest_blocks(&vec![alert; 20]);
+        let blocks = payload["blocks"].as_array().unwrap();
+        assert!(blocks.len() <= MAX_BLOCKS);
+        let notice = blocks.last().unwrap()["text"]["text"].as_str().unwrap();
+        assert!(notice.starts_with("_… et "));
+        assert!(notice.contains("autre(s) trajet(s)"));
+    }
+
+    #[test]
+    fn test_names_are_escaped_for_mrkdwn() {
+        let notifier = SlackNotifier::new();
+        let mut schedule = schedule(1);
+        schedule.available_plans[0].plan_name = "<Relax & Wide>".to_string();
+        let context = NotificationContext {
+            route_name: "Fuji <Express>".to_string(),
+            ..context()
+        };
+
+        let payload = notifier.build_blocks(std::slice::from_ref(&schedule), &[], &context);
+        let blocks = payload["blocks"].as_array().unwrap();
+        assert!(
+            blocks[1]["text"]["text"]
+                .as_str()
+                .unwrap()
+                .contains("Fuji &lt;Express&gt;")
+        );
+        assert!(
+            blocks[3]["text"]["text"]
+                .as_str()
+                .unwrap()
+                .contains("&lt;Relax &amp; Wide&gt;")
+        );
+
+        let alert = QueuedAlert {
+            schedules: vec![schedule],
+            changes: Vec::new(),
+            context,
+        };
+        let payload = notifier.build_digest_blocks(&[alert]);
+        let text = payload["blocks"][1]["text"]["text"].as_str().unwrap();
+        assert!(text.contains("Fuji &lt;Express&gt;"));
+        assert!(text.contains("&lt;Relax &amp; Wide&gt;"));
+    }
+}
@@ -0,0 +1,394 @@
+//! Wording of notifications in each [`Locale`].
+//!
+//! Notifiers assemble their messages from a [`Messages`] table, so a new
+//! language only needs a new table here.
+
+use crate::translations::{translate_route_name, translate_station_name};
+use crate::types::Locale;
+
+/// Every piece of text a notification can contain, in one language
+pub(crate) struct Messages {
+    pub startup_title: &'static str,
+    /// Users and routes being monitored, counts already formatted for the channel
+    pub startup_text: fn(&str, &str) -> String,
+    pub upstream_down_title: &'static str,
+    pub upstream_down_text: &'static str,
+    pub upstream_recovered_title: &'static str,
+    pub upstream_recovered_text: &'static str,
+
+    pub available_title: &'static str,
+    /// Title of an alert reporting that the last seats went
+    pub sold_out_title: &'static str,
+    pub buses_available: fn(usize) -> String,
+    pub changes: fn(usize) -> String,
+    /// Plans left out of a message that ran out of room
+    pub more_plans: fn(usize) -> String,
+    /// Routes left out of a digest that ran out of room
+    pub more_routes: fn(usize) -> String,
+    pub snooze_label: &'static str,
+    pub availability_label: &'static str,
+    /// Passenger count in the footer of alerts
+    pub passengers: fn(u8) -> String,
+    /// Label of the departure time window in the footer of alerts
+    pub departures: &'static str,
+    pub any_time: &'static str,
+
+    /// Date from its year, month and day
+    pub date: fn(&str, &str, &str) -> String,
+    /// Between a date and a time, spaces included
+    pub at: &'static str,
+    /// Between a label and its value, spaces included
+    pub colon: &'static str,
+    pub list_separator: &'static str,
+
+    pub departure: &'static str,
+    pub arrival: &'static str,
+    pub seats: &'static str,
+    pub price: &'static str,
+    pub plan: &'static str,
+    pub features: &'static str,
+    pub change: &'static str,
+    pub book: &'static str,
+    pub now_on_sale: &'static str,
+
+    pub remaining_seats: fn(u32) -> String,
+    pub seats_available: &'static str,
+    pub few_left: &'static str,
+    pub sold_out: &'static str,
+    pub waitlist: &'static str,
+    pub unknown: &'static str,
+
+    pub three_row: &'static str,
+    pub relax_seat: &'static str,
+    pub wide_seat: &'static str,
+    pub lady_only: &'static str,
+    pub free_wifi: &'static str,
+    pub paid_wifi: &'static str,
+    pub tv: &'static str,
+    pub power_plug: &'static str,
+    pub blanket: &'static str,
+    pub slippers: &'static str,
+    pub pillow: &'static str,
+    pub drink: &'static str,
+
+    pub archived_title: &'static str,
+    /// Stations of the route, then the first and last date it covered
+    pub archived_text: fn(&str, &str, &str) -> String,
+    pub digest_title: &'static str,
+    /// Shown instead of the list of buses when the last check found none
+    pub no_buses: &'static str,
+
+    /// Header of the bus number column on the availability page
+    pub bus: &'static str,
+    pub route_not_found: &'static str,
+    /// Question of the snooze page, for a pause of that many hours
+    pub snooze_question: fn(i64) -> String,
+    pub snooze_button: &'static str,
+    /// End of the pause, date and time already formatted
+    pub snoozed_until: fn(&str) -> String,
+    pub snooze_failed: &'static str,
+    pub no_check_recorded: &'static str,
+    pub availability_failed: &'static str,
+}
+
+static FR: Messages = Messages {
+    startup_title: "✅ Bot démarré",
+    startup_text: |users, routes| {
+        format!("Monitoring actif pour {users} utilisateur(s) et {routes} route(s)")
+    },
+    upstream_down_title: "⚠️ Site Highway Bus indisponible",
+    upstream_down_text: "Le site ne répond plus, les vérifications sont suspendues jusqu'à son retour",
+    upstream_recovered_title: "✅ Site Highway Bus de retour",
+    upstream_recovered_text: "Le site répond de nouveau, les vérifications ont repris",
+
+    available_title: "🚌 Bus disponibles !",
+    sold_out_title: "⛔ Plus de places disponibles",
+    buses_available: |count| format!("{count} bus avec places disponibles"),
+    changes: |count| format!("{count} changement(s)"),
+    more_plans: |count| format!("… et {count} autre(s) plan(s)"),
+    more_routes: |count| format!("… et {count} autre(s) trajet(s)"),
+    snooze_label: "⏸️ Pause 24 h",
+    availability_label: "📋 Toutes les places",
+    passengers: |count| format!("{count} passager(s)"),
+    departures: "Horaires",
+    any_time: "Tous horaires",
+
+    date: |year, month, day| format!("{day}/{month}/{year}"),
+    at: " à ",
+    colon: " : ",
+    list_separator: ", ",
+
+    departure: "Départ",
+    arrival: "Arrivée",
+    seats: "Places",
+    price: "Prix",
+    plan: "Plan",
+    features: "Équipements",
+    change: "Changement",
+    book: "Réserver",
+    now_on_sale: "Places en vente",
+
+    remaining_seats: |count| format!("{count} sièges"),
+    seats_available: "Places dispo",
+    few_left: "Dernières places",
+    sold_out: "Complet",
+    waitlist: "Liste d'attente",
+    unknown: "Inconnu",
+
+    three_row: "3 rangées",
+    relax_seat: "Siège relax",
+    wide_seat: "Siège large",
+    lady_only: "Femmes uniquement",
+    free_wifi: "Wi-Fi gratuit",
+    paid_wifi: "Wi-Fi payant",
+    tv: "TV",
+    power_plug: "Prise",
+    blanket: "Couverture",
+    slippers: "Chaussons",
+    pillow: "Oreiller",
+    drink: "Boisson",
+
+    archived_title: "🗓️ Recherche terminée",
+    archived_text: |route, start, end| {
+        format!(
+            "{route} : les dates du {start} au {end} sont passées, la route est archivée et n'est plus vérifiée"
+        )
+    },
+    digest_title: "🌙 Alertes pendant les heures calmes",
+    no_buses: "Aucun bus trouvé lors de la dernière vérification.",
+
+    bus: "Bus",
+    route_not_found: "Trajet introuvable.",
+    snooze_question: |hours| format!("Suspendre les alertes de ce trajet pendant {hours} h ?"),
+    snooze_button: "⏸️ Suspendre",
+    snoozed_until: |until| format!("Alertes suspendues jusqu'au {until}."),
+    snooze_failed: "Impossible de suspendre le trajet.",
+    no_check_recorded: "Aucune vérification enregistrée pour ce trajet.",
+    availability_failed: "Impossible de charger les disponibilités.",
+};
+
+static EN: Messages = Messages {
+    startup_title: "✅ Bot started",
+    startup_text: |users, routes| format!("Monitoring {users} user(s) and {routes} route(s)"),
+    upstream_down_title: "⚠️ Highway Bus site unavailable",
+    upstream_down_text: "The site is not responding, checks are paused until it is back",
+    upstream_recovered_title: "✅ Highway Bus site is back",
+    upstream_recovered_text: "The site is responding again, checks have resumed",
+
+    available_title: "🚌 Buses available!",
+    sold_out_title: "⛔ No more seats available",
+    buses_available: |count| format!("{count} bus(es) with seats available"),
+    changes: |count| format!("{count} change(s)"),
+    more_plans: |count| format!("… and {count} more plan(s)"),
+    more_routes: |count| format!("… and {count} more route(s)"),
+    snooze_label: "⏸️ Pause for 24 h",
+    availability_label: "📋 All seats",
+    passengers: |count| format!("{count} passenger(s)"),
+    departures: "Departures",
+    any_time: "Any time",
+
+    date: |year, month, day| format!("{year}-{month}-{day}"),
+    at: " at ",
+    colon: ": ",
+    list_separator: ", ",
+
+    departure: "Departure",
+    arrival: "Arrival",
+    seats: "Seats",
+    price: "Price",
+    plan: "Plan",
+    features: "Amenities",
+    change: "Change",
+    book: "Book",
+    now_on_sale: "Seats on sale",
+
+    remaining_seats: |count| format!("{count} seats"),
+    seats_available: "Seats available",
+    few_left: "Last seats",
+    sold_out: "Sold out",
+    waitlist: "Waitlist",
+    unknown: "Unknown",
+
+    three_row: "3 rows",
+    relax_seat: "Relax seat",
+    wide_seat: "Wide seat",
+    lady_only: "Women only",
+    free_wifi: "Free Wi-Fi",
+    paid_wifi: "Paid Wi-Fi",
+    tv: "TV",
+    power_plug: "Power outlet",
+    blanket: "Blanket",
+    slippers: "Slippers",
+    pillow: "Pillow",
+    drink: "Drink",
+
+    archived_title: "🗓️ Search ended",
+    archived_text: |route, start, end| {
+        format!(
+            "{route}: the dates from {start} to {end} have passed, the route is archived and no longer checked"
+        )
+    },
+    digest_title: "🌙 Alerts during quiet hours",
+    no_buses: "No bus found during the last check.",
+
+    bus: "Bus",
+    route_not_found: "Route not found.",
+    snooze_question: |hours| format!("Pause the alerts of this route for {hours} h?"),
+    snooze_button: "⏸️ Pause",
+    snoozed_until: |until| format!("Alerts paused until {until}."),
+    snooze_failed: "Could not pause the route.",
+    no_check_recorded: "No check recorded for this route yet.",
+    availability_failed: "Could not load the availability.",
+};
+
+static JA: Messages = Messages {
+    startup_title: "✅ Bot起動",
+    startup_text: |users, routes| format!("{users}人のユーザー、{routes}件のルートを監視中"),
+    upstream_down_title: "⚠️ Highway Busサイトに接続できません",
+    upstream_down_text: "サイトが応答しないため、復旧まで確認を停止しています",
+    upstream_recovered_title: "✅ Highway Busサイトが復旧しました",
+    upstream_recovered_text: "サイトが再び応答したため、確認を再開しました",
+
+    available_title: "🚌 空席があります！",
+    sold_out_title: "⛔ 空席がなくなりました",
+    buses_available: |count| format!("空席のあるバス {count}便"),
+    changes: |count| format!("{count}件の変更"),
+    more_plans: |count| format!("… ほか{count}件のプラン"),
+    more_routes: |count| format!("… ほか{count}件のルート"),
+    snooze_label: "⏸️ 24時間停止",
+    availability_label: "📋 すべての空席",
+    passengers: |count| format!("乗客{count}名"),
+    departures: "出発時刻",
+    any_time: "全時間帯",
+
+    date: |year, month, day| format!("{year}/{month}/{day}"),
+    at: " ",
+    colon: "：",
+    list_separator: "、",
+
+    departure: "出発",
+    arrival: "到着",
+    seats: "空席",
+    price: "料金",
+    plan: "プラン",
+    features: "設備",
+    change: "変更",
+    book: "予約する",
+    now_on_sale: "販売開始",
+
+    remaining_seats: |count| format!("残り{count}席"),
+    seats_available: "空席あり",
+    few_left: "残りわずか",
+    sold_out: "満席",
+    waitlist: "キャンセル待ち",
+    unknown: "不明",
+
+    three_row: "3列シート",
+    relax_seat: "リラックスシート",
+    wide_seat: "ワイドシート",
+    lady_only: "女性専用",
+    free_wifi: "無料Wi-Fi",
+    paid_wifi: "有料Wi-Fi",
+    tv: "テレビ",
+    power_plug: "コンセント",
+    blanket: "ブランケット",
+    slippers: "スリッパ",
+    pillow: "枕",
+    drink: "ドリンク",
+
+    archived_title: "🗓️ 検索終了",
+    archived_text: |route, start, end| {
+        format!(
+            "{route}：{start}〜{end}の日程が過ぎたため、ルートをアーカイブしました。今後は確認しません"
+        )
+    },
+    digest_title: "🌙 通知停止時間中のアラート",
+    no_buses: "前回の確認ではバスが見つかりませんでした。",
+
+    bus: "便",
+    route_not_found: "ルートが見つかりません。",
+    snooze_question: |hours| format!("このルートのアラートを{hours}時間停止しますか？"),
+    snooze_button: "⏸️ 停止する",
+    snoozed_until: |until| format!("{until}までアラートを停止しました。"),
+    snooze_failed: "ルートを停止できませんでした。",
+    no_check_recorded: "このルートの確認記録はまだありません。",
+    availability_failed: "空席情報を読み込めませんでした。",
+};
+
+/// Wording of notifications in `locale`
+pub(crate) fn messages(locale: Locale) -> &'static Messages {
+    match locale {
+        Locale::Fr => &FR,
+        Locale::En => &EN,
+        Locale::Ja => &JA,
+    }
+}
+
+/// Name of a station as the operator's site spells it, translated unless
+/// notifications are in Japanese
+pub fn station_name(japanese: &str, locale: Locale) -> String {
+    match locale {
+        Locale::Ja => japanese.to_string(),
+        Locale::Fr | Locale::En => translate_station_name(japanese),
+    }
+}
+
+/// Name of a route as the operator's catalog spells it, translated unless
+/// notifications are in Japanese
+pub fn route_name(japanese: &str, locale: Locale) -> String {
+    match locale {
+        Locale::Ja => japanese.to_string(),
+        Locale::Fr | Locale::En => translate_route_name(japanese),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_station_name() {
+        assert_eq!(
+            station_name("バスタ新宿（南口）", Locale::En),
+            "Shinjuku Expressway Bus Terminal (South Exit)"
+        );
+        assert_eq!(
+            station_name("バスタ新宿（南口）", Locale::Fr),
+            "Shinjuku Expressway Bus Terminal (South Exit)"
+        );
+        assert_eq!(
+            station_name("バスタ新宿（南口）", Locale::Ja),
+            "バスタ新宿（南口）"
+        );
+        assert_eq!(station_name("Station 001", Locale::En), "Station 001");
+    }
+
+    #[test]
+    fn test_route_name() {
+        assert_eq!(
+            route_name("新宿～富士五湖線", Locale::En),
+            "Shinjuku - Fuji Five Lakes"
+        );
+        assert_eq!(
+            route_name("新宿～富士五湖線", Locale::Fr),
+            "Shinjuku - Fuji Five Lakes"
+        );
+        assert_eq!(
+            route_name("新宿～富士五湖線", Locale::Ja),
+            "新宿～富士五湖線"
+        );
+        assert_eq!(route_name("未知の路線", Locale::En), "未知の路線");
+    }
+
+    #[test]
+    fn test_messages_per_locale() {
+        assert_eq!(messages(Locale::Fr).available_title, "🚌 Bus disponibles !");
+        assert_eq!(messages(Locale::En).available_title, "🚌 Buses available!");
+        assert_eq!(messages(Locale::Ja).available_title, "🚌 空席があります！");
+        assert_eq!(
+            (messages(Locale::En).startup_text)("2", "5"),
+            "Monitoring 2 user(s) and 5 route(s)"
+        );
+        assert_eq!((messages(Locale::Ja).remaining_seats)(3), "残り3席");
+    }
+}
@@ -0,0 +1,172 @@
+//! Request throttling for the bus operator's site.
+//!
+//! One [`RateLimiter`] lives in each [`BusScraper`](crate::scraper::BusScraper),
+//! so every tracker sharing the scraper also shares its limits.
+
+use std::time::Duration;
+use tokio::sync::{Mutex, Semaphore, SemaphorePermit};
+use tokio::time::Instant;
+
+const DEFAULT_MAX_CONCURRENT: usize = 4;
+const DEFAULT_MIN_INTERVAL_MS: u64 = 250;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitConfig {
+    /// Requests allowed in flight at once
+    pub max_concurrent: usize,
+    /// Minimum time between the start of two requests
+    pub min_interval: Duration,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: DEFAULT_MAX_CONCURRENT,
+            min_interval: Duration::from_millis(DEFAULT_MIN_INTERVAL_MS),
+        }
+    }
+}
+
+impl RateLimitConfig {
+    /// Read `SCRAPER_MAX_CONCURRENT` and `SCRAPER_MIN_INTERVAL_MS`, falling back to the defaults
+    pub fn from_env() -> Self {
+        let default = Self::default();
+
+        Self {
+            max_concurrent: dotenvy::var("SCRAPER_MAX_CONCURRENT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(default.max_concurrent),
+            min_interval: dotenvy::var("SCRAPER_MIN_INTERVAL_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map_or(default.min_interval, Duration::from_millis),
+        }
+    }
+}
+
+pub struct RateLimiter {
+    permits: Semaphore,
+    max_concurrent: usize,
+    min_interval: Duration,
+    /// Earliest start time for the next request
+    next_slot: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        let max_concurrent = config.max_concurrent.max(1);
+
+        Self {
+            permits: Semaphore::new(max_concurrent),
+            max_concurrent,
+            min_interval: config.min_interval,
+            next_slot: Mutex::new(Instant::now()),
+        }
+    }
+
+    pub const fn max_concurrent(&self) -> usize {
+        self.max_concurrent
+    }
+
+    /// Wait for a free slot; the request may start once this returns.
+    ///
+    /// Hold the permit until the response has been read.
+    pub async fn acquire(&self) -> Option<SemaphorePermit<'_>> {
+        // The semaphore is never closed, so this only ever yields a permit
+        let permit = self.permits.acquire().await.ok();
+
+        let wait = {
+            let mut next_slot = self.next_slot.lock().await;
+            let now = Instant::now();
+            let slot = (*next_slot).max(now);
+            *next_slot = slot + self.min_interval;
+            slot - now
+        };
+
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+
+        permit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    #[tokio::test]
+    async fn test_acquire_limits_concurrency() {
+        let limiter = RateLimiter::new(RateLimitConfig {
+            max_concurrent: 2,
+            min_interval: Duration::ZERO,
+        });
+
+        let first = limiter.acquire().await;
+        let _second = limiter.acquire().await;
+
+        let third = tokio::time::timeout(Duration::from_millis(50), limiter.acquire()).await;
+        assert!(third.is_err());
+
+        drop(first);
+        let third = tokio::time::timeout(Duration::from_millis(50), limiter.acquire()).await;
+        assert!(third.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_acquire_spaces_requests() {
+        let limiter = RateLimiter::new(RateLimitConfig {
+            max_concurrent: 4,
+            min_interval: Duration::from_millis(40),
+        });
+
+        let start = Instant::now();
+        for _ in 0..3 {
+            drop(limiter.acquire().await);
+        }
+
+        // First request starts at once, the other two wait one interval each
+        assert!(start.elapsed() >= Duration::from_millis(80));
+    }
+
+    #[test]
+    fn test_zero_concurrency_is_clamped() {
+        let limiter = RateLimiter::new(RateLimitConfig {
+            max_concurrent: 0,
+            min_interval: Duration::ZERO,
+        });
+        assert_eq!(limiter.max_concurrent(), 1);
+    }
+
+    #[test]
+    #[serial]
+    fn test_config_from_env() {
+        temp_env::with_vars(
+            [
+                ("SCRAPER_MAX_CONCURRENT", Some("8")),
+                ("SCRAPER_MIN_INTERVAL_MS", Some("100")),
+            ],
+            || {
+                let config = RateLimitConfig::from_env();
+                assert_eq!(config.max_concurrent, 8);
+                assert_eq!(config.min_interval, Duration::from_millis(100));
+            },
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_config_from_env_invalid_uses_defaults() {
+        temp_env::with_vars(
+            [
+                ("SCRAPER_MAX_CONCURRENT", Some("lots")),
+                ("SCRAPER_MIN_INTERVAL_MS", None),
+            ],
+            || {
+                assert_eq!(RateLimitConfig::from_env(), RateLimitConfig::default());
+            },
+        );
+    }
+}
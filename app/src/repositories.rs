@@ -1,28 +1,63 @@
-use crate::entities::{prelude::*, route_states, user_routes, users};
+use crate::entities::{
+    availability_snapshots, notification_channels, notification_outbox, prelude::*, route_states,
+    user_routes, users,
+};
 use crate::error::{Result, ScraperError};
-use chrono::Utc;
-use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use crate::notifier::QueuedAlert;
+use crate::types::{
+    BusSchedule, ChannelKind, DateRange, DateWindow, DeliveryStatus, Locale, NotificationChannel,
+    PlanFilter, QuietHours, RouteStatus, SeatType,
+};
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
 use uuid::Uuid;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct UserRouteWithDetails {
     pub user_route_id: Uuid,
     pub email: String,
     pub notify_on_change_only: bool,
+    /// The route's own interval when it has one, the user's otherwise
     pub scrape_interval_secs: i64,
-    pub discord_webhook_url: Option<String>,
+    /// Poll faster as the next departure approaches
+    pub adaptive_interval: bool,
+    /// Also alert when seats seen on sale are no longer available
+    pub notify_on_sold_out: bool,
+    /// When alerts of the user are held back for a digest
+    pub quiet_hours: Option<QuietHours>,
+    /// Language of the user's notifications
+    pub locale: Locale,
+    pub notification_channels: Vec<NotificationChannel>,
     pub area_id: i32,
     pub route_id: String,
     pub departure_station: String,
     pub arrival_station: String,
     pub date_start: String,
     pub date_end: String,
+    /// Rolling window checked instead of `date_start`..`date_end`
+    pub date_window: Option<DateWindow>,
     pub departure_time_min: Option<String>,
     pub departure_time_max: Option<String>,
+    pub plan_filter: PlanFilter,
     pub passengers: PassengerDetails,
 }
 
-#[derive(Debug, Clone)]
+impl UserRouteWithDetails {
+    pub fn date_range(&self) -> DateRange {
+        DateRange {
+            start: self.date_start.clone(),
+            end: self.date_end.clone(),
+            window: self.date_window.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PassengerDetails {
     pub adult_men: i16,
     pub adult_women: i16,
@@ -50,6 +85,57 @@ impl PassengerDetails {
 #[derive(Debug, Clone)]
 pub struct RouteStateDetails {
     pub last_seen_hash: String,
+    /// Schedules seen by the last check, `None` before the first one stored
+    pub last_snapshot: Option<Vec<BusSchedule>>,
+}
+
+/// An alert in the outbox, due for delivery to one channel
+#[derive(Debug, Clone)]
+pub struct PendingDelivery {
+    pub id: Uuid,
+    pub user_route_id: Uuid,
+    pub channel: NotificationChannel,
+    pub alert: QueuedAlert,
+    /// Attempts already made
+    pub attempts: u32,
+    /// Held back during quiet hours, to be sent in a digest
+    pub digest: bool,
+    /// Messages of a split alert accepted by earlier attempts
+    pub messages_sent: u32,
+}
+
+/// Read the plan filter stored in the columns of a route
+pub fn plan_filter_from_route(route: &user_routes::Model) -> PlanFilter {
+    PlanFilter {
+        max_price: route.max_price.and_then(|p| u32::try_from(p).ok()),
+        seat_type: route.seat_type.as_deref().and_then(SeatType::parse),
+        plan_ids_allow: route
+            .plan_ids_allow
+            .as_deref()
+            .and_then(|ids| PlanFilter::parse_plan_ids(ids).ok())
+            .unwrap_or_default(),
+        plan_ids_deny: route
+            .plan_ids_deny
+            .as_deref()
+            .and_then(|ids| PlanFilter::parse_plan_ids(ids).ok())
+            .unwrap_or_default(),
+        min_remaining_seats: route
+            .min_remaining_seats
+            .and_then(|n| u32::try_from(n).ok()),
+    }
+}
+
+/// Store `filter` in the plan filter columns of a route, using `NULL` for unset values
+pub fn set_plan_filter(route: &mut user_routes::ActiveModel, filter: &PlanFilter) {
+    let plan_ids = |ids: &[u32]| (!ids.is_empty()).then(|| PlanFilter::format_plan_ids(ids));
+
+    route.max_price = Set(filter.max_price.and_then(|p| i32::try_from(p).ok()));
+    route.seat_type = Set(filter.seat_type.map(|s| s.as_str().to_string()));
+    route.plan_ids_allow = Set(plan_ids(&filter.plan_ids_allow));
+    route.plan_ids_deny = Set(plan_ids(&filter.plan_ids_deny));
+    route.min_remaining_seats = Set(filter
+        .min_remaining_seats
+        .and_then(|n| i32::try_from(n).ok()));
 }
 
 pub async fn get_all_active_user_routes(
@@ -64,8 +150,19 @@ pub async fn get_all_active_user_routes(
     let mut result = Vec::new();
 
     for user in users_list {
+        let channels = get_enabled_notification_channels(db, user.id).await?;
+        let quiet_hours = user_quiet_hours(&user);
+        let locale = Locale::parse(&user.locale).unwrap_or_default();
+
         let routes = UserRoutes::find()
             .filter(user_routes::Column::UserId.eq(user.id))
+            .filter(user_routes::Column::Status.eq(RouteStatus::Active.as_str()))
+            .filter(user_routes::Column::Enabled.eq(true))
+            .filter(
+                Condition::any()
+                    .add(user_routes::Column::PausedUntil.is_null())
+                    .add(user_routes::Column::PausedUntil.lte(Utc::now())),
+            )
             .all(db)
             .await
             .map_err(|e| ScraperError::Config(format!("Failed to fetch user routes: {e}")))?;
@@ -79,20 +176,30 @@ pub async fn get_all_active_user_routes(
                     ScraperError::Config(format!("No passengers found for route {}", route.id))
                 })?;
 
+            let plan_filter = plan_filter_from_route(&route);
+
             result.push(UserRouteWithDetails {
                 user_route_id: route.id,
                 email: user.email.clone(),
                 notify_on_change_only: user.notify_on_change_only,
-                scrape_interval_secs: user.scrape_interval_secs,
-                discord_webhook_url: user.discord_webhook_url.clone(),
+                scrape_interval_secs: route
+                    .scrape_interval_secs
+                    .unwrap_or(user.scrape_interval_secs),
+                adaptive_interval: route.adaptive_interval,
+                notify_on_sold_out: route.notify_on_sold_out,
+                quiet_hours: quiet_hours.clone(),
+                locale,
+                notification_channels: channels.clone(),
                 area_id: route.area_id,
                 route_id: route.route_id,
                 departure_station: route.departure_station,
                 arrival_station: route.arrival_station,
                 date_start: route.date_start,
                 date_end: route.date_end,
+                date_window: route.date_window.as_deref().and_then(DateWindow::parse),
                 departure_time_min: route.departure_time_min,
                 departure_time_max: route.departure_time_max,
+                plan_filter,
                 passengers: PassengerDetails {
                     adult_men: passengers.adult_men,
                     adult_women: passengers.adult_women,
@@ -110,6 +217,75 @@ pub async fn get_all_active_user_routes(
     Ok(result)
 }
 
+/// Quiet hours configured by a user, if both times are set
+pub fn user_quiet_hours(user: &users::Model) -> Option<QuietHours> {
+    QuietHours::from_parts(
+        user.quiet_hours_start.as_deref(),
+        user.quiet_hours_end.as_deref(),
+        user.quiet_hours_tz.as_deref(),
+    )
+}
+
+/// Move routes to [`RouteStatus::Archived`] so no tracker picks them up again
+pub async fn archive_user_routes(db: &DatabaseConnection, ids: &[Uuid]) -> Result<()> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+
+    UserRoutes::update_many()
+        .col_expr(
+            user_routes::Column::Status,
+            RouteStatus::Archived.as_str().into(),
+        )
+        .filter(user_routes::Column::Id.is_in(ids.iter().copied()))
+        .exec(db)
+        .await
+        .map_err(|e| ScraperError::Config(format!("Failed to archive user routes: {e}")))?;
+
+    Ok(())
+}
+
+/// Enabled channels of a user, skipping rows whose kind this build does not know.
+pub async fn get_enabled_notification_channels(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+) -> Result<Vec<NotificationChannel>> {
+    let channels = NotificationChannels::find()
+        .filter(notification_channels::Column::UserId.eq(user_id))
+        .filter(notification_channels::Column::Enabled.eq(true))
+        .order_by_asc(notification_channels::Column::CreatedAt)
+        .all(db)
+        .await
+        .map_err(|e| ScraperError::Config(format!("Failed to fetch notification channels: {e}")))?;
+
+    Ok(channels
+        .into_iter()
+        .filter_map(|c| {
+            ChannelKind::parse(&c.kind).map(|kind| NotificationChannel {
+                kind,
+                target: c.target,
+            })
+        })
+        .collect())
+}
+
+/// Locale of the user owning a route, `None` when the route does not exist.
+pub async fn get_route_owner_locale(
+    db: &DatabaseConnection,
+    user_route_id: Uuid,
+) -> Result<Option<Locale>> {
+    let owner = UserRoutes::find_by_id(user_route_id)
+        .find_also_related(Users)
+        .one(db)
+        .await
+        .map_err(|e| ScraperError::Config(format!("Failed to fetch route owner: {e}")))?;
+
+    Ok(owner.map(|(_, user)| {
+        user.and_then(|user| Locale::parse(&user.locale))
+            .unwrap_or_default()
+    }))
+}
+
 pub async fn get_route_state(
     db: &DatabaseConnection,
     user_route_id: Uuid,
@@ -121,15 +297,36 @@ pub async fn get_route_state(
 
     Ok(state.map(|s| RouteStateDetails {
         last_seen_hash: s.last_seen_hash,
+        // An unreadable snapshot only costs one diff against nothing
+        last_snapshot: s
+            .last_snapshot
+            .and_then(|json| serde_json::from_str(&json).ok()),
     }))
 }
 
+/// Store the hash and schedules of a check, counting the check and, when
+/// `increment_alerts` is set, the alert it sent.
 pub async fn update_route_state(
     db: &DatabaseConnection,
     user_route_id: Uuid,
     hash: String,
+    snapshot: &[BusSchedule],
+    increment_alerts: bool,
+) -> Result<()> {
+    write_route_state(db, user_route_id, hash, snapshot, increment_alerts).await
+}
+
+/// [`update_route_state`] on any connection, so it can run inside a transaction
+async fn write_route_state<C: ConnectionTrait>(
+    db: &C,
+    user_route_id: Uuid,
+    hash: String,
+    snapshot: &[BusSchedule],
     increment_alerts: bool,
 ) -> Result<()> {
+    let snapshot = serde_json::to_string(snapshot)
+        .map_err(|e| ScraperError::Config(format!("Failed to serialize route snapshot: {e}")))?;
+
     let existing = RouteStates::find_by_id(user_route_id)
         .one(db)
         .await
@@ -138,6 +335,7 @@ pub async fn update_route_state(
     if let Some(state) = existing {
         let mut active_model: route_states::ActiveModel = state.into();
         active_model.last_seen_hash = Set(hash);
+        active_model.last_snapshot = Set(Some(snapshot));
         active_model.last_check = Set(Some(Utc::now()));
         active_model.total_checks = Set(active_model.total_checks.unwrap() + 1);
         if increment_alerts {
@@ -154,6 +352,7 @@ pub async fn update_route_state(
             last_check: Set(Some(Utc::now())),
             total_checks: Set(1),
             total_alerts: Set(if increment_alerts { 1 } else { 0 }),
+            last_snapshot: Set(Some(snapshot)),
         };
         new_state
             .insert(db)
@@ -164,11 +363,308 @@ pub async fn update_route_state(
     Ok(())
 }
 
+/// Store the state of a check that raised an alert and queue the alert for
+/// every channel, in one transaction.
+///
+/// The state only moves on once the alert is stored, so a channel being down
+/// delays the alert instead of losing it. An alert raised during quiet hours
+/// is held until `held_until` and then sent in a digest.
+#[allow(clippy::too_many_arguments)]
+pub async fn record_alert(
+    db: &DatabaseConnection,
+    user_route_id: Uuid,
+    hash: String,
+    snapshot: &[BusSchedule],
+    channels: &[NotificationChannel],
+    alert: &QueuedAlert,
+    held_until: Option<DateTime<Utc>>,
+) -> Result<()> {
+    let payload = serde_json::to_string(alert)
+        .map_err(|e| ScraperError::Config(format!("Failed to serialize alert: {e}")))?;
+    let now = Utc::now();
+
+    let txn = db
+        .begin()
+        .await
+        .map_err(|e| ScraperError::Config(format!("Failed to start transaction: {e}")))?;
+
+    write_route_state(&txn, user_route_id, hash, snapshot, true).await?;
+
+    let rows: Vec<notification_outbox::ActiveModel> = channels
+        .iter()
+        .map(|channel| notification_outbox::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_route_id: Set(user_route_id),
+            channel_kind: Set(channel.kind.as_str().to_string()),
+            target: Set(channel.target.clone()),
+            payload: Set(payload.clone()),
+            status: Set(DeliveryStatus::Pending.as_str().to_string()),
+            attempts: Set(0),
+            next_attempt_at: Set(held_until.unwrap_or(now)),
+            last_error: Set(None),
+            created_at: Set(now),
+            delivered_at: Set(None),
+            digest: Set(held_until.is_some()),
+            messages_sent: Set(0),
+        })
+        .collect();
+    if !rows.is_empty() {
+        NotificationOutbox::insert_many(rows)
+            .exec(&txn)
+            .await
+            .map_err(|e| ScraperError::Config(format!("Failed to queue alert: {e}")))?;
+    }
+
+    txn.commit()
+        .await
+        .map_err(|e| ScraperError::Config(format!("Failed to commit alert: {e}")))
+}
+
+/// Up to `limit` pending deliveries whose next attempt is due at `now`, oldest first.
+///
+/// Rows this build cannot read (unknown channel kind, unparseable alert) are
+/// marked failed rather than returned.
+pub async fn get_due_deliveries(
+    db: &DatabaseConnection,
+    now: DateTime<Utc>,
+    limit: u64,
+) -> Result<Vec<PendingDelivery>> {
+    let rows = NotificationOutbox::find()
+        .filter(notification_outbox::Column::Status.eq(DeliveryStatus::Pending.as_str()))
+        .filter(notification_outbox::Column::NextAttemptAt.lte(now))
+        .order_by_asc(notification_outbox::Column::NextAttemptAt)
+        .order_by_asc(notification_outbox::Column::CreatedAt)
+        .limit(limit)
+        .all(db)
+        .await
+        .map_err(|e| ScraperError::Config(format!("Failed to fetch due deliveries: {e}")))?;
+
+    let mut due = Vec::with_capacity(rows.len());
+    for row in rows {
+        let attempts = u32::try_from(row.attempts).unwrap_or(0);
+        let messages_sent = u32::try_from(row.messages_sent).unwrap_or(0);
+        let Some(kind) = ChannelKind::parse(&row.channel_kind) else {
+            let error = format!("Unknown channel kind {}", row.channel_kind);
+            mark_delivery_failed(db, row.id, attempts, messages_sent, &error, None).await?;
+            continue;
+        };
+        let alert = match serde_json::from_str(&row.payload) {
+            Ok(alert) => alert,
+            Err(e) => {
+                let error = format!("Unreadable alert: {e}");
+                mark_delivery_failed(db, row.id, attempts, messages_sent, &error, None).await?;
+                continue;
+            }
+        };
+
+        due.push(PendingDelivery {
+            id: row.id,
+            user_route_id: row.user_route_id,
+            channel: NotificationChannel {
+                kind,
+                target: row.target,
+            },
+            alert,
+            attempts,
+            digest: row.digest,
+            messages_sent,
+        });
+    }
+
+    Ok(due)
+}
+
+/// Record that a channel accepted an alert after `attempts` attempts in total
+pub async fn mark_delivery_sent(db: &DatabaseConnection, id: Uuid, attempts: u32) -> Result<()> {
+    NotificationOutbox::update_many()
+        .col_expr(
+            notification_outbox::Column::Status,
+            DeliveryStatus::Sent.as_str().into(),
+        )
+        .col_expr(
+            notification_outbox::Column::Attempts,
+            i32::try_from(attempts).unwrap_or(i32::MAX).into(),
+        )
+        .col_expr(notification_outbox::Column::DeliveredAt, Utc::now().into())
+        .filter(notification_outbox::Column::Id.eq(id))
+        .exec(db)
+        .await
+        .map_err(|e| ScraperError::Config(format!("Failed to mark delivery sent: {e}")))?;
+
+    Ok(())
+}
+
+/// Record a failed attempt, `attempts` being the total so far and
+/// `messages_sent` the messages of the alert delivered before the failure:
+/// the alert is retried from there at `retry_at`, or given up on when there is none.
+#[allow(clippy::too_many_arguments)]
+pub async fn mark_delivery_failed(
+    db: &DatabaseConnection,
+    id: Uuid,
+    attempts: u32,
+    messages_sent: u32,
+    error: &str,
+    retry_at: Option<DateTime<Utc>>,
+) -> Result<()> {
+    let mut update = NotificationOutbox::update_many()
+        .col_expr(
+            notification_outbox::Column::Attempts,
+            i32::try_from(attempts).unwrap_or(i32::MAX).into(),
+        )
+        .col_expr(
+            notification_outbox::Column::MessagesSent,
+            i32::try_from(messages_sent).unwrap_or(i32::MAX).into(),
+        )
+        .col_expr(notification_outbox::Column::LastError, error.into());
+    update = match retry_at {
+        Some(at) => update.col_expr(notification_outbox::Column::NextAttemptAt, at.into()),
+        None => update.col_expr(
+            notification_outbox::Column::Status,
+            DeliveryStatus::Failed.as_str().into(),
+        ),
+    };
+
+    update
+        .filter(notification_outbox::Column::Id.eq(id))
+        .exec(db)
+        .await
+        .map_err(|e| ScraperError::Config(format!("Failed to record delivery failure: {e}")))?;
+
+    Ok(())
+}
+
+/// Record the plans of a check whose seats, status or price changed since
+/// the last row of that plan, so a steady route adds nothing to the history.
+///
+/// A row holds from its `checked_at` until the next row of the same plan.
+pub async fn record_availability_snapshots(
+    db: &DatabaseConnection,
+    user_route_id: Uuid,
+    checked_at: DateTime<Utc>,
+    schedules: &[BusSchedule],
+) -> Result<()> {
+    if schedules.is_empty() {
+        return Ok(());
+    }
+
+    let dates: HashSet<&str> = schedules
+        .iter()
+        .map(|s| s.departure_date.as_str())
+        .collect();
+    let previous = AvailabilitySnapshots::find()
+        .filter(availability_snapshots::Column::UserRouteId.eq(user_route_id))
+        .filter(availability_snapshots::Column::DepartureDate.is_in(dates))
+        .order_by_asc(availability_snapshots::Column::CheckedAt)
+        .all(db)
+        .await
+        .map_err(|e| ScraperError::Config(format!("Failed to fetch availability history: {e}")))?;
+    // Later rows overwrite earlier ones, leaving the last known state of each plan
+    let last_known: HashMap<_, _> = previous
+        .into_iter()
+        .map(|row| {
+            (
+                (row.departure_date, row.departure_time, row.plan_id),
+                (row.status, row.remaining_seats, row.price),
+            )
+        })
+        .collect();
+
+    let rows: Vec<availability_snapshots::ActiveModel> = schedules
+        .iter()
+        .flat_map(|schedule| {
+            schedule.available_plans.iter().map(move |plan| {
+                let key = (
+                    schedule.departure_date.clone(),
+                    schedule.departure_time.clone(),
+                    i64::from(plan.plan_id),
+                );
+                let state = (
+                    plan.availability.status().to_string(),
+                    plan.availability
+                        .remaining_seats()
+                        .and_then(|n| i32::try_from(n).ok()),
+                    i64::from(plan.price),
+                );
+                (key, state)
+            })
+        })
+        .filter(|(key, state)| last_known.get(key) != Some(state))
+        .map(
+            |((departure_date, departure_time, plan_id), (status, remaining_seats, price))| {
+                availability_snapshots::ActiveModel {
+                    id: Set(Uuid::new_v4()),
+                    user_route_id: Set(user_route_id),
+                    checked_at: Set(checked_at),
+                    departure_date: Set(departure_date),
+                    departure_time: Set(departure_time),
+                    plan_id: Set(plan_id),
+                    status: Set(status),
+                    remaining_seats: Set(remaining_seats),
+                    price: Set(price),
+                }
+            },
+        )
+        .collect();
+
+    if rows.is_empty() {
+        return Ok(());
+    }
+
+    AvailabilitySnapshots::insert_many(rows)
+        .exec(db)
+        .await
+        .map_err(|e| {
+            ScraperError::Config(format!("Failed to insert availability snapshots: {e}"))
+        })?;
+
+    Ok(())
+}
+
+/// History rows for a route checked within `checked`, oldest first.
+pub async fn get_availability_history(
+    db: &DatabaseConnection,
+    user_route_id: Uuid,
+    checked: Range<DateTime<Utc>>,
+) -> Result<Vec<availability_snapshots::Model>> {
+    AvailabilitySnapshots::find()
+        .filter(availability_snapshots::Column::UserRouteId.eq(user_route_id))
+        .filter(availability_snapshots::Column::CheckedAt.gte(checked.start))
+        .filter(availability_snapshots::Column::CheckedAt.lt(checked.end))
+        .order_by_asc(availability_snapshots::Column::CheckedAt)
+        .order_by_asc(availability_snapshots::Column::DepartureDate)
+        .order_by_asc(availability_snapshots::Column::DepartureTime)
+        .all(db)
+        .await
+        .map_err(|e| ScraperError::Config(format!("Failed to fetch availability history: {e}")))
+}
+
+/// History rows for a single departure (e.g. the 22:25 bus on a given date)
+/// checked within `checked`, oldest first.
+pub async fn get_departure_history(
+    db: &DatabaseConnection,
+    user_route_id: Uuid,
+    departure_date: &str,
+    departure_time: &str,
+    checked: Range<DateTime<Utc>>,
+) -> Result<Vec<availability_snapshots::Model>> {
+    AvailabilitySnapshots::find()
+        .filter(availability_snapshots::Column::UserRouteId.eq(user_route_id))
+        .filter(availability_snapshots::Column::DepartureDate.eq(departure_date))
+        .filter(availability_snapshots::Column::DepartureTime.eq(departure_time))
+        .filter(availability_snapshots::Column::CheckedAt.gte(checked.start))
+        .filter(availability_snapshots::Column::CheckedAt.lt(checked.end))
+        .order_by_asc(availability_snapshots::Column::CheckedAt)
+        .all(db)
+        .await
+        .map_err(|e| ScraperError::Config(format!("Failed to fetch departure history: {e}")))
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
     use crate::db::init_database;
+    use crate::types::{PricingPlan, SeatAvailability};
     use migration::{Migrator, MigratorTrait};
 
     async fn setup_test_db() -> DatabaseConnection {
@@ -200,8 +696,11 @@ mod tests {
             enabled: Set(true),
             notify_on_change_only: Set(true),
             scrape_interval_secs: Set(300),
-            discord_webhook_url: Set(None),
             created_at: Set(chrono::Utc::now()),
+            quiet_hours_start: Set(None),
+            quiet_hours_end: Set(None),
+            quiet_hours_tz: Set(None),
+            locale: Set("fr".to_string()),
         };
         user.insert(&db).await.unwrap();
 
@@ -217,6 +716,18 @@ mod tests {
             departure_time_min: Set(None),
             departure_time_max: Set(None),
             created_at: Set(chrono::Utc::now()),
+            max_price: Set(None),
+            seat_type: Set(None),
+            plan_ids_allow: Set(None),
+            plan_ids_deny: Set(None),
+            min_remaining_seats: Set(None),
+            date_window: Set(None),
+            status: Set("active".to_string()),
+            enabled: Set(true),
+            paused_until: Set(None),
+            scrape_interval_secs: Set(None),
+            adaptive_interval: Set(false),
+            notify_on_sold_out: Set(false),
         };
         route.insert(&db).await.unwrap();
 
@@ -236,19 +747,680 @@ mod tests {
         let state = get_route_state(&db, route_id).await.unwrap();
         assert!(state.is_none());
 
-        update_route_state(&db, route_id, "hash1".to_string(), false)
+        update_route_state(&db, route_id, "hash1".to_string(), &[], false)
             .await
             .unwrap();
 
         let state = get_route_state(&db, route_id).await.unwrap();
         assert!(state.is_some());
         assert_eq!(state.as_ref().unwrap().last_seen_hash, "hash1");
+        assert_eq!(
+            state
+                .as_ref()
+                .unwrap()
+                .last_snapshot
+                .as_deref()
+                .map(<[_]>::len),
+            Some(0)
+        );
 
-        update_route_state(&db, route_id, "hash2".to_string(), true)
+        let schedule = BusSchedule {
+            bus_number: "Bus_1".to_string(),
+            route_name: "Test Route".to_string(),
+            departure_station: "001".to_string(),
+            departure_date: "20251012".to_string(),
+            departure_time: "08:30".to_string(),
+            arrival_station: "498".to_string(),
+            arrival_date: "20251012".to_string(),
+            arrival_time: "10:00".to_string(),
+            way_no: 1,
+            available_plans: vec![crate::types::PricingPlan {
+                plan_id: 12345,
+                plan_index: 0,
+                plan_name: "Standard".to_string(),
+                price: 2100,
+                display_price: "2,100円".to_string(),
+                availability: crate::types::SeatAvailability::FewLeft,
+                details: None,
+            }],
+            booking_url: None,
+        };
+        update_route_state(&db, route_id, "hash2".to_string(), &[schedule], true)
             .await
             .unwrap();
 
-        let state = get_route_state(&db, route_id).await.unwrap();
-        assert_eq!(state.as_ref().unwrap().last_seen_hash, "hash2");
+        let state = get_route_state(&db, route_id).await.unwrap().unwrap();
+        assert_eq!(state.last_seen_hash, "hash2");
+        let snapshot = state.last_snapshot.unwrap();
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(
+            snapshot[0].available_plans[0].availability,
+            crate::types::SeatAvailability::FewLeft
+        );
+    }
+
+    #[tokio::test]
+    async fn test_archived_routes_are_not_active() {
+        use crate::entities::{user_passengers, user_routes, users};
+        use sea_orm::{ActiveModelTrait, Set};
+
+        let db = setup_test_db().await;
+
+        let user_id = Uuid::new_v4();
+        let route_id = Uuid::new_v4();
+
+        users::ActiveModel {
+            id: Set(user_id),
+            email: Set("archive@test.com".to_string()),
+            enabled: Set(true),
+            notify_on_change_only: Set(true),
+            scrape_interval_secs: Set(300),
+            created_at: Set(chrono::Utc::now()),
+            quiet_hours_start: Set(None),
+            quiet_hours_end: Set(None),
+            quiet_hours_tz: Set(None),
+            locale: Set("fr".to_string()),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        user_routes::ActiveModel {
+            id: Set(route_id),
+            user_id: Set(user_id),
+            area_id: Set(1),
+            route_id: Set("155".to_string()),
+            departure_station: Set("001".to_string()),
+            arrival_station: Set("498".to_string()),
+            date_start: Set("2025-10-12".to_string()),
+            date_end: Set("2025-10-19".to_string()),
+            created_at: Set(chrono::Utc::now()),
+            ..Default::default()
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        user_passengers::ActiveModel {
+            user_route_id: Set(route_id),
+            adult_men: Set(1),
+            adult_women: Set(0),
+            child_men: Set(0),
+            child_women: Set(0),
+            handicap_adult_men: Set(0),
+            handicap_adult_women: Set(0),
+            handicap_child_men: Set(0),
+            handicap_child_women: Set(0),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        // New routes are active through the column default
+        assert_eq!(get_all_active_user_routes(&db).await.unwrap().len(), 1);
+
+        archive_user_routes(&db, &[]).await.unwrap();
+        archive_user_routes(&db, &[route_id]).await.unwrap();
+
+        assert!(get_all_active_user_routes(&db).await.unwrap().is_empty());
+        let stored = UserRoutes::find_by_id(route_id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.status, RouteStatus::Archived.as_str());
+    }
+
+    #[tokio::test]
+    async fn test_disabled_and_paused_routes_are_not_active() {
+        use crate::entities::{user_passengers, user_routes, users};
+        use sea_orm::{ActiveModelTrait, Set};
+
+        let db = setup_test_db().await;
+
+        let user_id = Uuid::new_v4();
+        let route_id = Uuid::new_v4();
+
+        users::ActiveModel {
+            id: Set(user_id),
+            email: Set("pause@test.com".to_string()),
+            enabled: Set(true),
+            notify_on_change_only: Set(true),
+            scrape_interval_secs: Set(300),
+            created_at: Set(chrono::Utc::now()),
+            quiet_hours_start: Set(None),
+            quiet_hours_end: Set(None),
+            quiet_hours_tz: Set(None),
+            locale: Set("fr".to_string()),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        let route = user_routes::ActiveModel {
+            id: Set(route_id),
+            user_id: Set(user_id),
+            area_id: Set(1),
+            route_id: Set("155".to_string()),
+            departure_station: Set("001".to_string()),
+            arrival_station: Set("498".to_string()),
+            date_start: Set("2025-10-12".to_string()),
+            date_end: Set("2025-10-19".to_string()),
+            created_at: Set(chrono::Utc::now()),
+            ..Default::default()
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        user_passengers::ActiveModel {
+            user_route_id: Set(route_id),
+            adult_men: Set(1),
+            adult_women: Set(0),
+            child_men: Set(0),
+            child_women: Set(0),
+            handicap_adult_men: Set(0),
+            handicap_adult_women: Set(0),
+            handicap_child_men: Set(0),
+            handicap_child_women: Set(0),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        // New routes are enabled and not paused through the column defaults
+        assert!(route.enabled);
+        assert_eq!(route.paused_until, None);
+        assert_eq!(get_all_active_user_routes(&db).await.unwrap().len(), 1);
+
+        let mut disabled: user_routes::ActiveModel = route.clone().into();
+        disabled.enabled = Set(false);
+        let route = disabled.update(&db).await.unwrap();
+        assert!(get_all_active_user_routes(&db).await.unwrap().is_empty());
+
+        let mut paused: user_routes::ActiveModel = route.into();
+        paused.enabled = Set(true);
+        paused.paused_until = Set(Some(Utc::now() + chrono::Duration::hours(1)));
+        let route = paused.update(&db).await.unwrap();
+        assert!(get_all_active_user_routes(&db).await.unwrap().is_empty());
+
+        // A pause that has run out no longer hides the route
+        let mut expired: user_routes::ActiveModel = route.into();
+        expired.paused_until = Set(Some(Utc::now() - chrono::Duration::minutes(1)));
+        expired.update(&db).await.unwrap();
+        assert_eq!(get_all_active_user_routes(&db).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_enabled_notification_channels() {
+        use crate::entities::{notification_channels, users};
+
+        let db = setup_test_db().await;
+        let user_id = Uuid::new_v4();
+
+        users::ActiveModel {
+            id: Set(user_id),
+            email: Set("channels@test.com".to_string()),
+            enabled: Set(true),
+            notify_on_change_only: Set(true),
+            scrape_interval_secs: Set(300),
+            created_at: Set(Utc::now()),
+            quiet_hours_start: Set(None),
+            quiet_hours_end: Set(None),
+            quiet_hours_tz: Set(None),
+            locale: Set("fr".to_string()),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        for (kind, target, enabled) in [
+            ("discord", "https://discord.test/on", true),
+            ("discord", "https://discord.test/off", false),
+            ("carrier_pigeon", "loft 3", true),
+        ] {
+            notification_channels::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                user_id: Set(user_id),
+                kind: Set(kind.to_string()),
+                target: Set(target.to_string()),
+                enabled: Set(enabled),
+                created_at: Set(Utc::now()),
+            }
+            .insert(&db)
+            .await
+            .unwrap();
+        }
+
+        let channels = get_enabled_notification_channels(&db, user_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            channels,
+            vec![NotificationChannel {
+                kind: ChannelKind::Discord,
+                target: "https://discord.test/on".to_string(),
+            }]
+        );
+    }
+
+    fn snapshot_schedule(departure_time: &str, availability: SeatAvailability) -> BusSchedule {
+        BusSchedule {
+            bus_number: "Bus_1".to_string(),
+            route_name: String::new(),
+            departure_station: String::new(),
+            departure_date: "20251015".to_string(),
+            departure_time: departure_time.to_string(),
+            arrival_station: String::new(),
+            arrival_date: String::new(),
+            arrival_time: "06:00".to_string(),
+            way_no: 0,
+            available_plans: vec![PricingPlan {
+                plan_id: 42,
+                plan_index: 0,
+                plan_name: String::new(),
+                price: 9800,
+                display_price: "9800円".to_string(),
+                availability,
+                details: None,
+            }],
+            booking_url: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_availability_history_time_ranges() {
+        use crate::entities::{user_routes, users};
+        use chrono::Duration;
+
+        let db = setup_test_db().await;
+        let user_id = Uuid::new_v4();
+        let route_id = Uuid::new_v4();
+
+        users::ActiveModel {
+            id: Set(user_id),
+            email: Set("history@test.com".to_string()),
+            enabled: Set(true),
+            notify_on_change_only: Set(true),
+            scrape_interval_secs: Set(300),
+            created_at: Set(Utc::now()),
+            quiet_hours_start: Set(None),
+            quiet_hours_end: Set(None),
+            quiet_hours_tz: Set(None),
+            locale: Set("fr".to_string()),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        user_routes::ActiveModel {
+            id: Set(route_id),
+            user_id: Set(user_id),
+            area_id: Set(1),
+            route_id: Set("155".to_string()),
+            departure_station: Set("001".to_string()),
+            arrival_station: Set("498".to_string()),
+            date_start: Set("2025-10-15".to_string()),
+            date_end: Set("2025-10-15".to_string()),
+            departure_time_min: Set(None),
+            departure_time_max: Set(None),
+            created_at: Set(Utc::now()),
+            max_price: Set(None),
+            seat_type: Set(None),
+            plan_ids_allow: Set(None),
+            plan_ids_deny: Set(None),
+            min_remaining_seats: Set(None),
+            date_window: Set(None),
+            status: Set("active".to_string()),
+            enabled: Set(true),
+            paused_until: Set(None),
+            scrape_interval_secs: Set(None),
+            adaptive_interval: Set(false),
+            notify_on_sold_out: Set(false),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        let first_check = Utc::now() - Duration::days(7);
+        let second_check = Utc::now() - Duration::hours(1);
+
+        record_availability_snapshots(
+            &db,
+            route_id,
+            first_check,
+            &[
+                snapshot_schedule("22:25", SeatAvailability::SoldOut),
+                snapshot_schedule("23:10", SeatAvailability::SoldOut),
+            ],
+        )
+        .await
+        .unwrap();
+        record_availability_snapshots(
+            &db,
+            route_id,
+            second_check,
+            &[snapshot_schedule(
+                "22:25",
+                SeatAvailability::Available {
+                    remaining_seats: Some(2),
+                },
+            )],
+        )
+        .await
+        .unwrap();
+
+        let all = get_availability_history(
+            &db,
+            route_id,
+            first_check - Duration::minutes(1)..Utc::now(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].status, "sold_out");
+        assert_eq!(all[2].status, "available");
+        assert_eq!(all[2].remaining_seats, Some(2));
+        assert_eq!(all[2].price, 9800);
+
+        let recent =
+            get_availability_history(&db, route_id, Utc::now() - Duration::days(1)..Utc::now())
+                .await
+                .unwrap();
+        assert_eq!(recent.len(), 1);
+
+        let departure = get_departure_history(
+            &db,
+            route_id,
+            "20251015",
+            "22:25",
+            first_check - Duration::minutes(1)..Utc::now(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(departure.len(), 2);
+        assert_eq!(departure[0].status, "sold_out");
+        assert_eq!(departure[1].status, "available");
+
+        // Checks that see nothing new add no rows; the next change does
+        let unchanged = [
+            snapshot_schedule(
+                "22:25",
+                SeatAvailability::Available {
+                    remaining_seats: Some(2),
+                },
+            ),
+            snapshot_schedule("23:10", SeatAvailability::SoldOut),
+        ];
+        for _ in 0..3 {
+            record_availability_snapshots(&db, route_id, Utc::now(), &unchanged)
+                .await
+                .unwrap();
+        }
+        let later = Utc::now() + Duration::seconds(1);
+        record_availability_snapshots(
+            &db,
+            route_id,
+            later,
+            &[snapshot_schedule("22:25", SeatAvailability::SoldOut)],
+        )
+        .await
+        .unwrap();
+
+        let all = get_availability_history(
+            &db,
+            route_id,
+            first_check - Duration::minutes(1)..later + Duration::seconds(1),
+        )
+        .await
+        .unwrap();
+        assert_eq!(all.len(), 4);
+        assert_eq!(all[3].departure_time, "22:25");
+        assert_eq!(all[3].status, "sold_out");
+    }
+
+    #[tokio::test]
+    async fn test_record_availability_snapshots_empty_is_noop() {
+        let db = setup_test_db().await;
+
+        record_availability_snapshots(&db, Uuid::new_v4(), Utc::now(), &[])
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_outbox_delivery_lifecycle() {
+        use crate::entities::{user_routes, users};
+        use crate::notifier::NotificationContext;
+        use chrono::Duration;
+
+        let db = setup_test_db().await;
+        let user_id = Uuid::new_v4();
+        let route_id = Uuid::new_v4();
+
+        users::ActiveModel {
+            id: Set(user_id),
+            email: Set("outbox@test.com".to_string()),
+            enabled: Set(true),
+            notify_on_change_only: Set(true),
+            scrape_interval_secs: Set(300),
+            created_at: Set(Utc::now()),
+            quiet_hours_start: Set(None),
+            quiet_hours_end: Set(None),
+            quiet_hours_tz: Set(None),
+            locale: Set("fr".to_string()),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        user_routes::ActiveModel {
+            id: Set(route_id),
+            user_id: Set(user_id),
+            area_id: Set(1),
+            route_id: Set("155".to_string()),
+            departure_station: Set("001".to_string()),
+            arrival_station: Set("498".to_string()),
+            date_start: Set("2025-10-15".to_string()),
+            date_end: Set("2025-10-15".to_string()),
+            departure_time_min: Set(None),
+            departure_time_max: Set(None),
+            created_at: Set(Utc::now()),
+            max_price: Set(None),
+            seat_type: Set(None),
+            plan_ids_allow: Set(None),
+            plan_ids_deny: Set(None),
+            min_remaining_seats: Set(None),
+            date_window: Set(None),
+            status: Set("active".to_string()),
+            enabled: Set(true),
+            paused_until: Set(None),
+            scrape_interval_secs: Set(None),
+            adaptive_interval: Set(false),
+            notify_on_sold_out: Set(false),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        let schedules = vec![snapshot_schedule(
+            "22:25",
+            SeatAvailability::Available {
+                remaining_seats: Some(2),
+            },
+        )];
+        let alert = QueuedAlert {
+            schedules: schedules.clone(),
+            changes: Vec::new(),
+            context: NotificationContext {
+                departure_station_name: "Shinjuku".to_string(),
+                arrival_station_name: "Kamikochi".to_string(),
+                route_name: String::new(),
+                date_range: ("20251015".to_string(), "20251015".to_string()),
+                passenger_count: 1,
+                time_filter: None,
+                snooze_url: None,
+                availability_url: None,
+                locale: Locale::default(),
+            },
+        };
+        let channels = [
+            NotificationChannel {
+                kind: ChannelKind::Discord,
+                target: "https://discord.example/webhook".to_string(),
+            },
+            NotificationChannel {
+                kind: ChannelKind::Slack,
+                target: "https://hooks.slack.example/services/X".to_string(),
+            },
+        ];
+
+        record_alert(
+            &db,
+            route_id,
+            "v2:1".to_string(),
+            &schedules,
+            &channels,
+            &alert,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let state = RouteStates::find_by_id(route_id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(state.total_alerts, 1);
+
+        let due = get_due_deliveries(&db, Utc::now(), 10).await.unwrap();
+        assert_eq!(due.len(), 2);
+        assert!(due.iter().all(|d| d.attempts == 0));
+        assert_eq!(due[0].alert.schedules[0].departure_time, "22:25");
+        let discord = due.iter().find(|d| d.channel == channels[0]).unwrap();
+        let slack = due.iter().find(|d| d.channel == channels[1]).unwrap();
+
+        mark_delivery_sent(&db, discord.id, 1).await.unwrap();
+        let retry_at = Utc::now() + Duration::minutes(5);
+        mark_delivery_failed(&db, slack.id, 1, 1, "HTTP 500", Some(retry_at))
+            .await
+            .unwrap();
+
+        assert!(
+            get_due_deliveries(&db, Utc::now(), 10)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        let retried = get_due_deliveries(&db, retry_at, 10).await.unwrap();
+        assert_eq!(retried.len(), 1);
+        assert_eq!(retried[0].attempts, 1);
+        assert_eq!(retried[0].messages_sent, 1);
+
+        mark_delivery_failed(&db, slack.id, 2, 1, "HTTP 500", None)
+            .await
+            .unwrap();
+        assert!(
+            get_due_deliveries(&db, retry_at, 10)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let rows = NotificationOutbox::find().all(&db).await.unwrap();
+        let sent = rows.iter().find(|r| r.id == discord.id).unwrap();
+        assert_eq!(sent.status, "sent");
+        assert!(sent.delivered_at.is_some());
+        let failed = rows.iter().find(|r| r.id == slack.id).unwrap();
+        assert_eq!(failed.status, "failed");
+        assert_eq!(failed.attempts, 2);
+        assert_eq!(failed.last_error.as_deref(), Some("HTTP 500"));
+
+        let quiet_end = Utc::now() + Duration::hours(8);
+        record_alert(
+            &db,
+            route_id,
+            "v2:2".to_string(),
+            &schedules,
+            &channels[..1],
+            &alert,
+            Some(quiet_end),
+        )
+        .await
+        .unwrap();
+        assert!(
+            get_due_deliveries(&db, Utc::now(), 10)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        let held = get_due_deliveries(&db, quiet_end, 10).await.unwrap();
+        assert_eq!(held.len(), 1);
+        assert!(held[0].digest);
+        assert_eq!(held[0].user_route_id, route_id);
+    }
+
+    #[tokio::test]
+    async fn test_get_route_owner_locale() {
+        use crate::entities::{user_routes, users};
+
+        let db = setup_test_db().await;
+        let user_id = Uuid::new_v4();
+        let route_id = Uuid::new_v4();
+
+        users::ActiveModel {
+            id: Set(user_id),
+            email: Set("locale@test.com".to_string()),
+            enabled: Set(true),
+            notify_on_change_only: Set(true),
+            scrape_interval_secs: Set(300),
+            created_at: Set(Utc::now()),
+            quiet_hours_start: Set(None),
+            quiet_hours_end: Set(None),
+            quiet_hours_tz: Set(None),
+            locale: Set("ja".to_string()),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        user_routes::ActiveModel {
+            id: Set(route_id),
+            user_id: Set(user_id),
+            area_id: Set(1),
+            route_id: Set("155".to_string()),
+            departure_station: Set("001".to_string()),
+            arrival_station: Set("498".to_string()),
+            date_start: Set("2025-10-15".to_string()),
+            date_end: Set("2025-10-15".to_string()),
+            departure_time_min: Set(None),
+            departure_time_max: Set(None),
+            created_at: Set(Utc::now()),
+            max_price: Set(None),
+            seat_type: Set(None),
+            plan_ids_allow: Set(None),
+            plan_ids_deny: Set(None),
+            min_remaining_seats: Set(None),
+            date_window: Set(None),
+            status: Set("active".to_string()),
+            enabled: Set(true),
+            paused_until: Set(None),
+            scrape_interval_secs: Set(None),
+            adaptive_interval: Set(false),
+            notify_on_sold_out: Set(false),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_route_owner_locale(&db, route_id).await.unwrap(),
+            Some(Locale::Ja)
+        );
+        assert_eq!(
+            get_route_owner_locale(&db, Uuid::new_v4()).await.unwrap(),
+            None
+        );
     }
 }
@@ -0,0 +1,179 @@
+//! Short-lived cache of schedule pages shared by every tracker.
+//!
+//! Trackers watching the same route, date and passengers ask for the same
+//! page. The first caller fetches it; callers arriving while that fetch is in
+//! flight wait for it, and later callers reuse the page until it expires.
+//! Failures are never cached: the next caller tries again.
+
+use crate::error::{Result, ScraperError};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+use tokio::sync::OnceCell;
+use tokio::time::Instant;
+
+const DEFAULT_TTL_SECS: u64 = 30;
+
+type Entry = Arc<OnceCell<(Instant, String)>>;
+
+pub struct ResponseCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl Default for ResponseCache {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(DEFAULT_TTL_SECS))
+    }
+}
+
+impl ResponseCache {
+    /// A zero `ttl` disables caching; concurrent callers are then not coalesced either
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Read `SCRAPER_SCHEDULE_CACHE_SECS`, falling back to the default
+    pub fn ttl_from_env() -> Duration {
+        dotenvy::var("SCRAPER_SCHEDULE_CACHE_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .map_or(Duration::from_secs(DEFAULT_TTL_SECS), Duration::from_secs)
+    }
+
+    /// The cached page for `key`, or the result of `fetch`, run at most once at a time per key
+    pub async fn get_or_fetch<F, Fut>(&self, key: String, fetch: F) -> Result<String>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<String>>,
+    {
+        if self.ttl.is_zero() {
+            return fetch().await;
+        }
+
+        let entry = self.entry(key);
+        let (_, page) = entry
+            .get_or_try_init(|| async { Ok::<_, ScraperError>((Instant::now(), fetch().await?)) })
+            .await?;
+
+        Ok(page.clone())
+    }
+
+    /// Entry for `key`, replacing it if expired. Expired entries of other keys are dropped too.
+    fn entry(&self, key: String) -> Entry {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        let ttl = self.ttl;
+        // An entry still being fetched (or whose fetch failed) has no timestamp and stays
+        entries.retain(|_, entry| {
+            entry
+                .get()
+                .is_none_or(|(fetched_at, _)| fetched_at.elapsed() < ttl)
+        });
+
+        Arc::clone(entries.entry(key).or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    async fn counted(calls: &AtomicU32, page: &str) -> Result<String> {
+        calls.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        Ok(page.to_string())
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_callers_share_one_fetch() {
+        let cache = ResponseCache::new(Duration::from_secs(30));
+        let calls = AtomicU32::new(0);
+
+        let (a, b, c) = tokio::join!(
+            cache.get_or_fetch("k".to_string(), || counted(&calls, "page")),
+            cache.get_or_fetch("k".to_string(), || counted(&calls, "page")),
+            cache.get_or_fetch("k".to_string(), || counted(&calls, "page")),
+        );
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        for page in [a, b, c] {
+            assert_eq!(page.ok().as_deref(), Some("page"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_keys_are_fetched_separately() {
+        let cache = ResponseCache::new(Duration::from_secs(30));
+        let calls = AtomicU32::new(0);
+
+        let (a, b) = tokio::join!(
+            cache.get_or_fetch("k1".to_string(), || counted(&calls, "one")),
+            cache.get_or_fetch("k2".to_string(), || counted(&calls, "two")),
+        );
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(a.ok().as_deref(), Some("one"));
+        assert_eq!(b.ok().as_deref(), Some("two"));
+    }
+
+    #[tokio::test]
+    async fn test_expired_page_is_fetched_again() {
+        let cache = ResponseCache::new(Duration::from_millis(30));
+        let calls = AtomicU32::new(0);
+
+        let _ = cache
+            .get_or_fetch("k".to_string(), || counted(&calls, "old"))
+            .await;
+        let cached = cache
+            .get_or_fetch("k".to_string(), || counted(&calls, "new"))
+            .await;
+        assert_eq!(cached.ok().as_deref(), Some("old"));
+
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        let fresh = cache
+            .get_or_fetch("k".to_string(), || counted(&calls, "new"))
+            .await;
+
+        assert_eq!(fresh.ok().as_deref(), Some("new"));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_failures_are_not_cached() {
+        let cache = ResponseCache::new(Duration::from_secs(30));
+        let calls = AtomicU32::new(0);
+
+        let failed = cache
+            .get_or_fetch("k".to_string(), || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(ScraperError::InvalidResponse("HTTP 404".to_string()))
+            })
+            .await;
+        assert!(failed.is_err());
+
+        let page = cache
+            .get_or_fetch("k".to_string(), || counted(&calls, "page"))
+            .await;
+        assert_eq!(page.ok().as_deref(), Some("page"));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_zero_ttl_disables_cache() {
+        let cache = ResponseCache::new(Duration::ZERO);
+        let calls = AtomicU32::new(0);
+
+        let _ = cache
+            .get_or_fetch("k".to_string(), || counted(&calls, "page"))
+            .await;
+        let _ = cache
+            .get_or_fetch("k".to_string(), || counted(&calls, "page"))
+            .await;
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,180 @@
+//! Retry schedule for requests to the bus operator's site.
+//!
+//! Which errors are worth retrying is decided by [`ScraperError::is_retryable`];
+//! this module only decides how long to wait before the next attempt.
+
+use crate::error::ScraperError;
+use std::time::Duration;
+
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const DEFAULT_BASE_DELAY_MS: u64 = 1000;
+const DEFAULT_MAX_DELAY_SECS: u64 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts made after the first one fails; 0 disables retrying
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each following one
+    pub base_delay: Duration,
+    /// Upper bound for any delay, including one asked for by `Retry-After`
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RETRIES,
+            base_delay: Duration::from_millis(DEFAULT_BASE_DELAY_MS),
+            max_delay: Duration::from_secs(DEFAULT_MAX_DELAY_SECS),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Read `SCRAPER_MAX_RETRIES` and `SCRAPER_RETRY_BASE_MS`, falling back to the defaults
+    pub fn from_env() -> Self {
+        let default = Self::default();
+
+        Self {
+            max_retries: dotenvy::var("SCRAPER_MAX_RETRIES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(default.max_retries),
+            base_delay: dotenvy::var("SCRAPER_RETRY_BASE_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map_or(default.base_delay, Duration::from_millis),
+            ..default
+        }
+    }
+
+    /// Exponential backoff before retry number `retry` (1-based), with jitter.
+    ///
+    /// The delay is drawn between half and all of `base_delay * 2^(retry - 1)`,
+    /// so trackers failing together do not all come back at the same instant.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        let delay = self.base_delay.saturating_mul(factor).min(self.max_delay);
+
+        delay.mul_f64(0.5 + fastrand::f64() / 2.0)
+    }
+
+    /// Delay before retry number `retry` after `error`; the server's `Retry-After` wins
+    pub fn delay_for(&self, retry: u32, error: &ScraperError) -> Duration {
+        error
+            .retry_after()
+            .map_or_else(|| self.backoff(retry), |d| d.min(self.max_delay))
+    }
+}
+
+/// Parse a `Retry-After` header, given either in seconds or as an HTTP date
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    // A date already in the past means "now"
+    Some(
+        (date.with_timezone(&chrono::Utc) - chrono::Utc::now())
+            .to_std()
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    fn policy(base_ms: u64, max_ms: u64) -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 3,
+            base_delay: Duration::from_millis(base_ms),
+            max_delay: Duration::from_millis(max_ms),
+        }
+    }
+
+    #[test]
+    fn test_backoff_doubles_within_jitter_bounds() {
+        let policy = policy(100, 10_000);
+
+        for (retry, full_ms) in [(1, 100), (2, 200), (3, 400)] {
+            let delay = policy.backoff(retry);
+            assert!(delay >= Duration::from_millis(full_ms / 2), "{delay:?}");
+            assert!(delay <= Duration::from_millis(full_ms), "{delay:?}");
+        }
+    }
+
+    #[test]
+    fn test_backoff_is_capped() {
+        let policy = policy(1000, 1500);
+
+        assert!(policy.backoff(10) <= Duration::from_millis(1500));
+        assert!(policy.backoff(u32::MAX) <= Duration::from_millis(1500));
+    }
+
+    #[test]
+    fn test_delay_for_prefers_retry_after() {
+        let policy = policy(100, 5000);
+        let error = ScraperError::Upstream {
+            status: 429,
+            retry_after: Some(Duration::from_secs(2)),
+        };
+        assert_eq!(policy.delay_for(1, &error), Duration::from_secs(2));
+
+        let error = ScraperError::Upstream {
+            status: 503,
+            retry_after: Some(Duration::from_hours(1)),
+        };
+        assert_eq!(policy.delay_for(1, &error), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        assert_eq!(parse_retry_after("120"), Some(Duration::from_mins(2)));
+        assert_eq!(parse_retry_after(" 0 "), Some(Duration::ZERO));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon"), None);
+
+        let later = (chrono::Utc::now() + chrono::Duration::seconds(90)).to_rfc2822();
+        let delay = parse_retry_after(&later).unwrap_or_default();
+        assert!(delay > Duration::from_secs(80) && delay <= Duration::from_secs(90));
+    }
+
+    #[test]
+    #[serial]
+    fn test_policy_from_env() {
+        temp_env::with_vars(
+            [
+                ("SCRAPER_MAX_RETRIES", Some("5")),
+                ("SCRAPER_RETRY_BASE_MS", Some("200")),
+            ],
+            || {
+                let policy = RetryPolicy::from_env();
+                assert_eq!(policy.max_retries, 5);
+                assert_eq!(policy.base_delay, Duration::from_millis(200));
+                assert_eq!(policy.max_delay, RetryPolicy::default().max_delay);
+            },
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_policy_from_env_invalid_uses_defaults() {
+        temp_env::with_vars(
+            [
+                ("SCRAPER_MAX_RETRIES", Some("-1")),
+                ("SCRAPER_RETRY_BASE_MS", None),
+            ],
+            || {
+                assert_eq!(RetryPolicy::from_env(), RetryPolicy::default());
+            },
+        );
+    }
+}
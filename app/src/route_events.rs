@@ -0,0 +1,55 @@
+//! Change events emitted by the API write paths so the background tracker
+//! can start, restart or stop individual route trackers without a restart.
+
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteChange {
+    RouteCreated(Uuid),
+    RouteUpdated(Uuid),
+    RouteDeleted(Uuid),
+    /// A user was created, edited or deleted; all of their routes may be affected.
+    UserChanged(Uuid),
+}
+
+pub type RouteChangeSender = UnboundedSender<RouteChange>;
+pub type RouteChangeReceiver = UnboundedReceiver<RouteChange>;
+
+pub fn route_change_channel() -> (RouteChangeSender, RouteChangeReceiver) {
+    unbounded_channel()
+}
+
+/// Publish a change if a sender is available in the Leptos context.
+///
+/// The tracker may be disabled or already stopped, so a missing sender or a
+/// closed channel is not an error.
+pub fn publish_route_change(change: RouteChange) {
+    if let Some(sender) = leptos::prelude::use_context::<RouteChangeSender>()
+        && sender.send(change).is_err()
+    {
+        tracing::debug!("Route change {:?} dropped: tracker not listening", change);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_publish_without_context_is_noop() {
+        publish_route_change(RouteChange::RouteDeleted(Uuid::new_v4()));
+    }
+
+    #[test]
+    fn test_channel_delivers_changes_in_order() {
+        let (tx, mut rx) = route_change_channel();
+        let id = Uuid::new_v4();
+
+        tx.send(RouteChange::RouteCreated(id)).ok();
+        tx.send(RouteChange::RouteDeleted(id)).ok();
+
+        assert_eq!(rx.try_recv().ok(), Some(RouteChange::RouteCreated(id)));
+        assert_eq!(rx.try_recv().ok(), Some(RouteChange::RouteDeleted(id)));
+    }
+}
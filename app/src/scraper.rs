@@ -1,19 +1,68 @@
+use crate::circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitState};
 use crate::error::{Result, ScraperError};
 use crate::html_parser;
-use crate::types::{BusSchedule, Route, ScrapeRequest, Station};
+use crate::rate_limiter::{RateLimitConfig, RateLimiter};
+use crate::response_cache::ResponseCache;
+use crate::retry::{RetryPolicy, parse_retry_after};
+use crate::types::{
+    Amenity, AvailableDate, BusSchedule, PlanDetails, Route, ScrapeRequest, SeatType, Station,
+};
+use futures::{StreamExt, stream};
 use quick_xml::Reader;
 use quick_xml::events::Event;
-use reqwest::Client;
+use reqwest::{Client, Response, StatusCode, header};
+use std::collections::{HashMap, HashSet};
 use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio::time::Instant;
 use tracing::{debug, warn};
 
-const MAX_RETRIES: u32 = 3;
-const RETRY_DELAY_MS: u64 = 1000;
+/// How long a plan lookup is reused, whether it succeeded or failed
+const PLAN_DETAILS_TTL: Duration = Duration::from_hours(6);
+
 const USER_AGENT: &str = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36";
 
+/// Outcome of [`BusScraper::check_availability_full`], date by date
+#[derive(Debug, Default)]
+pub struct AvailabilityCheck {
+    /// Schedules of each date fetched successfully, in date order
+    pub fetched: Vec<(String, Vec<BusSchedule>)>,
+    /// Dates that could not be fetched, with the reason
+    pub failed: Vec<(String, ScraperError)>,
+}
+
+impl AvailabilityCheck {
+    /// Whether every date was fetched
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    pub fn failed_dates(&self) -> Vec<&str> {
+        self.failed.iter().map(|(date, _)| date.as_str()).collect()
+    }
+
+    /// Schedules of the dates that were fetched; failed dates contribute nothing
+    pub fn into_schedules(self) -> Vec<BusSchedule> {
+        self.fetched
+            .into_iter()
+            .flat_map(|(_, schedules)| schedules)
+            .collect()
+    }
+}
+
 pub struct BusScraper {
     client: Client,
     base_url: String,
+    /// Plan details by `plan_id` with the time they were looked up; `None` when
+    /// the lookup failed, so a broken plan is not refetched on every check
+    plan_details: RwLock<HashMap<u32, (Instant, Option<PlanDetails>)>>,
+    /// Every request to the site goes through this, whichever tracker sends it
+    limiter: RateLimiter,
+    retry: RetryPolicy,
+    /// Shared like the limiter, so one outage stops every tracker at once
+    breaker: CircuitBreaker,
+    /// Schedule pages by query, so trackers asking for the same one share a fetch
+    schedule_pages: ResponseCache,
 }
 
 impl BusScraper {
@@ -24,31 +73,115 @@ impl BusScraper {
             .build()
             .map_err(ScraperError::Http)?;
 
-        Ok(Self { client, base_url })
+        Ok(Self {
+            client,
+            base_url,
+            plan_details: RwLock::new(HashMap::new()),
+            limiter: RateLimiter::new(RateLimitConfig::default()),
+            retry: RetryPolicy::default(),
+            breaker: CircuitBreaker::new(CircuitBreakerConfig::default()),
+            schedule_pages: ResponseCache::default(),
+        })
+    }
+
+    #[must_use]
+    pub fn with_rate_limit(mut self, config: RateLimitConfig) -> Self {
+        self.limiter = RateLimiter::new(config);
+        self
+    }
+
+    #[must_use]
+    pub const fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    #[must_use]
+    pub fn with_circuit_breaker(mut self, config: CircuitBreakerConfig) -> Self {
+        self.breaker = CircuitBreaker::new(config);
+        self
+    }
+
+    /// How long a schedule page is reused; zero fetches it on every call
+    #[must_use]
+    pub fn with_schedule_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.schedule_pages = ResponseCache::new(ttl);
+        self
+    }
+
+    pub fn upstream_state(&self) -> CircuitState {
+        self.breaker.state()
     }
 
+    /// Receiver notified when the site goes down or comes back
+    pub fn watch_upstream(&self) -> tokio::sync::watch::Receiver<CircuitState> {
+        self.breaker.subscribe()
+    }
+
+    /// Fetch schedules for every sold date of the request.
+    ///
+    /// A date that fails does not fail the whole check; it is reported in
+    /// [`AvailabilityCheck::failed`] so callers can tell missing data from sold-out buses.
     pub async fn check_availability_full(
         &self,
         request: &ScrapeRequest,
-    ) -> Result<Vec<BusSchedule>> {
+    ) -> Result<AvailabilityCheck> {
         let dates = request.date_range.dates()?;
-        let mut all_schedules = Vec::new();
-
-        for date in dates {
-            debug!("Fetching schedules for date: {}", date);
-
-            match self.fetch_schedules(request, &date).await {
+        let dates = match self
+            .fetch_available_dates(
+                &request.route_id.to_string(),
+                &request.departure_station,
+                &request.arrival_station,
+            )
+            .await
+        {
+            Ok(available) => {
+                let sold = filter_sold_dates(&dates, &available);
+                if sold.len() < dates.len() {
+                    debug!(
+                        "Skipping {} date(s) without service",
+                        dates.len() - sold.len()
+                    );
+                }
+                sold
+            }
+            // Every date would fail the same way
+            Err(e @ ScraperError::CircuitOpen { .. }) => return Err(e),
+            Err(e) => {
+                warn!(
+                    "Failed to fetch available dates, checking every date: {}",
+                    e
+                );
+                dates
+            }
+        };
+        // Dates are fetched concurrently; the limiter decides how many actually run at once
+        let results: Vec<_> = stream::iter(dates)
+            .map(|date| async move {
+                debug!("Fetching schedules for date: {}", date);
+                let result = self.fetch_schedules(request, &date).await;
+                (date, result)
+            })
+            .buffered(self.limiter.max_concurrent())
+            .collect()
+            .await;
+
+        let mut check = AvailabilityCheck::default();
+
+        for (date, result) in results {
+            match result {
                 Ok(schedules) => {
                     debug!("Found {} schedules for date {}", schedules.len(), date);
-                    all_schedules.extend(schedules);
+                    check.fetched.push((date, schedules));
                 }
                 Err(e) => {
                     warn!("Failed to fetch schedules for date {}: {}", date, e);
+                    check.failed.push((date, e));
                 }
             }
         }
 
-        Ok(all_schedules)
+        Ok(check)
     }
 
     pub async fn fetch_routes(&self, area_id: u32) -> Result<Vec<Route>> {
@@ -100,43 +233,36 @@ impl BusScraper {
         parse_stations(&xml)
     }
 
+    pub async fn fetch_available_dates(
+        &self,
+        route_id: &str,
+        departure_station: &str,
+        arrival_station: &str,
+    ) -> Result<Vec<AvailableDate>> {
+        let url = format!("{}/ajaxPulldown", self.base_url);
+        let xml = self
+            .fetch_with_retry(
+                &url,
+                &[
+                    ("mode", "date"),
+                    ("id", route_id),
+                    ("onStation", departure_station),
+                    ("offStation", arrival_station),
+                    ("lang", "EN"),
+                ],
+            )
+            .await?;
+
+        parse_dates(&xml)
+    }
+
     pub async fn fetch_schedules(
         &self,
         request: &ScrapeRequest,
         date: &str,
     ) -> Result<Vec<BusSchedule>> {
         let url = format!("{}/reservation/rsvPlanList", self.base_url);
-
-        let params = [
-            ("mode", "search".to_string()),
-            ("route", request.area_id.to_string()),
-            ("lineId", request.route_id.to_string()),
-            ("onStationCd", request.departure_station.clone()),
-            ("offStationCd", request.arrival_station.clone()),
-            ("bordingDate", date.to_string()),
-            ("danseiNum", request.passengers.total_male().to_string()),
-            ("zyoseiNum", request.passengers.total_female().to_string()),
-            ("adultMen", request.passengers.adult_men.to_string()),
-            ("adultWomen", request.passengers.adult_women.to_string()),
-            ("childMen", request.passengers.child_men.to_string()),
-            ("childWomen", request.passengers.child_women.to_string()),
-            (
-                "handicapAdultMen",
-                request.passengers.handicap_adult_men.to_string(),
-            ),
-            (
-                "handicapAdultWomen",
-                request.passengers.handicap_adult_women.to_string(),
-            ),
-            (
-                "handicapChildMen",
-                request.passengers.handicap_child_men.to_string(),
-            ),
-            (
-                "handicapChildWomen",
-                request.passengers.handicap_child_women.to_string(),
-            ),
-        ];
+        let params = schedule_query(request, date);
 
         let html = self.fetch_schedules_html(&url, &params).await?;
         let mut schedules = html_parser::parse_schedules_html(&html, date)?;
@@ -145,31 +271,115 @@ impl BusScraper {
             schedules.retain(|s| filter.matches(&s.departure_time));
         }
 
+        let booking_url = self.booking_url(request, date);
+        for schedule in &mut schedules {
+            schedule.booking_url.clone_from(&booking_url);
+        }
+
+        self.attach_plan_details(&mut schedules).await;
+
+        // After the details lookup, since the seat type comes from there
+        if let Some(ref filter) = request.plan_filter {
+            for schedule in &mut schedules {
+                schedule.available_plans.retain(|p| filter.matches(p));
+            }
+            schedules.retain(|s| !s.available_plans.is_empty());
+        }
+
         Ok(schedules)
     }
 
-    async fn fetch_schedules_html(&self, url: &str, params: &[(&str, String)]) -> Result<String> {
-        let query_params: Vec<(&str, &str)> =
-            params.iter().map(|(k, v)| (*k, v.as_str())).collect();
+    /// Fill in `details` of every plan.
+    ///
+    /// A failed lookup leaves the plan without details rather than failing the check.
+    async fn attach_plan_details(&self, schedules: &mut [BusSchedule]) {
+        for plan in schedules.iter_mut().flat_map(|s| &mut s.available_plans) {
+            if plan.plan_id != 0 {
+                plan.details = self.plan_details(plan.plan_id).await;
+            }
+        }
+    }
+
+    /// Fare conditions and equipment of a plan, cached per `plan_id` for
+    /// [`PLAN_DETAILS_TTL`]; `None` if the lookup failed now or recently
+    pub async fn plan_details(&self, plan_id: u32) -> Option<PlanDetails> {
+        if let Some((fetched_at, details)) = self.plan_details.read().await.get(&plan_id)
+            && fetched_at.elapsed() < PLAN_DETAILS_TTL
+        {
+            return details.clone();
+        }
+
+        let result = self.fetch_plan_details(plan_id).await;
+        if let Err(e) = &result {
+            warn!("Failed to fetch details for plan {}: {}", plan_id, e);
+        }
+        let details = match result {
+            // Nothing was asked of the site, so there is no answer to remember
+            Err(ScraperError::CircuitOpen { .. }) => return None,
+            result => result.ok(),
+        };
+        self.plan_details
+            .write()
+            .await
+            .insert(plan_id, (Instant::now(), details.clone()));
+
+        details
+    }
+
+    /// Skipped while the circuit is open, but never counted by it: the
+    /// details are optional, so a broken lookup must not stop availability checks.
+    async fn fetch_plan_details(&self, plan_id: u32) -> Result<PlanDetails> {
+        let url = format!("{}/reservation/ajaxPlanInfoPrint", self.base_url);
+        self.breaker.check()?;
+        let xml = self.fetch_plan_page(&url, plan_id).await?;
+
+        parse_plan_details(&xml)
+    }
 
+    async fn fetch_plan_page(&self, url: &str, plan_id: u32) -> Result<String> {
+        let _permit = self.limiter.acquire().await;
         let response = self
             .client
             .get(url)
             .header("User-Agent", USER_AGENT)
-            .header("Referer", format!("{}/", self.base_url))
-            .query(&query_params)
+            .header(
+                "Referer",
+                format!("{}/reservation/rsvPlanList", self.base_url),
+            )
+            .query(&[
+                ("discntPlanNo", plan_id.to_string()),
+                ("reference", "reservation".to_string()),
+            ])
             .send()
             .await?;
 
-        if !response.status().is_success() {
-            return Err(ScraperError::InvalidResponse(format!(
-                "HTTP {} for url={}",
-                response.status(),
-                url
-            )));
-        }
+        Ok(check_status(response, url)?.text().await?)
+    }
+
+    /// Link to the `rsvPlanList` search results for `date`, the page a user books from.
+    ///
+    /// Uses the same query as [`Self::fetch_schedules`]; `None` if the base URL is invalid.
+    pub fn booking_url(&self, request: &ScrapeRequest, date: &str) -> Option<String> {
+        let url = format!("{}/reservation/rsvPlanList", self.base_url);
+        let params = schedule_query(request, date);
 
-        let html = response.text().await?;
+        reqwest::Url::parse_with_params(&url, params.iter().map(|(k, v)| (*k, v.as_str())))
+            .ok()
+            .map(String::from)
+    }
+
+    async fn fetch_schedules_html(&self, url: &str, params: &[(&str, String)]) -> Result<String> {
+        let query_params: Vec<(&str, &str)> =
+            params.iter().map(|(k, v)| (*k, v.as_str())).collect();
+
+        let key = reqwest::Url::parse_with_params(url, &query_params)
+            .map_or_else(|_| url.to_string(), String::from);
+        let html = self
+            .schedule_pages
+            .get_or_fetch(key, || {
+                self.retrying(url, || self.fetch_page(url, &query_params))
+            })
+            .await?;
         debug!("Fetched schedules HTML, length: {}", html.len());
 
         #[cfg(debug_assertions)]
@@ -182,29 +392,67 @@ impl BusScraper {
     }
 
     async fn fetch_with_retry(&self, url: &str, params: &[(&str, &str)]) -> Result<String> {
-        let mut attempts = 0;
+        self.retrying(url, || self.fetch_data(url, params)).await
+    }
 
-        loop {
-            attempts += 1;
+    /// Run `request` again while it fails with a retryable error, up to the retry policy.
+    ///
+    /// Each attempt takes its own rate-limiter slot; none is held while waiting.
+    /// The circuit breaker sees the call as a whole, once retries are exhausted.
+    async fn retrying<F, Fut>(&self, url: &str, request: F) -> Result<String>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<String>>,
+    {
+        self.breaker.check()?;
+        let result = self.retry_loop(url, request).await;
+        self.breaker.record(&result);
+        result
+    }
 
-            match self.fetch_data(url, params).await {
-                Ok(response) => return Ok(response),
-                Err(ScraperError::ServiceUnavailable) if attempts < MAX_RETRIES => {
+    async fn retry_loop<F, Fut>(&self, url: &str, mut request: F) -> Result<String>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<String>>,
+    {
+        let mut retries = 0;
+
+        loop {
+            match request().await {
+                Err(e) if e.is_retryable() && retries < self.retry.max_retries => {
+                    retries += 1;
+                    let delay = self.retry.delay_for(retries, &e);
                     warn!(
-                        "Service unavailable (attempt {}/{}), retrying in {}ms",
-                        attempts,
-                        MAX_RETRIES,
-                        RETRY_DELAY_MS * u64::from(attempts)
+                        "{} for url={} (retry {}/{}), retrying in {}ms",
+                        e,
+                        url,
+                        retries,
+                        self.retry.max_retries,
+                        delay.as_millis()
                     );
-                    tokio::time::sleep(Duration::from_millis(RETRY_DELAY_MS * u64::from(attempts)))
-                        .await;
+                    tokio::time::sleep(delay).await;
                 }
-                Err(e) => return Err(e),
+                result => return result,
             }
         }
     }
 
+    async fn fetch_page(&self, url: &str, query: &[(&str, &str)]) -> Result<String> {
+        let _permit = self.limiter.acquire().await;
+        let response = self
+            .client
+            .get(url)
+            .header("User-Agent", USER_AGENT)
+            .header("Referer", format!("{}/", self.base_url))
+            .query(query)
+            .send()
+            .await?;
+
+        Ok(check_status(response, url)?.text().await?)
+    }
+
     async fn fetch_data(&self, url: &str, params: &[(&str, &str)]) -> Result<String> {
+        let _permit = self.limiter.acquire().await;
         let response = self
             .client
             .post(url)
@@ -214,21 +462,38 @@ impl BusScraper {
             .send()
             .await?;
 
-        if !response.status().is_success() {
-            return Err(ScraperError::InvalidResponse(format!(
-                "HTTP {} for url={}",
-                response.status(),
-                url
-            )));
-        }
-
-        let body = response.text().await?;
+        let body = check_status(response, url)?.text().await?;
         debug!("Response body: {}", body);
 
         Ok(body)
     }
 }
 
+/// Pass successful responses through; 429 and 5xx become retryable [`ScraperError::Upstream`]
+fn check_status(response: Response, url: &str) -> Result<Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+        let retry_after = response
+            .headers()
+            .get(header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_retry_after);
+
+        return Err(ScraperError::Upstream {
+            status: status.as_u16(),
+            retry_after,
+        });
+    }
+
+    Err(ScraperError::InvalidResponse(format!(
+        "HTTP {status} for url={url}"
+    )))
+}
+
 fn parse_routes(xml: &str) -> Result<Vec<Route>> {
     let mut reader = Reader::from_str(xml);
     reader.config_mut().trim_text(true);
@@ -315,6 +580,148 @@ fn parse_stations(xml: &str) -> Result<Vec<Station>> {
     Ok(stations)
 }
 
+fn parse_dates(xml: &str) -> Result<Vec<AvailableDate>> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut dates = Vec::new();
+    let mut current_id = None;
+    let mut current_name = None;
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => match e.name().as_ref() {
+                b"id" => {
+                    if let (Some(date_id), Some(display_name)) =
+                        (current_id.take(), current_name.take())
+                    {
+                        dates.push(AvailableDate {
+                            date_id,
+                            display_name,
+                        });
+                    }
+                    current_id = Some(read_text(&mut reader)?);
+                }
+                b"name" => {
+                    current_name = Some(read_text(&mut reader)?);
+                }
+                _ => {}
+            },
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(ScraperError::Parse(format!("XML error: {e}"))),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    if let (Some(date_id), Some(display_name)) = (current_id, current_name) {
+        dates.push(AvailableDate {
+            date_id,
+            display_name,
+        });
+    }
+
+    Ok(dates)
+}
+
+fn parse_plan_details(xml: &str) -> Result<PlanDetails> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut details = PlanDetails::default();
+    let mut found = false;
+    let mut current_tag = Vec::new();
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => {
+                current_tag = e.name().as_ref().to_vec();
+                found |= current_tag == b"discntPlan";
+            }
+            Ok(Event::Text(e)) => {
+                let text = e
+                    .unescape()
+                    .map_err(|e| ScraperError::Parse(format!("Text unescape error: {e}")))?;
+                set_plan_detail(&mut details, &current_tag, text.into_owned());
+            }
+            Ok(Event::End(_)) => current_tag.clear(),
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(ScraperError::Parse(format!("XML error: {e}"))),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    if found {
+        Ok(details)
+    } else {
+        Err(ScraperError::Parse(
+            "discntPlan element not found".to_string(),
+        ))
+    }
+}
+
+fn set_plan_detail(details: &mut PlanDetails, tag: &[u8], text: String) {
+    let flag = text == "1";
+    match tag {
+        b"discntNote" => details.discount_note = text,
+        b"appliedCondNote" => details.applied_condition = text,
+        b"wayChangeNote" => details.way_change_note = text,
+        b"repayFeeNote" => details.repay_fee_note = text,
+        b"pointNote" => details.point_note = text,
+        b"settlementLimitNote" => details.settlement_limit = text,
+        b"etcNote" => details.etc_note = text,
+        b"linkUrl" => details.link_url = text,
+        b"ladyOnlyFlg" => details.lady_only = flag,
+        _ if !flag => {}
+        b"oneSeatFlg" => details.seat_type = SeatType::ThreeRow,
+        b"wideSeatFlg" if details.seat_type != SeatType::ThreeRow => {
+            details.seat_type = SeatType::Wide;
+        }
+        b"relaxFlg" if details.seat_type == SeatType::Standard => {
+            details.seat_type = SeatType::Relax;
+        }
+        _ => {
+            if let Some(amenity) = amenity_for_flag(tag) {
+                details.amenities.push(amenity);
+            }
+        }
+    }
+}
+
+fn amenity_for_flag(tag: &[u8]) -> Option<Amenity> {
+    match tag {
+        b"wirelessFreeFlg" => Some(Amenity::FreeWifi),
+        b"wirelessPayFlg" => Some(Amenity::PaidWifi),
+        b"tvFlg" => Some(Amenity::Tv),
+        b"plugFlg" => Some(Amenity::PowerPlug),
+        b"blanketFlg" => Some(Amenity::Blanket),
+        b"slipperFlg" => Some(Amenity::Slippers),
+        b"pillowFlg" => Some(Amenity::Pillow),
+        b"drinkFlg" => Some(Amenity::Drink),
+        _ => None,
+    }
+}
+
+/// Keep only the requested dates the operator sells.
+///
+/// The sales calendar covers a limited horizon (about 30 days), so dates after
+/// its last entry are kept: they may simply not be listed yet.
+fn filter_sold_dates(dates: &[String], available: &[AvailableDate]) -> Vec<String> {
+    let Some(horizon) = available.iter().map(|d| d.date_id.as_str()).max() else {
+        return dates.to_vec();
+    };
+    let sold: HashSet<&str> = available.iter().map(|d| d.date_id.as_str()).collect();
+
+    dates
+        .iter()
+        .filter(|date| date.as_str() > horizon || sold.contains(date.as_str()))
+        .cloned()
+        .collect()
+}
+
 fn read_text(reader: &mut Reader<&[u8]>) -> Result<String> {
     let mut buf = Vec::new();
     match reader.read_event_into(&mut buf) {
@@ -326,11 +733,105 @@ fn read_text(reader: &mut Reader<&[u8]>) -> Result<String> {
     }
 }
 
+/// Query string of the `rsvPlanList` search for one boarding date
+fn schedule_query(request: &ScrapeRequest, date: &str) -> Vec<(&'static str, String)> {
+    vec![
+        ("mode", "search".to_string()),
+        ("route", request.area_id.to_string()),
+        ("lineId", request.route_id.to_string()),
+        ("onStationCd", request.departure_station.clone()),
+        ("offStationCd", request.arrival_station.clone()),
+        ("bordingDate", date.to_string()),
+        ("danseiNum", request.passengers.total_male().to_string()),
+        ("zyoseiNum", request.passengers.total_female().to_string()),
+        ("adultMen", request.passengers.adult_men.to_string()),
+        ("adultWomen", request.passengers.adult_women.to_string()),
+        ("childMen", request.passengers.child_men.to_string()),
+        ("childWomen", request.passengers.child_women.to_string()),
+        (
+            "handicapAdultMen",
+            request.passengers.handicap_adult_men.to_string(),
+        ),
+        (
+            "handicapAdultWomen",
+            request.passengers.handicap_adult_women.to_string(),
+        ),
+        (
+            "handicapChildMen",
+            request.passengers.handicap_child_men.to_string(),
+        ),
+        (
+            "handicapChildWomen",
+            request.passengers.handicap_child_women.to_string(),
+        ),
+    ]
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
 
+    // === parse_plan_details TESTS ===
+
+    #[test]
+    fn test_parse_plan_details() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8" ?>
+<results>
+<discntPlan>
+    <discntNote>バスタ新宿と富士五湖方面を結ぶバスの運賃になります。</discntNote>
+    <etcNote>往復割引はありません。</etcNote>
+    <linkUrl></linkUrl>
+    <ladyOnlyFlg>1</ladyOnlyFlg>
+    <wirelessFreeFlg>1</wirelessFreeFlg>
+    <wirelessPayFlg>0</wirelessPayFlg>
+    <plugFlg>1</plugFlg>
+    <oneSeatFlg>1</oneSeatFlg>
+    <drinkFlg>0</drinkFlg>
+</discntPlan>
+</results>"#;
+
+        let details = parse_plan_details(xml).unwrap();
+        assert_eq!(
+            details.discount_note,
+            "バスタ新宿と富士五湖方面を結ぶバスの運賃になります。"
+        );
+        assert_eq!(details.etc_note, "往復割引はありません。");
+        assert!(details.link_url.is_empty());
+        assert!(details.lady_only);
+        assert_eq!(details.seat_type, SeatType::ThreeRow);
+        assert_eq!(
+            details.amenities,
+            vec![Amenity::FreeWifi, Amenity::PowerPlug]
+        );
+    }
+
+    #[test]
+    fn test_parse_plan_details_seat_type_priority() {
+        let xml = r"<discntPlan><relaxFlg>1</relaxFlg><wideSeatFlg>1</wideSeatFlg></discntPlan>";
+        assert_eq!(parse_plan_details(xml).unwrap().seat_type, SeatType::Wide);
+
+        let xml =
+            r"<discntPlan><oneSeatFlg>1</oneSeatFlg><wideSeatFlg>1</wideSeatFlg></discntPlan>";
+        assert_eq!(
+            parse_plan_details(xml).unwrap().seat_type,
+            SeatType::ThreeRow
+        );
+
+        let xml = r"<discntPlan><oneSeatFlg>0</oneSeatFlg></discntPlan>";
+        let details = parse_plan_details(xml).unwrap();
+        assert_eq!(details.seat_type, SeatType::Standard);
+        assert!(details.amenities.is_empty());
+        assert!(!details.lady_only);
+    }
+
+    #[test]
+    fn test_parse_plan_details_missing_plan_returns_error() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8" ?><results></results>"#;
+
+        assert!(parse_plan_details(xml).is_err());
+    }
+
     // === parse_routes TESTS ===
 
     #[test]
@@ -453,6 +954,85 @@ mod tests {
         assert_eq!(stations[0].name, "東京駅 八重洲口");
     }
 
+    // === parse_dates TESTS ===
+
+    #[test]
+    fn test_parse_dates_valid_xml() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8" ?>
+<results>
+<rosen>
+    <num>2</num>
+    <id>20251012</id>
+        <name>2025/10/12(日)</name>
+        <switchChangeableFlg></switchChangeableFlg>
+        <nearbyStationFlg></nearbyStationFlg>
+    <id>20251013</id>
+        <name>2025/10/13(祝)</name>
+        <switchChangeableFlg></switchChangeableFlg>
+        <nearbyStationFlg></nearbyStationFlg>
+</rosen>
+</results>"#;
+
+        let dates = parse_dates(xml).unwrap();
+        assert_eq!(dates.len(), 2);
+        assert_eq!(dates[0].date_id, "20251012");
+        assert_eq!(dates[0].display_name, "2025/10/12(日)");
+        assert_eq!(dates[1].date_id, "20251013");
+        assert_eq!(dates[1].display_name, "2025/10/13(祝)");
+    }
+
+    #[test]
+    fn test_parse_dates_empty_xml() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?><results><rosen><num>0</num></rosen></results>"#;
+
+        let dates = parse_dates(xml).unwrap();
+        assert!(dates.is_empty());
+    }
+
+    // === filter_sold_dates TESTS ===
+
+    fn available(ids: &[&str]) -> Vec<AvailableDate> {
+        ids.iter()
+            .map(|id| AvailableDate {
+                date_id: (*id).to_string(),
+                display_name: String::new(),
+            })
+            .collect()
+    }
+
+    fn dates(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| (*id).to_string()).collect()
+    }
+
+    #[test]
+    fn test_filter_sold_dates_skips_dates_without_service() {
+        let requested = dates(&["20251012", "20251013", "20251014"]);
+        let sold = available(&["20251012", "20251014"]);
+
+        assert_eq!(
+            filter_sold_dates(&requested, &sold),
+            dates(&["20251012", "20251014"])
+        );
+    }
+
+    #[test]
+    fn test_filter_sold_dates_keeps_dates_beyond_calendar_horizon() {
+        let requested = dates(&["20251013", "20251020", "20251115"]);
+        let sold = available(&["20251012", "20251014"]);
+
+        assert_eq!(
+            filter_sold_dates(&requested, &sold),
+            dates(&["20251020", "20251115"])
+        );
+    }
+
+    #[test]
+    fn test_filter_sold_dates_empty_calendar_keeps_all() {
+        let requested = dates(&["20251012", "20251013"]);
+
+        assert_eq!(filter_sold_dates(&requested, &[]), requested);
+    }
+
     // === read_text TESTS ===
 
     #[test]
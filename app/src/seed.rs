@@ -1,6 +1,9 @@
+use crate::api::NotificationChannelDto;
+use crate::api_impl::replace_notification_channels;
 use crate::config::Config;
 use crate::entities::{prelude::*, user_passengers, user_routes, users};
 use crate::error::Result;
+use crate::types::{ChannelKind, Locale, RouteStatus};
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel, QueryFilter,
     Set,
@@ -28,7 +31,6 @@ pub async fn seed_from_env(db: &DatabaseConnection) -> Result<()> {
         user_active.enabled = Set(true);
         user_active.notify_on_change_only = Set(config.notify_on_change_only);
         user_active.scrape_interval_secs = Set(config.scrape_interval_secs as i64);
-        user_active.discord_webhook_url = Set(config.discord_webhook_url.clone());
 
         let updated_user = user_active.update(db).await?;
         info!("Updated user configuration for: {}", email);
@@ -42,8 +44,11 @@ pub async fn seed_from_env(db: &DatabaseConnection) -> Result<()> {
             enabled: Set(true),
             notify_on_change_only: Set(config.notify_on_change_only),
             scrape_interval_secs: Set(config.scrape_interval_secs as i64),
-            discord_webhook_url: Set(config.discord_webhook_url.clone()),
             created_at: Set(chrono::Utc::now()),
+            quiet_hours_start: Set(None),
+            quiet_hours_end: Set(None),
+            quiet_hours_tz: Set(None),
+            locale: Set(Locale::default().as_str().to_string()),
         };
         user.insert(db).await?;
         info!("Created user with ID: {}", user_id);
@@ -51,6 +56,17 @@ pub async fn seed_from_env(db: &DatabaseConnection) -> Result<()> {
         user_id
     };
 
+    let channels = config
+        .discord_webhook_url
+        .iter()
+        .map(|url| NotificationChannelDto {
+            kind: ChannelKind::Discord,
+            target: url.clone(),
+            enabled: true,
+        })
+        .collect();
+    replace_notification_channels(db, user_id, channels).await?;
+
     let existing_route = UserRoutes::find()
         .filter(user_routes::Column::UserId.eq(user_id))
         .filter(user_routes::Column::AreaId.eq(config.request.area_id as i32))
@@ -66,6 +82,7 @@ pub async fn seed_from_env(db: &DatabaseConnection) -> Result<()> {
         let mut route_active: user_routes::ActiveModel = existing.into_active_model();
         route_active.date_start = Set(config.request.date_range.start.clone());
         route_active.date_end = Set(config.request.date_range.end.clone());
+        route_active.status = Set(RouteStatus::Active.as_str().to_string());
         route_active.departure_time_min = Set(config
             .request
             .time_filter
@@ -103,6 +120,18 @@ pub async fn seed_from_env(db: &DatabaseConnection) -> Result<()> {
                 .as_ref()
                 .and_then(|f| f.departure_max.clone())),
             created_at: Set(chrono::Utc::now()),
+            max_price: Set(None),
+            seat_type: Set(None),
+            plan_ids_allow: Set(None),
+            plan_ids_deny: Set(None),
+            min_remaining_seats: Set(None),
+            date_window: Set(None),
+            status: Set(RouteStatus::Active.as_str().to_string()),
+            enabled: Set(true),
+            paused_until: Set(None),
+            scrape_interval_secs: Set(None),
+            adaptive_interval: Set(false),
+            notify_on_sold_out: Set(false),
         };
         route.insert(db).await?;
         info!("Created route with ID: {}", route_id);
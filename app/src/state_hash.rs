@@ -0,0 +1,139 @@
+//! Stable hash of a route's availability, stored in `route_states.last_seen_hash`.
+//!
+//! The schedules are written out in a canonical text form and hashed with
+//! xxh64, so the same availability gives the same hash on every build and
+//! toolchain. Stored hashes carry a `v{N}:` prefix; a hash written by an older
+//! version (the first one had no prefix) cannot be compared with a new one and
+//! is replaced by the current state without counting as a change.
+
+use crate::types::{BusSchedule, SeatAvailability};
+use std::fmt::Write;
+use xxhash_rust::xxh64::xxh64;
+
+/// Bump whenever [`canonical_state`] or the hash algorithm changes
+pub const STATE_HASH_VERSION: u32 = 2;
+
+/// One line per bus, in the order given: date, time, then every plan as
+/// `plan_id,price,availability`
+pub fn canonical_state(schedules: &[BusSchedule]) -> String {
+    let mut out = String::new();
+
+    for schedule in schedules {
+        out.push_str(&schedule.departure_date);
+        out.push(' ');
+        out.push_str(&schedule.departure_time);
+
+        for plan in &schedule.available_plans {
+            let _ = write!(
+                out,
+                "|{},{},{}",
+                plan.plan_id,
+                plan.price,
+                plan.availability.status()
+            );
+            if let SeatAvailability::Available {
+                remaining_seats: Some(n),
+            } = plan.availability
+            {
+                let _ = write!(out, ":{n}");
+            }
+        }
+        out.push('\n');
+    }
+
+    out
+}
+
+pub fn state_hash(schedules: &[BusSchedule]) -> u64 {
+    xxh64(canonical_state(schedules).as_bytes(), 0)
+}
+
+/// [`state_hash`] as stored in `route_states.last_seen_hash`, e.g. `v2:ef46db3751d8e999`
+pub fn format_state_hash(hash: u64) -> String {
+    format!("v{STATE_HASH_VERSION}:{hash:016x}")
+}
+
+/// Whether `stored` was written by the current version and can be compared
+pub fn is_current_version(stored: &str) -> bool {
+    stored
+        .strip_prefix('v')
+        .and_then(|rest| rest.split_once(':'))
+        .is_some_and(|(version, _)| version.parse() == Ok(STATE_HASH_VERSION))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PricingPlan;
+
+    fn schedule(time: &str, availability: SeatAvailability) -> BusSchedule {
+        BusSchedule {
+            bus_number: "Bus_1".to_string(),
+            route_name: "Test Route".to_string(),
+            departure_station: "001".to_string(),
+            departure_date: "20250115".to_string(),
+            departure_time: time.to_string(),
+            arrival_station: "064".to_string(),
+            arrival_date: "20250115".to_string(),
+            arrival_time: "10:00".to_string(),
+            way_no: 1,
+            available_plans: vec![PricingPlan {
+                plan_id: 12345,
+                plan_index: 0,
+                plan_name: "Standard".to_string(),
+                price: 2100,
+                display_price: "2,100円".to_string(),
+                availability,
+                details: None,
+            }],
+            booking_url: None,
+        }
+    }
+
+    #[test]
+    fn test_canonical_state() {
+        let schedules = vec![
+            schedule(
+                "08:30",
+                SeatAvailability::Available {
+                    remaining_seats: Some(3),
+                },
+            ),
+            schedule("10:00", SeatAvailability::SoldOut),
+        ];
+
+        assert_eq!(
+            canonical_state(&schedules),
+            "20250115 08:30|12345,2100,available:3\n20250115 10:00|12345,2100,sold_out\n"
+        );
+    }
+
+    #[test]
+    fn test_canonical_state_ignores_display_fields() {
+        let a = schedule("08:30", SeatAvailability::FewLeft);
+        let mut b = a.clone();
+        b.bus_number = "Bus_2".to_string();
+        b.available_plans[0].plan_name = "Renamed".to_string();
+
+        assert_eq!(canonical_state(&[a]), canonical_state(&[b]));
+    }
+
+    #[test]
+    fn test_state_hash_is_pinned() {
+        // Must not change between builds; bump STATE_HASH_VERSION if it has to
+        let schedules = vec![schedule("08:30", SeatAvailability::FewLeft)];
+        assert_eq!(
+            format_state_hash(state_hash(&schedules)),
+            "v2:d41b10992b3c9e1b"
+        );
+        assert_eq!(format_state_hash(state_hash(&[])), "v2:ef46db3751d8e999");
+    }
+
+    #[test]
+    fn test_is_current_version() {
+        assert!(is_current_version(&format_state_hash(state_hash(&[]))));
+        assert!(!is_current_version("12345678901234567890"));
+        assert!(!is_current_version("v1:0123456789abcdef"));
+        assert!(!is_current_version(""));
+    }
+}
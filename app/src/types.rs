@@ -1,5 +1,5 @@
-use chrono::NaiveDate;
-use serde::Serialize;
+use chrono::{Datelike, Local, NaiveDate, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
 
 use crate::error::{Result, ScraperError};
 
@@ -12,6 +12,7 @@ pub struct ScrapeRequest {
     pub date_range: DateRange,
     pub passengers: PassengerCount,
     pub time_filter: Option<TimeFilter>,
+    pub plan_filter: Option<PlanFilter>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -73,10 +74,27 @@ impl Default for PassengerCount {
 pub struct DateRange {
     pub start: String,
     pub end: String,
+    /// Replaces `start` and `end` when set
+    pub window: Option<DateWindow>,
 }
 
 impl DateRange {
+    /// Dates to check today, as `YYYYMMDD`
     pub fn dates(&self) -> Result<Vec<String>> {
+        self.dates_on(Local::now().date_naive())
+    }
+
+    /// Dates to check on `today`, as `YYYYMMDD`
+    pub fn dates_on(&self, today: NaiveDate) -> Result<Vec<String>> {
+        if let Some(window) = &self.window {
+            window.validate()?;
+            return Ok(window
+                .dates(today)
+                .into_iter()
+                .map(|d| d.format("%Y%m%d").to_string())
+                .collect());
+        }
+
         let start = Self::parse_date(&self.start)?;
         let end = Self::parse_date(&self.end)?;
 
@@ -98,6 +116,11 @@ impl DateRange {
         Ok(dates)
     }
 
+    /// Whether every date of the range is before `today`; rolling windows never end
+    pub fn has_ended(&self, today: NaiveDate) -> bool {
+        self.window.is_none() && Self::parse_date(&self.end).is_ok_and(|end| end < today)
+    }
+
     fn parse_date(date_str: &str) -> Result<NaiveDate> {
         NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
             .or_else(|_| NaiveDate::parse_from_str(date_str, "%Y%m%d"))
@@ -109,6 +132,162 @@ impl DateRange {
     }
 }
 
+/// Longest window accepted, in days from today
+pub const MAX_WINDOW_DAYS: u32 = 365;
+
+/// Dates relative to the day of the check, so a route never goes stale.
+///
+/// Stored and sent as a compact spec (see [`Self::to_spec`]).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum DateWindow {
+    /// From `today + start_days` to `today + end_days`, both included
+    Relative { start_days: u32, end_days: u32 },
+    /// Every day in `weekdays` from today over the next `weeks` weeks
+    Weekdays { weekdays: Vec<Weekday>, weeks: u32 },
+}
+
+impl DateWindow {
+    /// Dates covered on `today`, in order
+    pub fn dates(&self, today: NaiveDate) -> Vec<NaiveDate> {
+        let (first, last) = self.bounds(today);
+        first
+            .iter_days()
+            .take_while(|day| *day <= last)
+            .filter(|day| match self {
+                Self::Relative { .. } => true,
+                Self::Weekdays { weekdays, .. } => weekdays.contains(&day.weekday()),
+            })
+            .collect()
+    }
+
+    /// First and last day the window can cover on `today`
+    pub fn bounds(&self, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+        let (start, end) = match self {
+            Self::Relative {
+                start_days,
+                end_days,
+            } => (*start_days, *end_days),
+            Self::Weekdays { weeks, .. } => (0, weeks.saturating_mul(7).saturating_sub(1)),
+        };
+        let offset = |days: u32| today + chrono::Days::new(u64::from(days.min(MAX_WINDOW_DAYS)));
+        (offset(start), offset(end))
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            Self::Relative {
+                start_days,
+                end_days,
+            } => {
+                if start_days > end_days {
+                    return Err(ScraperError::Config(
+                        "Window start must not be after its end".to_string(),
+                    ));
+                }
+                if *end_days > MAX_WINDOW_DAYS {
+                    return Err(ScraperError::Config(format!(
+                        "Window cannot extend beyond {MAX_WINDOW_DAYS} days"
+                    )));
+                }
+            }
+            Self::Weekdays { weekdays, weeks } => {
+                if weekdays.is_empty() {
+                    return Err(ScraperError::Config(
+                        "At least one weekday required".to_string(),
+                    ));
+                }
+                if *weeks == 0 || *weeks > MAX_WINDOW_DAYS / 7 {
+                    return Err(ScraperError::Config(format!(
+                        "Weeks must be between 1 and {}",
+                        MAX_WINDOW_DAYS / 7
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// `relative:3:30` or `weekdays:fri,sat:8`
+    pub fn to_spec(&self) -> String {
+        match self {
+            Self::Relative {
+                start_days,
+                end_days,
+            } => format!("relative:{start_days}:{end_days}"),
+            Self::Weekdays { weekdays, weeks } => {
+                format!("weekdays:{}:{weeks}", format_weekdays(weekdays))
+            }
+        }
+    }
+
+    /// Parse a spec written by [`Self::to_spec`]; `None` if malformed or invalid
+    pub fn parse(spec: &str) -> Option<Self> {
+        let mut parts = spec.trim().split(':');
+        let window = match (parts.next()?, parts.next()?, parts.next()?) {
+            ("relative", start, end) => Self::Relative {
+                start_days: start.parse().ok()?,
+                end_days: end.parse().ok()?,
+            },
+            ("weekdays", days, weeks) => Self::Weekdays {
+                weekdays: parse_weekdays(days),
+                weeks: weeks.parse().ok()?,
+            },
+            _ => return None,
+        };
+
+        (parts.next().is_none() && window.validate().is_ok()).then_some(window)
+    }
+
+    /// Short description for route lists
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Relative {
+                start_days,
+                end_days,
+            } => format!("Today+{start_days} to today+{end_days}"),
+            Self::Weekdays { weekdays, weeks } => {
+                let days: Vec<String> = weekdays.iter().map(ToString::to_string).collect();
+                format!("{} for {weeks} week(s)", days.join(", "))
+            }
+        }
+    }
+}
+
+impl From<DateWindow> for String {
+    fn from(window: DateWindow) -> Self {
+        window.to_spec()
+    }
+}
+
+impl TryFrom<String> for DateWindow {
+    type Error = String;
+
+    fn try_from(spec: String) -> std::result::Result<Self, Self::Error> {
+        Self::parse(&spec).ok_or_else(|| format!("Invalid date window '{spec}'"))
+    }
+}
+
+/// Weekdays in Monday-first order, deduplicated; unknown names are skipped
+pub fn parse_weekdays(value: &str) -> Vec<Weekday> {
+    let mut weekdays: Vec<Weekday> = value
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter_map(|day| day.parse().ok())
+        .collect();
+    weekdays.sort_by_key(Weekday::num_days_from_monday);
+    weekdays.dedup();
+    weekdays
+}
+
+/// `mon,fri` style list, the format read by [`parse_weekdays`]
+pub fn format_weekdays(weekdays: &[Weekday]) -> String {
+    weekdays
+        .iter()
+        .map(|day| day.to_string().to_lowercase())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
 #[derive(Debug, Clone)]
 pub struct TimeFilter {
     pub departure_min: Option<String>,
@@ -131,6 +310,185 @@ impl TimeFilter {
     }
 }
 
+/// Shortest check interval a route may set, in seconds
+pub const MIN_ROUTE_INTERVAL_SECS: i64 = 60;
+
+/// Zone of quiet hours that do not name one
+pub const DEFAULT_TIME_ZONE: &str = "Asia/Tokyo";
+
+/// Daily period during which a user's alerts are held back, then sent as one digest.
+///
+/// A period whose end is before its start spans midnight.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuietHours {
+    /// Local time, `HH:MM`
+    pub start: String,
+    /// Local time, `HH:MM`, excluded
+    pub end: String,
+    /// IANA zone the times are in, e.g. `Asia/Tokyo`
+    pub time_zone: String,
+}
+
+impl QuietHours {
+    /// Quiet hours from the optional columns of a user, when both times are set
+    pub fn from_parts(
+        start: Option<&str>,
+        end: Option<&str>,
+        time_zone: Option<&str>,
+    ) -> Option<Self> {
+        Some(Self {
+            start: start?.to_string(),
+            end: end?.to_string(),
+            time_zone: time_zone
+                .filter(|tz| !tz.is_empty())
+                .unwrap_or(DEFAULT_TIME_ZONE)
+                .to_string(),
+        })
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let (start, end) = self.times()?;
+        if start == end {
+            return Err(ScraperError::Config(
+                "Quiet hours must not start and end at the same time".to_string(),
+            ));
+        }
+        #[cfg(feature = "ssr")]
+        self.zone()?;
+        Ok(())
+    }
+
+    /// Whether the local `time` falls in the period
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        let Ok((start, end)) = self.times() else {
+            return false;
+        };
+
+        if start <= end {
+            start <= time && time < end
+        } else {
+            time >= start || time < end
+        }
+    }
+
+    /// Whether `now` falls in the period, in the period's zone
+    #[cfg(feature = "ssr")]
+    pub fn is_quiet_at(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.quiet_until(now).is_some()
+    }
+
+    /// When the period `now` falls in ends, or `None` outside the period
+    #[cfg(feature = "ssr")]
+    pub fn quiet_until(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Option<chrono::DateTime<chrono::Utc>> {
+        use chrono::TimeZone;
+
+        let zone = self.zone().ok()?;
+        let local = now.with_timezone(&zone);
+        if !self.contains(local.time()) {
+            return None;
+        }
+
+        let (_, end) = self.times().ok()?;
+        let mut date = local.date_naive();
+        if local.time() >= end {
+            date = date.succ_opt()?;
+        }
+        let end = date.and_time(end);
+        // An end time skipped by a DST change is taken an hour later
+        let end = zone.from_local_datetime(&end).earliest().or_else(|| {
+            zone.from_local_datetime(&(end + chrono::Duration::hours(1)))
+                .earliest()
+        })?;
+        Some(end.with_timezone(&chrono::Utc))
+    }
+
+    #[cfg(feature = "ssr")]
+    fn zone(&self) -> Result<chrono_tz::Tz> {
+        self.time_zone
+            .parse()
+            .map_err(|_| ScraperError::Config(format!("Unknown time zone '{}'", self.time_zone)))
+    }
+
+    fn times(&self) -> Result<(NaiveTime, NaiveTime)> {
+        let parse = |time: &str| {
+            NaiveTime::parse_from_str(time, "%H:%M").map_err(|_| {
+                ScraperError::Config(format!(
+                    "Invalid quiet hours time '{time}' (expected HH:MM)"
+                ))
+            })
+        };
+        Ok((parse(&self.start)?, parse(&self.end)?))
+    }
+}
+
+/// Per-route restrictions on which plans are worth notifying about
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlanFilter {
+    /// Price ceiling in yen, inclusive
+    pub max_price: Option<u32>,
+    pub seat_type: Option<SeatType>,
+    /// Only these plan ids are kept; empty keeps every plan
+    pub plan_ids_allow: Vec<u32>,
+    pub plan_ids_deny: Vec<u32>,
+    pub min_remaining_seats: Option<u32>,
+}
+
+impl PlanFilter {
+    /// Whether `plan` passes the filter.
+    ///
+    /// Values the site did not give us never exclude a plan: a zero price,
+    /// missing plan details, or a seat count the operator does not show.
+    pub fn matches(&self, plan: &PricingPlan) -> bool {
+        if !self.plan_ids_allow.is_empty() && !self.plan_ids_allow.contains(&plan.plan_id) {
+            return false;
+        }
+        if self.plan_ids_deny.contains(&plan.plan_id) {
+            return false;
+        }
+        if let Some(max) = self.max_price
+            && plan.price > 0
+            && plan.price > max
+        {
+            return false;
+        }
+        if let Some(seat_type) = self.seat_type
+            && let Some(ref details) = plan.details
+            && details.seat_type != seat_type
+        {
+            return false;
+        }
+        if let Some(min) = self.min_remaining_seats
+            && let Some(remaining) = plan.availability.remaining_seats()
+            && remaining < min
+        {
+            return false;
+        }
+        true
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Parse a comma- or space-separated list of plan ids; anything that is not a number is an error
+    pub fn parse_plan_ids(ids: &str) -> Result<Vec<u32>> {
+        ids.split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|id| !id.is_empty())
+            .map(|id| {
+                id.parse()
+                    .map_err(|_| ScraperError::Config(format!("Invalid plan id '{id}'")))
+            })
+            .collect()
+    }
+
+    pub fn format_plan_ids(ids: &[u32]) -> String {
+        ids.iter().map(u32::to_string).collect::<Vec<_>>().join(",")
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Route {
     pub id: String,
@@ -144,7 +502,16 @@ pub struct Station {
     pub name: String,
 }
 
-#[derive(Debug, Clone, Serialize)]
+/// A departure date the operator sells tickets for, from the `mode=date` pulldown.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AvailableDate {
+    /// Date in `YYYYMMDD` format
+    pub date_id: String,
+    /// Operator display label, e.g. `2025/10/12(日)`
+    pub display_name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BusSchedule {
     pub bus_number: String,
     pub route_name: String,
@@ -155,10 +522,27 @@ pub struct BusSchedule {
     pub arrival_date: String,
     pub arrival_time: String,
     pub way_no: u32,
+    /// Every plan offered on this bus, including sold-out ones.
+    /// Use [`BusSchedule::bookable_plans`] for plans that can be booked now.
     pub available_plans: Vec<PricingPlan>,
+    /// `rsvPlanList` search page this schedule was scraped from, for booking
+    pub booking_url: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+impl BusSchedule {
+    /// Plans that currently have seats for sale
+    pub fn bookable_plans(&self) -> impl Iterator<Item = &PricingPlan> {
+        self.available_plans
+            .iter()
+            .filter(|p| p.availability.is_bookable())
+    }
+
+    pub fn has_bookable_plans(&self) -> bool {
+        self.bookable_plans().next().is_some()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PricingPlan {
     pub plan_id: u32,
     pub plan_index: u32,
@@ -166,12 +550,258 @@ pub struct PricingPlan {
     pub price: u32,
     pub display_price: String,
     pub availability: SeatAvailability,
+    /// From `ajaxPlanInfoPrint`; `None` until fetched or if the lookup failed
+    pub details: Option<PlanDetails>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+/// Fare conditions and bus equipment of a plan, from `ajaxPlanInfoPrint`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlanDetails {
+    pub discount_note: String,
+    pub applied_condition: String,
+    pub way_change_note: String,
+    pub repay_fee_note: String,
+    pub point_note: String,
+    pub settlement_limit: String,
+    pub etc_note: String,
+    pub link_url: String,
+    pub lady_only: bool,
+    pub seat_type: SeatType,
+    pub amenities: Vec<Amenity>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SeatType {
+    /// 4-row seating
+    #[default]
+    Standard,
+    /// Independent seats in 3 rows
+    ThreeRow,
+    Relax,
+    Wide,
+}
+
+impl SeatType {
+    pub const ALL: [Self; 4] = [Self::Standard, Self::ThreeRow, Self::Relax, Self::Wide];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Standard => "standard",
+            Self::ThreeRow => "three_row",
+            Self::Relax => "relax",
+            Self::Wide => "wide",
+        }
+    }
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Standard => "Standard (4 rows)",
+            Self::ThreeRow => "3-row independent",
+            Self::Relax => "Relax",
+            Self::Wide => "Wide",
+        }
+    }
+
+    pub fn parse(seat_type: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.as_str() == seat_type)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Amenity {
+    FreeWifi,
+    PaidWifi,
+    Tv,
+    PowerPlug,
+    Blanket,
+    Slippers,
+    Pillow,
+    Drink,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(tag = "status", rename_all = "snake_case")]
 pub enum SeatAvailability {
+    /// Seats on sale; the operator shows an exact count only when few remain
     Available { remaining_seats: Option<u32> },
+    /// Seats on sale but flagged as nearly gone without an exact count (`残りわずか`)
+    FewLeft,
+    /// Bus is full (`満席`)
+    SoldOut,
+    /// Bus is full but accepts cancellation waitlist requests (`キャンセル待ち`)
+    Waitlist,
+    /// Seat marker and button text were not recognised
+    Unknown,
+}
+
+impl SeatAvailability {
+    /// Whether seats can be booked right now
+    pub fn is_bookable(&self) -> bool {
+        matches!(self, Self::Available { .. } | Self::FewLeft)
+    }
+
+    /// Stable `snake_case` name, matching the serialized `status` tag
+    pub const fn status(&self) -> &'static str {
+        match self {
+            Self::Available { .. } => "available",
+            Self::FewLeft => "few_left",
+            Self::SoldOut => "sold_out",
+            Self::Waitlist => "waitlist",
+            Self::Unknown => "unknown",
+        }
+    }
+
+    pub const fn remaining_seats(&self) -> Option<u32> {
+        match self {
+            Self::Available { remaining_seats } => *remaining_seats,
+            _ => None,
+        }
+    }
+}
+
+/// Delivery channel for notifications, stored as `kind` in `notification_channels`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelKind {
+    /// Discord incoming webhook; the target is the webhook URL
+    #[default]
+    Discord,
+    /// Slack incoming webhook; the target is the webhook URL
+    Slack,
+    /// SMTP email; the target is the recipient address
+    Email,
+}
+
+impl ChannelKind {
+    pub const ALL: [Self; 3] = [Self::Discord, Self::Slack, Self::Email];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Discord => "discord",
+            Self::Slack => "slack",
+            Self::Email => "email",
+        }
+    }
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Discord => "Discord",
+            Self::Slack => "Slack",
+            Self::Email => "Email",
+        }
+    }
+
+    pub fn parse(kind: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|k| k.as_str() == kind)
+    }
+}
+
+/// Lifecycle of a tracked route, stored as `status` in `user_routes`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RouteStatus {
+    /// Checked by a tracker
+    #[default]
+    Active,
+    /// Its last date has passed; kept for reference but no longer checked
+    Archived,
+}
+
+impl RouteStatus {
+    pub const ALL: [Self; 2] = [Self::Active, Self::Archived];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Active => "active",
+            Self::Archived => "archived",
+        }
+    }
+
+    pub fn parse(status: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.as_str() == status)
+    }
+}
+
+/// Progress of an alert through the outbox, stored as `status` in `notification_outbox`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    /// Waiting for its first attempt or for a retry
+    #[default]
+    Pending,
+    /// Accepted by the channel
+    Sent,
+    /// Given up on after the last retry
+    Failed,
+}
+
+impl DeliveryStatus {
+    pub const ALL: [Self; 3] = [Self::Pending, Self::Sent, Self::Failed];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Sent => "sent",
+            Self::Failed => "failed",
+        }
+    }
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Pending => "Pending",
+            Self::Sent => "Sent",
+            Self::Failed => "Failed",
+        }
+    }
+
+    pub fn parse(status: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.as_str() == status)
+    }
+}
+
+/// Language of a user's notifications, stored as `locale` in `users`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Locale {
+    #[default]
+    Fr,
+    En,
+    Ja,
+}
+
+impl Locale {
+    pub const ALL: [Self; 3] = [Self::Fr, Self::En, Self::Ja];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Fr => "fr",
+            Self::En => "en",
+            Self::Ja => "ja",
+        }
+    }
+
+    /// Name of the language in that language
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Fr => "Français",
+            Self::En => "English",
+            Self::Ja => "日本語",
+        }
+    }
+
+    pub fn parse(locale: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|l| l.as_str() == locale)
+    }
+}
+
+/// Where a user's notifications are delivered
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NotificationChannel {
+    pub kind: ChannelKind,
+    /// Webhook URL or address, depending on `kind`
+    pub target: String,
 }
 
 #[cfg(test)]
@@ -184,6 +814,7 @@ mod tests {
         let range = DateRange {
             start: "2025-10-29".to_string(),
             end: "2025-11-02".to_string(),
+            window: None,
         };
 
         let dates = range.dates().unwrap();
@@ -197,6 +828,7 @@ mod tests {
         let range = DateRange {
             start: "20251029".to_string(),
             end: "20251102".to_string(),
+            window: None,
         };
 
         let dates = range.dates().unwrap();
@@ -210,6 +842,7 @@ mod tests {
         let range = DateRange {
             start: "2025-10-29".to_string(),
             end: "20251102".to_string(),
+            window: None,
         };
 
         let dates = range.dates().unwrap();
@@ -223,6 +856,7 @@ mod tests {
         let range = DateRange {
             start: "2025-10-29".to_string(),
             end: "2025-10-29".to_string(),
+            window: None,
         };
 
         let dates = range.dates().unwrap();
@@ -235,6 +869,7 @@ mod tests {
         let range = DateRange {
             start: "2025/10/29".to_string(),
             end: "2025-10-30".to_string(),
+            window: None,
         };
 
         assert!(range.dates().is_err());
@@ -245,6 +880,7 @@ mod tests {
         let range = DateRange {
             start: "2025-11-02".to_string(),
             end: "2025-10-29".to_string(),
+            window: None,
         };
 
         let result = range.dates();
@@ -257,6 +893,114 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_date_range_window_overrides_fixed_dates() {
+        let range = DateRange {
+            start: "2025-01-01".to_string(),
+            end: "2025-01-02".to_string(),
+            window: Some(DateWindow::Relative {
+                start_days: 3,
+                end_days: 5,
+            }),
+        };
+        let today = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+
+        assert_eq!(
+            range.dates_on(today).unwrap(),
+            vec!["20261020", "20261021", "20261022"]
+        );
+    }
+
+    #[test]
+    fn test_date_range_has_ended() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+        let range = |end: &str| DateRange {
+            start: "2026-10-01".to_string(),
+            end: end.to_string(),
+            window: None,
+        };
+
+        assert!(range("2026-10-16").has_ended(today));
+        assert!(range("20261016").has_ended(today));
+        assert!(!range("2026-10-17").has_ended(today));
+        // Unreadable dates are reported by `dates`, not silently archived
+        assert!(!range("").has_ended(today));
+
+        let rolling = DateRange {
+            window: DateWindow::parse("relative:0:7"),
+            ..range("2020-01-01")
+        };
+        assert!(!rolling.has_ended(today));
+    }
+
+    #[test]
+    fn test_date_window_weekdays() {
+        let window = DateWindow::Weekdays {
+            weekdays: vec![Weekday::Fri, Weekday::Sat],
+            weeks: 2,
+        };
+        // A Saturday: the window covers it and the next Friday and Saturday
+        let today = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+
+        let dates: Vec<String> = window
+            .dates(today)
+            .iter()
+            .map(|d| d.format("%Y%m%d").to_string())
+            .collect();
+        assert_eq!(dates, vec!["20261017", "20261023", "20261024", "20261030"]);
+    }
+
+    #[test]
+    fn test_date_window_large_weeks() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+        let window = DateWindow::Weekdays {
+            weekdays: vec![Weekday::Fri],
+            weeks: u32::MAX,
+        };
+
+        assert!(window.validate().is_err());
+        // Kept within the longest window instead of wrapping around
+        let (start, end) = window.bounds(today);
+        assert_eq!(start, today);
+        assert_eq!(end, today + chrono::Days::new(u64::from(MAX_WINDOW_DAYS)));
+    }
+
+    #[test]
+    fn test_date_window_spec_round_trip() {
+        for spec in ["relative:3:30", "weekdays:fri,sat:8"] {
+            let window = DateWindow::parse(spec).unwrap();
+            assert_eq!(window.to_spec(), spec);
+        }
+
+        assert_eq!(
+            DateWindow::parse("weekdays:Sat, friday:8").map(|w| w.to_spec()),
+            Some("weekdays:fri,sat:8".to_string())
+        );
+        assert_eq!(
+            serde_json::to_string(&DateWindow::parse("relative:0:7").unwrap()).unwrap(),
+            "\"relative:0:7\""
+        );
+    }
+
+    #[test]
+    fn test_date_window_invalid_specs() {
+        for spec in [
+            "",
+            "relative:30:3",
+            "relative:0:400",
+            "relative:a:3",
+            "weekdays::8",
+            "weekdays:fri:0",
+            "weekdays:fri:53",
+            "weekdays:fri:613566757",
+            "weekdays:fri:4294967295",
+            "monthly:1:2",
+            "relative:0:7:extra",
+        ] {
+            assert!(DateWindow::parse(spec).is_none(), "{spec}");
+        }
+    }
+
     #[test]
     fn test_passenger_count_validation() {
         let valid = PassengerCount {
@@ -311,6 +1055,39 @@ mod tests {
         assert!(!filter.matches("10:01"));
     }
 
+    #[test]
+    fn test_channel_kind_round_trip() {
+        for kind in ChannelKind::ALL {
+            assert_eq!(ChannelKind::parse(kind.as_str()), Some(kind));
+        }
+        assert_eq!(ChannelKind::parse("carrier_pigeon"), None);
+    }
+
+    #[test]
+    fn test_route_status_round_trip() {
+        for status in RouteStatus::ALL {
+            assert_eq!(RouteStatus::parse(status.as_str()), Some(status));
+        }
+        assert_eq!(RouteStatus::parse("expired"), None);
+    }
+
+    #[test]
+    fn test_delivery_status_round_trip() {
+        for status in DeliveryStatus::ALL {
+            assert_eq!(DeliveryStatus::parse(status.as_str()), Some(status));
+        }
+        assert_eq!(DeliveryStatus::parse("lost"), None);
+    }
+
+    #[test]
+    fn test_locale_round_trip() {
+        for locale in Locale::ALL {
+            assert_eq!(Locale::parse(locale.as_str()), Some(locale));
+        }
+        assert_eq!(Locale::parse("de"), None);
+        assert_eq!(Locale::default(), Locale::Fr);
+    }
+
     #[test]
     fn test_time_filter_no_max() {
         let filter = TimeFilter {
@@ -322,4 +1099,187 @@ mod tests {
         assert!(filter.matches("23:59"));
         assert!(!filter.matches("07:59"));
     }
+
+    fn quiet_hours(start: &str, end: &str) -> QuietHours {
+        QuietHours {
+            start: start.to_string(),
+            end: end.to_string(),
+            time_zone: DEFAULT_TIME_ZONE.to_string(),
+        }
+    }
+
+    fn time(value: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(value, "%H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_quiet_hours_contains() {
+        let day = quiet_hours("13:00", "15:00");
+        assert!(day.contains(time("13:00")));
+        assert!(day.contains(time("14:59")));
+        assert!(!day.contains(time("15:00")));
+        assert!(!day.contains(time("12:59")));
+
+        let night = quiet_hours("23:00", "07:00");
+        assert!(night.contains(time("23:30")));
+        assert!(night.contains(time("00:00")));
+        assert!(night.contains(time("06:59")));
+        assert!(!night.contains(time("07:00")));
+        assert!(!night.contains(time("22:59")));
+    }
+
+    #[test]
+    #[cfg(feature = "ssr")]
+    fn test_quiet_hours_is_quiet_at_uses_zone() {
+        let night = quiet_hours("23:00", "07:00");
+        // 15:00 UTC is midnight in Tokyo
+        let now = chrono::DateTime::parse_from_rfc3339("2026-10-17T15:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        assert!(night.is_quiet_at(now));
+
+        let paris = QuietHours {
+            time_zone: "Europe/Paris".to_string(),
+            ..night
+        };
+        assert!(!paris.is_quiet_at(now));
+    }
+
+    #[test]
+    #[cfg(feature = "ssr")]
+    fn test_quiet_hours_quiet_until() {
+        let at = |value: &str| {
+            chrono::DateTime::parse_from_rfc3339(value)
+                .unwrap()
+                .with_timezone(&chrono::Utc)
+        };
+        let night = quiet_hours("23:00", "07:00");
+
+        // 23:30 in Tokyo ends the next morning
+        assert_eq!(
+            night.quiet_until(at("2026-10-17T14:30:00Z")),
+            Some(at("2026-10-17T22:00:00Z"))
+        );
+        // 03:00 in Tokyo ends the same morning
+        assert_eq!(
+            night.quiet_until(at("2026-10-17T18:00:00Z")),
+            Some(at("2026-10-17T22:00:00Z"))
+        );
+        // Noon in Tokyo is outside the period
+        assert_eq!(night.quiet_until(at("2026-10-17T03:00:00Z")), None);
+
+        let day = quiet_hours("13:00", "15:00");
+        assert_eq!(
+            day.quiet_until(at("2026-10-17T04:30:00Z")),
+            Some(at("2026-10-17T06:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn test_quiet_hours_validate() {
+        assert!(quiet_hours("23:00", "07:00").validate().is_ok());
+        assert!(quiet_hours("07:00", "07:00").validate().is_err());
+        assert!(quiet_hours("25:00", "07:00").validate().is_err());
+        #[cfg(feature = "ssr")]
+        assert!(
+            QuietHours {
+                time_zone: "Mars/Olympus".to_string(),
+                ..quiet_hours("23:00", "07:00")
+            }
+            .validate()
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_quiet_hours_from_parts() {
+        assert_eq!(
+            QuietHours::from_parts(Some("23:00"), Some("07:00"), None),
+            Some(quiet_hours("23:00", "07:00"))
+        );
+        assert_eq!(
+            QuietHours::from_parts(Some("23:00"), None, Some("Europe/Paris")),
+            None
+        );
+    }
+
+    fn test_plan(plan_id: u32, price: u32, remaining_seats: Option<u32>) -> PricingPlan {
+        PricingPlan {
+            plan_id,
+            plan_index: 0,
+            plan_name: String::new(),
+            price,
+            display_price: String::new(),
+            availability: SeatAvailability::Available { remaining_seats },
+            details: None,
+        }
+    }
+
+    #[test]
+    fn test_plan_filter_price_and_plan_lists() {
+        let filter = PlanFilter {
+            max_price: Some(8000),
+            plan_ids_allow: vec![1, 2],
+            plan_ids_deny: vec![2],
+            ..PlanFilter::default()
+        };
+
+        assert!(filter.matches(&test_plan(1, 8000, None)));
+        assert!(!filter.matches(&test_plan(1, 8001, None)));
+        assert!(!filter.matches(&test_plan(2, 5000, None)));
+        assert!(!filter.matches(&test_plan(3, 5000, None)));
+        // An unparsed price is not evidence the plan is too expensive
+        assert!(filter.matches(&test_plan(1, 0, None)));
+    }
+
+    #[test]
+    fn test_plan_filter_seat_type_and_remaining_seats() {
+        let filter = PlanFilter {
+            seat_type: Some(SeatType::ThreeRow),
+            min_remaining_seats: Some(3),
+            ..PlanFilter::default()
+        };
+
+        let mut plan = test_plan(1, 5000, Some(2));
+        assert!(!filter.matches(&plan));
+
+        plan.availability = SeatAvailability::Available {
+            remaining_seats: None,
+        };
+        assert!(filter.matches(&plan));
+
+        plan.details = Some(PlanDetails {
+            seat_type: SeatType::Standard,
+            ..PlanDetails::default()
+        });
+        assert!(!filter.matches(&plan));
+
+        plan.details = Some(PlanDetails {
+            seat_type: SeatType::ThreeRow,
+            ..PlanDetails::default()
+        });
+        assert!(filter.matches(&plan));
+    }
+
+    #[test]
+    fn test_plan_ids_round_trip() {
+        let ids = PlanFilter::parse_plan_ids("12345, 678 ,,9").unwrap();
+        assert_eq!(ids, vec![12345, 678, 9]);
+        assert_eq!(PlanFilter::format_plan_ids(&ids), "12345,678,9");
+        assert!(PlanFilter::parse_plan_ids("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_plan_ids_rejects_non_numbers() {
+        assert!(PlanFilter::parse_plan_ids("12345, abc").is_err());
+        assert!(PlanFilter::parse_plan_ids("-1").is_err());
+    }
+
+    #[test]
+    fn test_seat_type_round_trip() {
+        for seat_type in SeatType::ALL {
+            assert_eq!(SeatType::parse(seat_type.as_str()), Some(seat_type));
+        }
+        assert_eq!(SeatType::parse("hammock"), None);
+    }
 }
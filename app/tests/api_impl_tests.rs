@@ -10,15 +10,22 @@
     clippy::uninlined_format_args
 )]
 
-use app::api::{UserFormDto, UserRouteFormDto};
+use app::api::{NotificationChannelDto, UserFormDto, UserRouteFormDto};
 use app::api_impl::{
     create_user_impl, create_user_route_impl, delete_user_impl, delete_user_route_impl,
-    get_user_routes_impl, get_users_impl, parse_uuid, update_user_impl, update_user_route_impl,
-    user_route_to_dto, user_route_with_passengers_to_dto, user_to_dto,
+    get_alert_deliveries_impl, get_user_routes_impl, get_users_impl, parse_uuid,
+    pause_user_route_impl, set_user_route_enabled_impl, snooze_user_route_impl, update_user_impl,
+    update_user_route_impl, user_route_to_dto, user_route_with_passengers_to_dto, user_to_dto,
+};
+use app::entities::{notification_channels, user_passengers, user_routes, users};
+use app::notifier::{NotificationContext, QueuedAlert};
+use app::repositories::{mark_delivery_failed, record_alert};
+use app::types::{
+    ChannelKind, DateWindow, DeliveryStatus, Locale, NotificationChannel, PlanFilter, QuietHours,
+    SeatType,
 };
-use app::entities::{user_passengers, user_routes, users};
 use migration::{Migrator, MigratorTrait};
-use sea_orm::{ActiveModelTrait, Database, DatabaseConnection, Set};
+use sea_orm::{ActiveModelTrait, Database, DatabaseConnection, EntityTrait, Set};
 use uuid::Uuid;
 
 async fn setup_test_db() -> DatabaseConnection {
@@ -39,8 +46,11 @@ async fn create_test_user(db: &DatabaseConnection, email: &str) -> Uuid {
         enabled: Set(true),
         notify_on_change_only: Set(true),
         scrape_interval_secs: Set(300),
-        discord_webhook_url: Set(None),
         created_at: Set(chrono::Utc::now()),
+        quiet_hours_start: Set(None),
+        quiet_hours_end: Set(None),
+        quiet_hours_tz: Set(None),
+        locale: Set("fr".to_string()),
     };
     user.insert(db).await.expect("Failed to create test user");
     user_id
@@ -88,11 +98,32 @@ fn test_user_to_dto_converts_all_fields() {
         enabled: true,
         notify_on_change_only: false,
         scrape_interval_secs: 600,
-        discord_webhook_url: Some("https://discord.webhook".to_string()),
         created_at: now,
+        quiet_hours_start: None,
+        quiet_hours_end: None,
+        quiet_hours_tz: None,
+        locale: "fr".to_string(),
     };
-
-    let dto = user_to_dto(user);
+    let channels = vec![
+        notification_channels::Model {
+            id: Uuid::new_v4(),
+            user_id,
+            kind: "discord".to_string(),
+            target: "https://discord.webhook".to_string(),
+            enabled: true,
+            created_at: now,
+        },
+        notification_channels::Model {
+            id: Uuid::new_v4(),
+            user_id,
+            kind: "carrier_pigeon".to_string(),
+            target: "loft 3".to_string(),
+            enabled: true,
+            created_at: now,
+        },
+    ];
+
+    let dto = user_to_dto(user, channels);
 
     assert_eq!(dto.id, user_id.to_string());
     assert_eq!(dto.email, "test@example.com");
@@ -100,8 +131,12 @@ fn test_user_to_dto_converts_all_fields() {
     assert!(!dto.notify_on_change_only);
     assert_eq!(dto.scrape_interval_secs, 600);
     assert_eq!(
-        dto.discord_webhook_url,
-        Some("https://discord.webhook".to_string())
+        dto.notification_channels,
+        vec![NotificationChannelDto {
+            kind: ChannelKind::Discord,
+            target: "https://discord.webhook".to_string(),
+            enabled: true,
+        }]
     );
 }
 
@@ -118,9 +153,21 @@ fn test_user_route_to_dto_converts_all_fields() {
         arrival_station: "064".to_string(),
         date_start: "20250101".to_string(),
         date_end: "20250107".to_string(),
+        date_window: None,
         departure_time_min: Some("08:00".to_string()),
         departure_time_max: Some("18:00".to_string()),
         created_at: chrono::Utc::now(),
+        max_price: None,
+        seat_type: None,
+        plan_ids_allow: None,
+        plan_ids_deny: None,
+        min_remaining_seats: None,
+        status: "active".to_string(),
+        enabled: true,
+        paused_until: None,
+        scrape_interval_secs: None,
+        adaptive_interval: false,
+        notify_on_sold_out: false,
     };
 
     let dto = user_route_to_dto(route);
@@ -145,9 +192,21 @@ fn test_user_route_with_passengers_to_dto_with_none() {
         arrival_station: "064".to_string(),
         date_start: "20250101".to_string(),
         date_end: "20250107".to_string(),
+        date_window: None,
         departure_time_min: None,
         departure_time_max: None,
         created_at: chrono::Utc::now(),
+        max_price: None,
+        seat_type: None,
+        plan_ids_allow: None,
+        plan_ids_deny: None,
+        min_remaining_seats: None,
+        status: "active".to_string(),
+        enabled: true,
+        paused_until: None,
+        scrape_interval_secs: None,
+        adaptive_interval: false,
+        notify_on_sold_out: false,
     };
 
     let dto = user_route_with_passengers_to_dto(route, None);
@@ -175,9 +234,21 @@ fn test_user_route_with_passengers_to_dto_with_passengers() {
         arrival_station: "064".to_string(),
         date_start: "20250101".to_string(),
         date_end: "20250107".to_string(),
+        date_window: None,
         departure_time_min: None,
         departure_time_max: None,
         created_at: chrono::Utc::now(),
+        max_price: None,
+        seat_type: None,
+        plan_ids_allow: None,
+        plan_ids_deny: None,
+        min_remaining_seats: None,
+        status: "active".to_string(),
+        enabled: true,
+        paused_until: None,
+        scrape_interval_secs: None,
+        adaptive_interval: false,
+        notify_on_sold_out: false,
     };
 
     let passengers = user_passengers::Model {
@@ -231,7 +302,13 @@ async fn test_create_user_impl_success() {
         enabled: true,
         notify_on_change_only: false,
         scrape_interval_secs: 600,
-        discord_webhook_url: Some("https://webhook.url".to_string()),
+        quiet_hours: None,
+        locale: Locale::default(),
+        notification_channels: vec![NotificationChannelDto {
+            kind: ChannelKind::Discord,
+            target: "https://webhook.url".to_string(),
+            enabled: true,
+        }],
     };
 
     let user = create_user_impl(&db, form).await.unwrap();
@@ -240,14 +317,15 @@ async fn test_create_user_impl_success() {
     assert!(user.enabled);
     assert!(!user.notify_on_change_only);
     assert_eq!(user.scrape_interval_secs, 600);
+    assert_eq!(user.notification_channels.len(), 1);
     assert_eq!(
-        user.discord_webhook_url,
-        Some("https://webhook.url".to_string())
+        user.notification_channels[0].target,
+        "https://webhook.url".to_string()
     );
 }
 
 #[tokio::test]
-async fn test_create_user_impl_without_webhook() {
+async fn test_create_user_impl_without_channels() {
     let db = setup_test_db().await;
 
     let form = UserFormDto {
@@ -255,12 +333,46 @@ async fn test_create_user_impl_without_webhook() {
         enabled: true,
         notify_on_change_only: true,
         scrape_interval_secs: 300,
-        discord_webhook_url: None,
+        quiet_hours: None,
+        locale: Locale::default(),
+        notification_channels: vec![],
     };
 
     let user = create_user_impl(&db, form).await.unwrap();
 
-    assert!(user.discord_webhook_url.is_none());
+    assert!(user.notification_channels.is_empty());
+}
+
+#[tokio::test]
+async fn test_create_user_impl_quiet_hours() {
+    let db = setup_test_db().await;
+
+    let quiet_hours = QuietHours {
+        start: "22:30".to_string(),
+        end: "07:00".to_string(),
+        time_zone: "Europe/Paris".to_string(),
+    };
+    let mut form = UserFormDto {
+        email: "quiet@test.com".to_string(),
+        enabled: true,
+        notify_on_change_only: true,
+        scrape_interval_secs: 300,
+        quiet_hours: Some(quiet_hours.clone()),
+        locale: Locale::default(),
+        notification_channels: vec![],
+    };
+
+    let user = create_user_impl(&db, form.clone()).await.unwrap();
+    assert_eq!(user.quiet_hours, Some(quiet_hours));
+
+    form.email = "badzone@test.com".to_string();
+    form.quiet_hours = Some(QuietHours {
+        start: "22:30".to_string(),
+        end: "07:00".to_string(),
+        time_zone: "Mars/Olympus".to_string(),
+    });
+    assert!(create_user_impl(&db, form).await.is_err());
+    assert_eq!(get_users_impl(&db).await.unwrap().len(), 1);
 }
 
 #[tokio::test]
@@ -273,7 +385,13 @@ async fn test_update_user_impl_success() {
         enabled: false,
         notify_on_change_only: true,
         scrape_interval_secs: 900,
-        discord_webhook_url: Some("https://new.webhook".to_string()),
+        quiet_hours: None,
+        locale: Locale::default(),
+        notification_channels: vec![NotificationChannelDto {
+            kind: ChannelKind::Discord,
+            target: "https://new.webhook".to_string(),
+            enabled: true,
+        }],
     };
 
     let updated = update_user_impl(&db, user_id, form).await.unwrap();
@@ -282,6 +400,46 @@ async fn test_update_user_impl_success() {
     assert!(!updated.enabled);
     assert!(updated.notify_on_change_only);
     assert_eq!(updated.scrape_interval_secs, 900);
+    assert_eq!(updated.notification_channels.len(), 1);
+}
+
+#[tokio::test]
+async fn test_update_user_impl_replaces_channels() {
+    let db = setup_test_db().await;
+    let user_id = create_test_user(&db, "channels@test.com").await;
+
+    let form = |targets: &[&str]| UserFormDto {
+        email: "channels@test.com".to_string(),
+        enabled: true,
+        notify_on_change_only: true,
+        scrape_interval_secs: 300,
+        quiet_hours: None,
+        locale: Locale::default(),
+        notification_channels: targets
+            .iter()
+            .map(|t| NotificationChannelDto {
+                kind: ChannelKind::Discord,
+                target: (*t).to_string(),
+                enabled: true,
+            })
+            .collect(),
+    };
+
+    update_user_impl(&db, user_id, form(&["https://a", "https://b"]))
+        .await
+        .unwrap();
+    update_user_impl(&db, user_id, form(&["https://c", "  "]))
+        .await
+        .unwrap();
+
+    let users = get_users_impl(&db).await.unwrap();
+    let user = users.iter().find(|u| u.id == user_id.to_string()).unwrap();
+    let targets: Vec<_> = user
+        .notification_channels
+        .iter()
+        .map(|c| c.target.as_str())
+        .collect();
+    assert_eq!(targets, vec!["https://c"]);
 }
 
 #[tokio::test]
@@ -294,7 +452,9 @@ async fn test_update_user_impl_not_found() {
         enabled: true,
         notify_on_change_only: true,
         scrape_interval_secs: 300,
-        discord_webhook_url: None,
+        quiet_hours: None,
+        locale: Locale::default(),
+        notification_channels: vec![],
     };
 
     let result = update_user_impl(&db, non_existent_id, form).await;
@@ -342,8 +502,13 @@ async fn test_create_user_route_impl_success() {
         arrival_station: "064".to_string(),
         date_start: "20250101".to_string(),
         date_end: "20250107".to_string(),
+        date_window: None,
         departure_time_min: Some("08:00".to_string()),
         departure_time_max: Some("18:00".to_string()),
+        plan_filter: PlanFilter::default(),
+        scrape_interval_secs: None,
+        adaptive_interval: false,
+        notify_on_sold_out: false,
         adult_men: 2,
         adult_women: 1,
         child_men: 0,
@@ -375,8 +540,13 @@ async fn test_create_user_route_impl_invalid_user_id() {
         arrival_station: "064".to_string(),
         date_start: "20250101".to_string(),
         date_end: "20250107".to_string(),
+        date_window: None,
         departure_time_min: None,
         departure_time_max: None,
+        plan_filter: PlanFilter::default(),
+        scrape_interval_secs: None,
+        adaptive_interval: false,
+        notify_on_sold_out: false,
         adult_men: 1,
         adult_women: 0,
         child_men: 0,
@@ -406,8 +576,13 @@ async fn test_get_user_routes_impl_with_passengers() {
         arrival_station: "064".to_string(),
         date_start: "20250101".to_string(),
         date_end: "20250107".to_string(),
+        date_window: None,
         departure_time_min: None,
         departure_time_max: None,
+        plan_filter: PlanFilter::default(),
+        scrape_interval_secs: None,
+        adaptive_interval: false,
+        notify_on_sold_out: false,
         adult_men: 3,
         adult_women: 2,
         child_men: 1,
@@ -443,8 +618,13 @@ async fn test_update_user_route_impl_success() {
         arrival_station: "064".to_string(),
         date_start: "20250101".to_string(),
         date_end: "20250107".to_string(),
+        date_window: None,
         departure_time_min: None,
         departure_time_max: None,
+        plan_filter: PlanFilter::default(),
+        scrape_interval_secs: None,
+        adaptive_interval: false,
+        notify_on_sold_out: false,
         adult_men: 1,
         adult_women: 0,
         child_men: 0,
@@ -467,8 +647,13 @@ async fn test_update_user_route_impl_success() {
         arrival_station: "099".to_string(),
         date_start: "20250201".to_string(),
         date_end: "20250228".to_string(),
+        date_window: None,
         departure_time_min: Some("06:00".to_string()),
         departure_time_max: Some("22:00".to_string()),
+        plan_filter: PlanFilter::default(),
+        scrape_interval_secs: None,
+        adaptive_interval: false,
+        notify_on_sold_out: false,
         adult_men: 2,
         adult_women: 2,
         child_men: 0,
@@ -488,6 +673,214 @@ async fn test_update_user_route_impl_success() {
     assert_eq!(updated.departure_station, "010");
 }
 
+#[tokio::test]
+async fn test_user_route_plan_filter_persisted() {
+    let db = setup_test_db().await;
+    let user_id = create_test_user(&db, "planfilter@test.com").await;
+
+    let plan_filter = PlanFilter {
+        max_price: Some(8000),
+        seat_type: Some(SeatType::ThreeRow),
+        plan_ids_allow: vec![27775, 30000],
+        plan_ids_deny: Vec::new(),
+        min_remaining_seats: Some(2),
+    };
+    let mut form = UserRouteFormDto {
+        user_id: user_id.to_string(),
+        area_id: 100,
+        route_id: "155".to_string(),
+        departure_station: "001".to_string(),
+        arrival_station: "064".to_string(),
+        date_start: "20250101".to_string(),
+        date_end: "20250107".to_string(),
+        date_window: None,
+        departure_time_min: None,
+        departure_time_max: None,
+        plan_filter: plan_filter.clone(),
+        scrape_interval_secs: None,
+        adaptive_interval: false,
+        notify_on_sold_out: false,
+        adult_men: 1,
+        adult_women: 0,
+        child_men: 0,
+        child_women: 0,
+        handicap_adult_men: 0,
+        handicap_adult_women: 0,
+        handicap_child_men: 0,
+        handicap_child_women: 0,
+    };
+
+    let route = create_user_route_impl(&db, form.clone()).await.unwrap();
+    assert_eq!(route.plan_filter, plan_filter);
+
+    let routes = get_user_routes_impl(&db, user_id).await.unwrap();
+    assert_eq!(routes[0].plan_filter, plan_filter);
+
+    // Clearing the filter stores NULLs again
+    form.plan_filter = PlanFilter::default();
+    let route_uuid = parse_uuid(&route.id).unwrap();
+    update_user_route_impl(&db, route_uuid, form).await.unwrap();
+
+    let stored = user_routes::Entity::find_by_id(route_uuid)
+        .one(&db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.max_price, None);
+    assert_eq!(stored.seat_type, None);
+    assert_eq!(stored.plan_ids_allow, None);
+    assert_eq!(stored.min_remaining_seats, None);
+}
+
+#[tokio::test]
+async fn test_user_route_date_window_persisted() {
+    let db = setup_test_db().await;
+    let user_id = create_test_user(&db, "window@test.com").await;
+
+    let window = DateWindow::Weekdays {
+        weekdays: vec![chrono::Weekday::Fri, chrono::Weekday::Sat],
+        weeks: 8,
+    };
+    let mut form = UserRouteFormDto {
+        user_id: user_id.to_string(),
+        area_id: 100,
+        route_id: "155".to_string(),
+        departure_station: "001".to_string(),
+        arrival_station: "064".to_string(),
+        date_start: String::new(),
+        date_end: String::new(),
+        date_window: Some(window.clone()),
+        departure_time_min: None,
+        departure_time_max: None,
+        plan_filter: PlanFilter::default(),
+        scrape_interval_secs: None,
+        adaptive_interval: false,
+        notify_on_sold_out: false,
+        adult_men: 1,
+        adult_women: 0,
+        child_men: 0,
+        child_women: 0,
+        handicap_adult_men: 0,
+        handicap_adult_women: 0,
+        handicap_child_men: 0,
+        handicap_child_women: 0,
+    };
+
+    let route = create_user_route_impl(&db, form.clone()).await.unwrap();
+    assert_eq!(route.date_window, Some(window.clone()));
+
+    let route_uuid = parse_uuid(&route.id).unwrap();
+    let stored = user_routes::Entity::find_by_id(route_uuid)
+        .one(&db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.date_window.as_deref(), Some("weekdays:fri,sat:8"));
+
+    let routes = get_user_routes_impl(&db, user_id).await.unwrap();
+    assert_eq!(routes[0].date_window, Some(window));
+
+    // Back to fixed dates
+    form.date_window = None;
+    form.date_start = "20250101".to_string();
+    form.date_end = "20250107".to_string();
+    let updated = update_user_route_impl(&db, route_uuid, form).await.unwrap();
+    assert_eq!(updated.date_window, None);
+    assert_eq!(updated.date_start, "20250101");
+}
+
+#[tokio::test]
+async fn test_user_route_invalid_date_window_rejected() {
+    let db = setup_test_db().await;
+    let user_id = create_test_user(&db, "badwindow@test.com").await;
+
+    let form = UserRouteFormDto {
+        user_id: user_id.to_string(),
+        area_id: 100,
+        route_id: "155".to_string(),
+        departure_station: "001".to_string(),
+        arrival_station: "064".to_string(),
+        date_start: String::new(),
+        date_end: String::new(),
+        date_window: Some(DateWindow::Relative {
+            start_days: 30,
+            end_days: 3,
+        }),
+        departure_time_min: None,
+        departure_time_max: None,
+        plan_filter: PlanFilter::default(),
+        scrape_interval_secs: None,
+        adaptive_interval: false,
+        notify_on_sold_out: false,
+        adult_men: 1,
+        adult_women: 0,
+        child_men: 0,
+        child_women: 0,
+        handicap_adult_men: 0,
+        handicap_adult_women: 0,
+        handicap_child_men: 0,
+        handicap_child_women: 0,
+    };
+
+    assert!(create_user_route_impl(&db, form).await.is_err());
+    assert!(get_user_routes_impl(&db, user_id).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_user_route_polling_persisted() {
+    let db = setup_test_db().await;
+    let user_id = create_test_user(&db, "polling@test.com").await;
+
+    let mut form = UserRouteFormDto {
+        user_id: user_id.to_string(),
+        area_id: 100,
+        route_id: "155".to_string(),
+        departure_station: "001".to_string(),
+        arrival_station: "064".to_string(),
+        date_start: "20250101".to_string(),
+        date_end: "20250107".to_string(),
+        date_window: None,
+        departure_time_min: None,
+        departure_time_max: None,
+        plan_filter: PlanFilter::default(),
+        scrape_interval_secs: Some(120),
+        adaptive_interval: true,
+        notify_on_sold_out: false,
+        adult_men: 1,
+        adult_women: 0,
+        child_men: 0,
+        child_women: 0,
+        handicap_adult_men: 0,
+        handicap_adult_women: 0,
+        handicap_child_men: 0,
+        handicap_child_women: 0,
+    };
+
+    let route = create_user_route_impl(&db, form.clone()).await.unwrap();
+    assert_eq!(route.scrape_interval_secs, Some(120));
+    assert!(route.adaptive_interval);
+
+    let route_uuid = parse_uuid(&route.id).unwrap();
+    form.scrape_interval_secs = Some(0);
+    assert!(
+        update_user_route_impl(&db, route_uuid, form.clone())
+            .await
+            .is_err()
+    );
+    form.scrape_interval_secs = Some(30);
+    assert!(
+        update_user_route_impl(&db, route_uuid, form.clone())
+            .await
+            .is_err()
+    );
+
+    form.scrape_interval_secs = None;
+    form.adaptive_interval = false;
+    let updated = update_user_route_impl(&db, route_uuid, form).await.unwrap();
+    assert_eq!(updated.scrape_interval_secs, None);
+    assert!(!updated.adaptive_interval);
+}
+
 #[tokio::test]
 async fn test_update_user_route_impl_not_found() {
     let db = setup_test_db().await;
@@ -501,8 +894,13 @@ async fn test_update_user_route_impl_not_found() {
         arrival_station: "064".to_string(),
         date_start: "20250101".to_string(),
         date_end: "20250107".to_string(),
+        date_window: None,
         departure_time_min: None,
         departure_time_max: None,
+        plan_filter: PlanFilter::default(),
+        scrape_interval_secs: None,
+        adaptive_interval: false,
+        notify_on_sold_out: false,
         adult_men: 1,
         adult_women: 0,
         child_men: 0,
@@ -534,8 +932,13 @@ async fn test_delete_user_route_impl_success() {
         arrival_station: "064".to_string(),
         date_start: "20250101".to_string(),
         date_end: "20250107".to_string(),
+        date_window: None,
         departure_time_min: None,
         departure_time_max: None,
+        plan_filter: PlanFilter::default(),
+        scrape_interval_secs: None,
+        adaptive_interval: false,
+        notify_on_sold_out: false,
         adult_men: 1,
         adult_women: 0,
         child_men: 0,
@@ -558,6 +961,110 @@ async fn test_delete_user_route_impl_success() {
     assert!(routes.is_empty());
 }
 
+#[tokio::test]
+async fn test_snooze_and_resume_user_route() {
+    let db = setup_test_db().await;
+    let user_id = create_test_user(&db, "snooze@test.com").await;
+
+    let form = UserRouteFormDto {
+        user_id: user_id.to_string(),
+        area_id: 100,
+        route_id: "155".to_string(),
+        departure_station: "001".to_string(),
+        arrival_station: "064".to_string(),
+        date_start: "20250101".to_string(),
+        date_end: "20250107".to_string(),
+        date_window: None,
+        departure_time_min: None,
+        departure_time_max: None,
+        plan_filter: PlanFilter::default(),
+        scrape_interval_secs: None,
+        adaptive_interval: false,
+        notify_on_sold_out: false,
+        adult_men: 1,
+        adult_women: 0,
+        child_men: 0,
+        child_women: 0,
+        handicap_adult_men: 0,
+        handicap_adult_women: 0,
+        handicap_child_men: 0,
+        handicap_child_women: 0,
+    };
+
+    let route = create_user_route_impl(&db, form).await.unwrap();
+    assert!(route.enabled);
+    assert_eq!(route.paused_until, None);
+    let route_uuid = parse_uuid(&route.id).unwrap();
+
+    let snoozed = snooze_user_route_impl(&db, route_uuid).await.unwrap();
+    let until = chrono::DateTime::parse_from_rfc3339(&snoozed.paused_until.unwrap()).unwrap();
+    let minutes = (until.with_timezone(&chrono::Utc) - chrono::Utc::now()).num_minutes();
+    assert!((23 * 60..=24 * 60).contains(&minutes), "{minutes}");
+
+    let resumed = pause_user_route_impl(&db, route_uuid, None).await.unwrap();
+    assert_eq!(resumed.paused_until, None);
+
+    // A pause that has already ended is not reported
+    let past = chrono::Utc::now() - chrono::Duration::minutes(5);
+    let expired = pause_user_route_impl(&db, route_uuid, Some(past))
+        .await
+        .unwrap();
+    assert_eq!(expired.paused_until, None);
+
+    let result = snooze_user_route_impl(&db, Uuid::new_v4()).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_set_user_route_enabled() {
+    let db = setup_test_db().await;
+    let user_id = create_test_user(&db, "toggle@test.com").await;
+
+    let form = UserRouteFormDto {
+        user_id: user_id.to_string(),
+        area_id: 100,
+        route_id: "155".to_string(),
+        departure_station: "001".to_string(),
+        arrival_station: "064".to_string(),
+        date_start: "20250101".to_string(),
+        date_end: "20250107".to_string(),
+        date_window: None,
+        departure_time_min: None,
+        departure_time_max: None,
+        plan_filter: PlanFilter::default(),
+        scrape_interval_secs: None,
+        adaptive_interval: false,
+        notify_on_sold_out: false,
+        adult_men: 1,
+        adult_women: 0,
+        child_men: 0,
+        child_women: 0,
+        handicap_adult_men: 0,
+        handicap_adult_women: 0,
+        handicap_child_men: 0,
+        handicap_child_women: 0,
+    };
+
+    let route = create_user_route_impl(&db, form).await.unwrap();
+    let route_uuid = parse_uuid(&route.id).unwrap();
+    snooze_user_route_impl(&db, route_uuid).await.unwrap();
+
+    let disabled = set_user_route_enabled_impl(&db, route_uuid, false)
+        .await
+        .unwrap();
+    assert!(!disabled.enabled);
+
+    let routes = get_user_routes_impl(&db, user_id).await.unwrap();
+    assert!(!routes[0].enabled);
+
+    // Switching a route back on also ends its pause
+    let enabled = set_user_route_enabled_impl(&db, route_uuid, true)
+        .await
+        .unwrap();
+    assert!(enabled.enabled);
+    assert_eq!(enabled.paused_until, None);
+}
+
 #[tokio::test]
 async fn test_multiple_routes_per_user() {
     let db = setup_test_db().await;
@@ -573,8 +1080,13 @@ async fn test_multiple_routes_per_user() {
             arrival_station: "064".to_string(),
             date_start: "20250101".to_string(),
             date_end: "20250107".to_string(),
+            date_window: None,
             departure_time_min: None,
             departure_time_max: None,
+            plan_filter: PlanFilter::default(),
+            scrape_interval_secs: None,
+            adaptive_interval: false,
+            notify_on_sold_out: false,
             adult_men: 1,
             adult_women: 0,
             child_men: 0,
@@ -591,3 +1103,113 @@ async fn test_multiple_routes_per_user() {
 
     assert_eq!(routes.len(), 3);
 }
+
+// === Alert Delivery Tests ===
+
+#[tokio::test]
+async fn test_get_alert_deliveries_impl() {
+    let db = setup_test_db().await;
+    let user_id = create_test_user(&db, "alerts@test.com").await;
+    let other_user_id = create_test_user(&db, "quiet@test.com").await;
+
+    let form = UserRouteFormDto {
+        user_id: user_id.to_string(),
+        area_id: 100,
+        route_id: "155".to_string(),
+        departure_station: "001".to_string(),
+        arrival_station: "064".to_string(),
+        date_start: "20250101".to_string(),
+        date_end: "20250107".to_string(),
+        date_window: None,
+        departure_time_min: None,
+        departure_time_max: None,
+        plan_filter: PlanFilter::default(),
+        scrape_interval_secs: None,
+        adaptive_interval: false,
+        notify_on_sold_out: false,
+        adult_men: 1,
+        adult_women: 0,
+        child_men: 0,
+        child_women: 0,
+        handicap_adult_men: 0,
+        handicap_adult_women: 0,
+        handicap_child_men: 0,
+        handicap_child_women: 0,
+    };
+    let route = create_user_route_impl(&db, form).await.unwrap();
+    let route_id = parse_uuid(&route.id).unwrap();
+
+    let alert = QueuedAlert {
+        schedules: vec![],
+        changes: vec![],
+        context: NotificationContext {
+            departure_station_name: "Tokyo".to_string(),
+            arrival_station_name: "Osaka".to_string(),
+            route_name: String::new(),
+            date_range: ("20250101".to_string(), "20250107".to_string()),
+            passenger_count: 1,
+            time_filter: None,
+            snooze_url: None,
+            availability_url: None,
+            locale: Locale::default(),
+        },
+    };
+    let channels = [
+        NotificationChannel {
+            kind: ChannelKind::Discord,
+            target: "https://discord.com/api/webhooks/1/a".to_string(),
+        },
+        NotificationChannel {
+            kind: ChannelKind::Email,
+            target: "alerts@test.com".to_string(),
+        },
+    ];
+    record_alert(
+        &db,
+        route_id,
+        "hash".to_string(),
+        &[],
+        &channels,
+        &alert,
+        None,
+    )
+    .await
+    .unwrap();
+
+    let deliveries = get_alert_deliveries_impl(&db, user_id).await.unwrap();
+    assert_eq!(deliveries.len(), 2);
+    assert!(
+        deliveries
+            .iter()
+            .all(|d| d.status == DeliveryStatus::Pending && d.user_route_id == route_id.to_string())
+    );
+    assert_eq!(deliveries[0].departure_station_name, "Tokyo");
+    assert_eq!(deliveries[0].arrival_station_name, "Osaka");
+
+    let email = deliveries
+        .iter()
+        .find(|d| d.channel_kind == ChannelKind::Email)
+        .unwrap();
+    mark_delivery_failed(
+        &db,
+        Uuid::parse_str(&email.id).unwrap(),
+        11,
+        0,
+        "SMTP delivery failed",
+        None,
+    )
+    .await
+    .unwrap();
+
+    let deliveries = get_alert_deliveries_impl(&db, user_id).await.unwrap();
+    let email = deliveries
+        .iter()
+        .find(|d| d.channel_kind == ChannelKind::Email)
+        .unwrap();
+    assert_eq!(email.status, DeliveryStatus::Failed);
+    assert_eq!(email.attempts, 11);
+    assert_eq!(email.last_error.as_deref(), Some("SMTP delivery failed"));
+
+    let others = get_alert_deliveries_impl(&db, other_user_id).await.unwrap();
+    assert!(others.is_empty());
+}
@@ -45,8 +45,11 @@ async fn test_create_user() {
         enabled: Set(true),
         notify_on_change_only: Set(false),
         scrape_interval_secs: Set(300),
-        discord_webhook_url: Set(Some("https://discord.com/webhook".to_string())),
         created_at: Set(chrono::Utc::now()),
+        quiet_hours_start: Set(None),
+        quiet_hours_end: Set(None),
+        quiet_hours_tz: Set(None),
+        locale: Set("fr".to_string()),
     };
 
     let user = new_user.insert(&db).await.unwrap();
@@ -56,10 +59,6 @@ async fn test_create_user() {
     assert!(user.enabled);
     assert!(!user.notify_on_change_only);
     assert_eq!(user.scrape_interval_secs, 300);
-    assert_eq!(
-        user.discord_webhook_url,
-        Some("https://discord.com/webhook".to_string())
-    );
 }
 
 #[tokio::test]
@@ -74,8 +73,11 @@ async fn test_get_users_returns_all() {
             enabled: Set(true),
             notify_on_change_only: Set(false),
             scrape_interval_secs: Set(300),
-            discord_webhook_url: Set(None),
             created_at: Set(chrono::Utc::now()),
+            quiet_hours_start: Set(None),
+            quiet_hours_end: Set(None),
+            quiet_hours_tz: Set(None),
+            locale: Set("fr".to_string()),
         };
         new_user.insert(&db).await.unwrap();
     }
@@ -96,8 +98,11 @@ async fn test_update_user() {
         enabled: Set(false),
         notify_on_change_only: Set(false),
         scrape_interval_secs: Set(300),
-        discord_webhook_url: Set(None),
         created_at: Set(chrono::Utc::now()),
+        quiet_hours_start: Set(None),
+        quiet_hours_end: Set(None),
+        quiet_hours_tz: Set(None),
+        locale: Set("fr".to_string()),
     };
     new_user.insert(&db).await.unwrap();
 
@@ -108,7 +113,6 @@ async fn test_update_user() {
     active_user.enabled = Set(true);
     active_user.notify_on_change_only = Set(true);
     active_user.scrape_interval_secs = Set(600);
-    active_user.discord_webhook_url = Set(Some("https://new-webhook.com".to_string()));
 
     let updated_user = active_user.update(&db).await.unwrap();
 
@@ -116,10 +120,6 @@ async fn test_update_user() {
     assert!(updated_user.enabled);
     assert!(updated_user.notify_on_change_only);
     assert_eq!(updated_user.scrape_interval_secs, 600);
-    assert_eq!(
-        updated_user.discord_webhook_url,
-        Some("https://new-webhook.com".to_string())
-    );
 }
 
 #[tokio::test]
@@ -133,8 +133,11 @@ async fn test_delete_user() {
         enabled: Set(true),
         notify_on_change_only: Set(false),
         scrape_interval_secs: Set(300),
-        discord_webhook_url: Set(None),
         created_at: Set(chrono::Utc::now()),
+        quiet_hours_start: Set(None),
+        quiet_hours_end: Set(None),
+        quiet_hours_tz: Set(None),
+        locale: Set("fr".to_string()),
     };
     new_user.insert(&db).await.unwrap();
 
@@ -172,8 +175,11 @@ async fn create_test_user(db: &DatabaseConnection) -> Uuid {
         enabled: Set(true),
         notify_on_change_only: Set(false),
         scrape_interval_secs: Set(300),
-        discord_webhook_url: Set(None),
         created_at: Set(chrono::Utc::now()),
+        quiet_hours_start: Set(None),
+        quiet_hours_end: Set(None),
+        quiet_hours_tz: Set(None),
+        locale: Set("fr".to_string()),
     };
     new_user.insert(db).await.unwrap();
     user_id
@@ -197,6 +203,18 @@ async fn test_create_user_route_with_passengers() {
         departure_time_min: Set(Some("08:00".to_string())),
         departure_time_max: Set(Some("18:00".to_string())),
         created_at: Set(chrono::Utc::now()),
+        max_price: Set(None),
+        seat_type: Set(None),
+        plan_ids_allow: Set(None),
+        plan_ids_deny: Set(None),
+        min_remaining_seats: Set(None),
+        date_window: Set(None),
+        status: Set("active".to_string()),
+        enabled: Set(true),
+        paused_until: Set(None),
+        scrape_interval_secs: Set(None),
+        adaptive_interval: Set(false),
+        notify_on_sold_out: Set(false),
     };
     let route = new_route.insert(&db).await.unwrap();
 
@@ -249,6 +267,18 @@ async fn test_get_user_routes_by_user_id() {
             departure_time_min: Set(None),
             departure_time_max: Set(None),
             created_at: Set(chrono::Utc::now()),
+            max_price: Set(None),
+            seat_type: Set(None),
+            plan_ids_allow: Set(None),
+            plan_ids_deny: Set(None),
+            min_remaining_seats: Set(None),
+            date_window: Set(None),
+            status: Set("active".to_string()),
+            enabled: Set(true),
+            paused_until: Set(None),
+            scrape_interval_secs: Set(None),
+            adaptive_interval: Set(false),
+            notify_on_sold_out: Set(false),
         };
         new_route.insert(&db).await.unwrap();
 
@@ -300,6 +330,18 @@ async fn test_update_user_route() {
         departure_time_min: Set(None),
         departure_time_max: Set(None),
         created_at: Set(chrono::Utc::now()),
+        max_price: Set(None),
+        seat_type: Set(None),
+        plan_ids_allow: Set(None),
+        plan_ids_deny: Set(None),
+        min_remaining_seats: Set(None),
+        date_window: Set(None),
+        status: Set("active".to_string()),
+        enabled: Set(true),
+        paused_until: Set(None),
+        scrape_interval_secs: Set(None),
+        adaptive_interval: Set(false),
+        notify_on_sold_out: Set(false),
     };
     new_route.insert(&db).await.unwrap();
 
@@ -349,6 +391,18 @@ async fn test_update_passengers() {
         departure_time_min: Set(None),
         departure_time_max: Set(None),
         created_at: Set(chrono::Utc::now()),
+        max_price: Set(None),
+        seat_type: Set(None),
+        plan_ids_allow: Set(None),
+        plan_ids_deny: Set(None),
+        min_remaining_seats: Set(None),
+        date_window: Set(None),
+        status: Set("active".to_string()),
+        enabled: Set(true),
+        paused_until: Set(None),
+        scrape_interval_secs: Set(None),
+        adaptive_interval: Set(false),
+        notify_on_sold_out: Set(false),
     };
     new_route.insert(&db).await.unwrap();
 
@@ -408,6 +462,18 @@ async fn test_delete_user_route() {
         departure_time_min: Set(None),
         departure_time_max: Set(None),
         created_at: Set(chrono::Utc::now()),
+        max_price: Set(None),
+        seat_type: Set(None),
+        plan_ids_allow: Set(None),
+        plan_ids_deny: Set(None),
+        min_remaining_seats: Set(None),
+        date_window: Set(None),
+        status: Set("active".to_string()),
+        enabled: Set(true),
+        paused_until: Set(None),
+        scrape_interval_secs: Set(None),
+        adaptive_interval: Set(false),
+        notify_on_sold_out: Set(false),
     };
     new_route.insert(&db).await.unwrap();
 
@@ -484,6 +550,18 @@ async fn test_delete_user_does_not_cascade_to_routes() {
         departure_time_min: Set(None),
         departure_time_max: Set(None),
         created_at: Set(chrono::Utc::now()),
+        max_price: Set(None),
+        seat_type: Set(None),
+        plan_ids_allow: Set(None),
+        plan_ids_deny: Set(None),
+        min_remaining_seats: Set(None),
+        date_window: Set(None),
+        status: Set("active".to_string()),
+        enabled: Set(true),
+        paused_until: Set(None),
+        scrape_interval_secs: Set(None),
+        adaptive_interval: Set(false),
+        notify_on_sold_out: Set(false),
     };
     new_route.insert(&db).await.unwrap();
 
@@ -518,6 +596,18 @@ async fn test_route_with_no_time_filter() {
         departure_time_min: Set(None),
         departure_time_max: Set(None),
         created_at: Set(chrono::Utc::now()),
+        max_price: Set(None),
+        seat_type: Set(None),
+        plan_ids_allow: Set(None),
+        plan_ids_deny: Set(None),
+        min_remaining_seats: Set(None),
+        date_window: Set(None),
+        status: Set("active".to_string()),
+        enabled: Set(true),
+        paused_until: Set(None),
+        scrape_interval_secs: Set(None),
+        adaptive_interval: Set(false),
+        notify_on_sold_out: Set(false),
     };
     let route = new_route.insert(&db).await.unwrap();
 
@@ -543,6 +633,18 @@ async fn test_route_with_only_min_time() {
         departure_time_min: Set(Some("10:00".to_string())),
         departure_time_max: Set(None),
         created_at: Set(chrono::Utc::now()),
+        max_price: Set(None),
+        seat_type: Set(None),
+        plan_ids_allow: Set(None),
+        plan_ids_deny: Set(None),
+        min_remaining_seats: Set(None),
+        date_window: Set(None),
+        status: Set("active".to_string()),
+        enabled: Set(true),
+        paused_until: Set(None),
+        scrape_interval_secs: Set(None),
+        adaptive_interval: Set(false),
+        notify_on_sold_out: Set(false),
     };
     let route = new_route.insert(&db).await.unwrap();
 
@@ -568,6 +670,18 @@ async fn test_route_with_only_max_time() {
         departure_time_min: Set(None),
         departure_time_max: Set(Some("18:00".to_string())),
         created_at: Set(chrono::Utc::now()),
+        max_price: Set(None),
+        seat_type: Set(None),
+        plan_ids_allow: Set(None),
+        plan_ids_deny: Set(None),
+        min_remaining_seats: Set(None),
+        date_window: Set(None),
+        status: Set("active".to_string()),
+        enabled: Set(true),
+        paused_until: Set(None),
+        scrape_interval_secs: Set(None),
+        adaptive_interval: Set(false),
+        notify_on_sold_out: Set(false),
     };
     let route = new_route.insert(&db).await.unwrap();
 
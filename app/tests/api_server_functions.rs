@@ -23,6 +23,7 @@
 use app::{
     db,
     entities::{user_passengers, user_routes, users},
+    retry::RetryPolicy,
     scraper::BusScraper,
 };
 use axum::{
@@ -55,7 +56,15 @@ async fn setup_test_db() -> DatabaseConnection {
 }
 
 async fn setup_test_app(db: DatabaseConnection, mock_server: &MockServer) -> Router {
-    let scraper = Arc::new(BusScraper::new(mock_server.uri()).unwrap());
+    // Error responses fail at once instead of waiting out the backoff
+    let scraper = Arc::new(
+        BusScraper::new(mock_server.uri())
+            .unwrap()
+            .with_retry_policy(RetryPolicy {
+                max_retries: 0,
+                ..RetryPolicy::default()
+            }),
+    );
 
     let db_clone = db.clone();
     let scraper_clone = scraper.clone();
@@ -109,8 +118,11 @@ async fn create_test_user(db: &DatabaseConnection, email: &str) -> Uuid {
         enabled: Set(true),
         notify_on_change_only: Set(false),
         scrape_interval_secs: Set(300),
-        discord_webhook_url: Set(None),
         created_at: Set(chrono::Utc::now()),
+        quiet_hours_start: Set(None),
+        quiet_hours_end: Set(None),
+        quiet_hours_tz: Set(None),
+        locale: Set("fr".to_string()),
     };
     new_user.insert(db).await.unwrap();
     user_id
@@ -134,6 +146,18 @@ async fn create_test_user_route(
         departure_time_min: Set(Some("08:00".to_string())),
         departure_time_max: Set(Some("18:00".to_string())),
         created_at: Set(chrono::Utc::now()),
+        max_price: Set(None),
+        seat_type: Set(None),
+        plan_ids_allow: Set(None),
+        plan_ids_deny: Set(None),
+        min_remaining_seats: Set(None),
+        date_window: Set(None),
+        status: Set("active".to_string()),
+        enabled: Set(true),
+        paused_until: Set(None),
+        scrape_interval_secs: Set(None),
+        adaptive_interval: Set(false),
+        notify_on_sold_out: Set(false),
     };
     new_route.insert(db).await.unwrap();
 
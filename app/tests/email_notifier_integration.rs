@@ -0,0 +1,253 @@
+//! Integration tests for the email notifier
+//!
+//! Runs the notifier against a minimal in-process SMTP server that records
+//! every message it accepts
+#![allow(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::doc_markdown,
+    clippy::uninlined_format_args
+)]
+
+use app::notifier::{
+    EmailNotifier, NotificationContext, NotificationDispatcher, Notifier, SmtpConfig, SmtpSecurity,
+};
+use app::types::{
+    BusSchedule, ChannelKind, Locale, NotificationChannel, PricingPlan, SeatAvailability,
+};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+
+/// A message accepted by the fake server
+#[derive(Debug, Clone)]
+struct ReceivedMail {
+    rcpt_to: Vec<String>,
+    data: String,
+}
+
+/// Start a fake SMTP server on a random port.
+///
+/// Speaks just enough SMTP for lettre: greeting, EHLO, MAIL, RCPT, DATA, QUIT.
+async fn start_fake_smtp() -> (u16, Arc<Mutex<Vec<ReceivedMail>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let mails = Arc::new(Mutex::new(Vec::new()));
+    let received = Arc::clone(&mails);
+
+    tokio::spawn(async move {
+        loop {
+            let Ok((socket, _)) = listener.accept().await else {
+                return;
+            };
+            let received = Arc::clone(&received);
+
+            tokio::spawn(async move {
+                let (reader, mut writer) = socket.into_split();
+                let mut lines = BufReader::new(reader).lines();
+                let mut rcpt_to = Vec::new();
+
+                writer.write_all(b"220 fake-smtp ready\r\n").await.unwrap();
+
+                while let Ok(Some(line)) = lines.next_line().await {
+                    let command = line.to_ascii_uppercase();
+                    if command.starts_with("EHLO") || command.starts_with("HELO") {
+                        writer
+                            .write_all(b"250-fake-smtp\r\n250 8BITMIME\r\n")
+                            .await
+                            .unwrap();
+                    } else if command.starts_with("RCPT TO:") {
+                        rcpt_to.push(line[8..].trim().to_string());
+                        writer.write_all(b"250 OK\r\n").await.unwrap();
+                    } else if command == "DATA" {
+                        writer.write_all(b"354 End data with .\r\n").await.unwrap();
+                        let mut data = String::new();
+                        while let Ok(Some(data_line)) = lines.next_line().await {
+                            if data_line == "." {
+                                break;
+                            }
+                            data.push_str(&data_line);
+                            data.push('\n');
+                        }
+                        received.lock().unwrap().push(ReceivedMail {
+                            rcpt_to: std::mem::take(&mut rcpt_to),
+                            data,
+                        });
+                        writer.write_all(b"250 Queued\r\n").await.unwrap();
+                    } else if command == "QUIT" {
+                        writer.write_all(b"221 Bye\r\n").await.unwrap();
+                        return;
+                    } else {
+                        writer.write_all(b"250 OK\r\n").await.unwrap();
+                    }
+                }
+            });
+        }
+    });
+
+    (port, mails)
+}
+
+fn smtp_config(port: u16) -> SmtpConfig {
+    SmtpConfig {
+        host: "127.0.0.1".to_string(),
+        port,
+        security: SmtpSecurity::None,
+        username: None,
+        password: None,
+        from: "Bus Scraper <bus@example.com>".to_string(),
+    }
+}
+
+fn test_bus_schedule() -> BusSchedule {
+    BusSchedule {
+        bus_number: "Bus_1".to_string(),
+        route_name: "Test Route".to_string(),
+        departure_station: "001".to_string(),
+        departure_date: "20250115".to_string(),
+        departure_time: "08:30".to_string(),
+        arrival_station: "064".to_string(),
+        arrival_date: "20250115".to_string(),
+        arrival_time: "10:45".to_string(),
+        way_no: 1,
+        available_plans: vec![PricingPlan {
+            plan_id: 12345,
+            plan_index: 0,
+            plan_name: "Standard".to_string(),
+            price: 2100,
+            display_price: "2,100円".to_string(),
+            availability: SeatAvailability::Available {
+                remaining_seats: Some(5),
+            },
+            details: None,
+        }],
+        booking_url: None,
+    }
+}
+
+fn test_context() -> NotificationContext {
+    NotificationContext {
+        departure_station_name: "Shinjuku".to_string(),
+        arrival_station_name: "Kawaguchiko".to_string(),
+        route_name: String::new(),
+        date_range: ("20250115".to_string(), "20250120".to_string()),
+        passenger_count: 2,
+        time_filter: None,
+        snooze_url: None,
+        availability_url: None,
+        locale: Locale::default(),
+    }
+}
+
+#[tokio::test]
+async fn test_send_availability_alert_delivers_multipart_email() {
+    let (port, mails) = start_fake_smtp().await;
+    let notifier = EmailNotifier::new(&smtp_config(port)).unwrap();
+
+    let result = notifier
+        .send_availability_alert(
+            "user@example.com",
+            &[test_bus_schedule()],
+            &[],
+            &test_context(),
+        )
+        .await;
+
+    assert!(result.is_ok());
+    let mails = mails.lock().unwrap();
+    assert_eq!(mails.len(), 1);
+    assert_eq!(mails[0].rcpt_to, vec!["<user@example.com>"]);
+
+    let data = &mails[0].data;
+    assert!(data.contains("From: \"Bus Scraper\" <bus@example.com>"));
+    assert!(data.contains("To: user@example.com"));
+    assert!(data.contains("multipart/alternative"));
+    assert!(data.contains("text/plain"));
+    assert!(data.contains("text/html"));
+}
+
+#[tokio::test]
+async fn test_send_startup_notification_delivers_email() {
+    let (port, mails) = start_fake_smtp().await;
+    let notifier = EmailNotifier::new(&smtp_config(port)).unwrap();
+
+    let result = notifier
+        .send_startup_notification("user@example.com", 2, 3, Locale::Fr)
+        .await;
+
+    assert!(result.is_ok());
+    assert_eq!(mails.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_send_availability_alert_empty_schedules() {
+    let (port, mails) = start_fake_smtp().await;
+    let notifier = EmailNotifier::new(&smtp_config(port)).unwrap();
+
+    let result = notifier
+        .send_availability_alert("user@example.com", &[], &[], &test_context())
+        .await;
+
+    assert!(result.is_ok());
+    assert!(mails.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_send_availability_alert_invalid_address_returns_error() {
+    let (port, mails) = start_fake_smtp().await;
+    let notifier = EmailNotifier::new(&smtp_config(port)).unwrap();
+
+    let result = notifier
+        .send_availability_alert(
+            "not an address",
+            &[test_bus_schedule()],
+            &[],
+            &test_context(),
+        )
+        .await;
+
+    assert!(result.is_err());
+    assert!(mails.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_send_availability_alert_unreachable_server_reported() {
+    // Bind then drop to get a port nothing listens on
+    let port = {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        listener.local_addr().unwrap().port()
+    };
+    let notifier = EmailNotifier::new(&smtp_config(port)).unwrap();
+
+    let result = notifier
+        .send_availability_alert(
+            "user@example.com",
+            &[test_bus_schedule()],
+            &[],
+            &test_context(),
+        )
+        .await;
+
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_dispatcher_routes_email_channel() {
+    let (port, mails) = start_fake_smtp().await;
+    let dispatcher = NotificationDispatcher::empty().with(
+        ChannelKind::Email,
+        EmailNotifier::new(&smtp_config(port)).unwrap(),
+    );
+    let channels = vec![NotificationChannel {
+        kind: ChannelKind::Email,
+        target: "user@example.com".to_string(),
+    }];
+
+    let result = dispatcher
+        .send_availability_alert(&channels, &[test_bus_schedule()], &[], &test_context())
+        .await;
+
+    assert!(result.is_ok());
+    assert_eq!(mails.lock().unwrap().len(), 1);
+}
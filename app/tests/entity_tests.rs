@@ -35,8 +35,11 @@ async fn create_test_user(db: &sea_orm::DatabaseConnection, email: &str) -> Uuid
         enabled: Set(true),
         notify_on_change_only: Set(true),
         scrape_interval_secs: Set(300),
-        discord_webhook_url: Set(Some("https://discord.com/webhook".to_string())),
         created_at: Set(chrono::Utc::now()),
+        quiet_hours_start: Set(None),
+        quiet_hours_end: Set(None),
+        quiet_hours_tz: Set(None),
+        locale: Set("fr".to_string()),
     };
     user.insert(db).await.unwrap();
     user_id
@@ -61,6 +64,18 @@ async fn create_test_route(
         departure_time_min: Set(Some("06:00".to_string())),
         departure_time_max: Set(Some("10:00".to_string())),
         created_at: Set(chrono::Utc::now()),
+        max_price: Set(None),
+        seat_type: Set(None),
+        plan_ids_allow: Set(None),
+        plan_ids_deny: Set(None),
+        min_remaining_seats: Set(None),
+        date_window: Set(None),
+        status: Set("active".to_string()),
+        enabled: Set(true),
+        paused_until: Set(None),
+        scrape_interval_secs: Set(None),
+        adaptive_interval: Set(false),
+        notify_on_sold_out: Set(false),
     };
     route.insert(db).await.unwrap();
     route_id
@@ -94,6 +109,7 @@ async fn create_test_route_state(
         last_check: Set(Some(chrono::Utc::now())),
         total_checks: Set(1),
         total_alerts: Set(0),
+        last_snapshot: Set(None),
     };
     state.insert(db).await.unwrap();
 }
@@ -29,8 +29,11 @@ async fn test_multi_user_scenario() {
         enabled: Set(true),
         notify_on_change_only: Set(true),
         scrape_interval_secs: Set(300),
-        discord_webhook_url: Set(Some("https://discord.com/webhook1".to_string())),
         created_at: Set(chrono::Utc::now()),
+        quiet_hours_start: Set(None),
+        quiet_hours_end: Set(None),
+        quiet_hours_tz: Set(None),
+        locale: Set("fr".to_string()),
     };
     user1.insert(&db).await.unwrap();
 
@@ -40,8 +43,11 @@ async fn test_multi_user_scenario() {
         enabled: Set(true),
         notify_on_change_only: Set(false),
         scrape_interval_secs: Set(600),
-        discord_webhook_url: Set(Some("https://discord.com/webhook2".to_string())),
         created_at: Set(chrono::Utc::now()),
+        quiet_hours_start: Set(None),
+        quiet_hours_end: Set(None),
+        quiet_hours_tz: Set(None),
+        locale: Set("fr".to_string()),
     };
     user2.insert(&db).await.unwrap();
 
@@ -57,6 +63,18 @@ async fn test_multi_user_scenario() {
         departure_time_min: Set(Some("06:00".to_string())),
         departure_time_max: Set(Some("10:00".to_string())),
         created_at: Set(chrono::Utc::now()),
+        max_price: Set(None),
+        seat_type: Set(None),
+        plan_ids_allow: Set(None),
+        plan_ids_deny: Set(None),
+        min_remaining_seats: Set(None),
+        date_window: Set(None),
+        status: Set("active".to_string()),
+        enabled: Set(true),
+        paused_until: Set(None),
+        scrape_interval_secs: Set(None),
+        adaptive_interval: Set(false),
+        notify_on_sold_out: Set(false),
     };
     route1.insert(&db).await.unwrap();
 
@@ -72,6 +90,18 @@ async fn test_multi_user_scenario() {
         departure_time_min: Set(None),
         departure_time_max: Set(None),
         created_at: Set(chrono::Utc::now()),
+        max_price: Set(None),
+        seat_type: Set(None),
+        plan_ids_allow: Set(None),
+        plan_ids_deny: Set(None),
+        min_remaining_seats: Set(None),
+        date_window: Set(None),
+        status: Set("active".to_string()),
+        enabled: Set(true),
+        paused_until: Set(None),
+        scrape_interval_secs: Set(None),
+        adaptive_interval: Set(false),
+        notify_on_sold_out: Set(false),
     };
     route2.insert(&db).await.unwrap();
 
@@ -132,8 +162,11 @@ async fn test_route_state_isolation() {
         enabled: Set(true),
         notify_on_change_only: Set(true),
         scrape_interval_secs: Set(300),
-        discord_webhook_url: Set(None),
         created_at: Set(chrono::Utc::now()),
+        quiet_hours_start: Set(None),
+        quiet_hours_end: Set(None),
+        quiet_hours_tz: Set(None),
+        locale: Set("fr".to_string()),
     };
     user.insert(&db).await.unwrap();
 
@@ -149,6 +182,18 @@ async fn test_route_state_isolation() {
         departure_time_min: Set(None),
         departure_time_max: Set(None),
         created_at: Set(chrono::Utc::now()),
+        max_price: Set(None),
+        seat_type: Set(None),
+        plan_ids_allow: Set(None),
+        plan_ids_deny: Set(None),
+        min_remaining_seats: Set(None),
+        date_window: Set(None),
+        status: Set("active".to_string()),
+        enabled: Set(true),
+        paused_until: Set(None),
+        scrape_interval_secs: Set(None),
+        adaptive_interval: Set(false),
+        notify_on_sold_out: Set(false),
     };
     route1.insert(&db).await.unwrap();
 
@@ -164,6 +209,18 @@ async fn test_route_state_isolation() {
         departure_time_min: Set(None),
         departure_time_max: Set(None),
         created_at: Set(chrono::Utc::now()),
+        max_price: Set(None),
+        seat_type: Set(None),
+        plan_ids_allow: Set(None),
+        plan_ids_deny: Set(None),
+        min_remaining_seats: Set(None),
+        date_window: Set(None),
+        status: Set("active".to_string()),
+        enabled: Set(true),
+        paused_until: Set(None),
+        scrape_interval_secs: Set(None),
+        adaptive_interval: Set(false),
+        notify_on_sold_out: Set(false),
     };
     route2.insert(&db).await.unwrap();
 
@@ -193,10 +250,10 @@ async fn test_route_state_isolation() {
     };
     passengers2.insert(&db).await.unwrap();
 
-    update_route_state(&db, route1_id, "hash1".to_string(), false)
+    update_route_state(&db, route1_id, "hash1".to_string(), &[], false)
         .await
         .unwrap();
-    update_route_state(&db, route2_id, "hash2".to_string(), true)
+    update_route_state(&db, route2_id, "hash2".to_string(), &[], true)
         .await
         .unwrap();
 
@@ -206,7 +263,7 @@ async fn test_route_state_isolation() {
     assert_eq!(state1.last_seen_hash, "hash1");
     assert_eq!(state2.last_seen_hash, "hash2");
 
-    update_route_state(&db, route1_id, "hash1_updated".to_string(), true)
+    update_route_state(&db, route1_id, "hash1_updated".to_string(), &[], true)
         .await
         .unwrap();
 
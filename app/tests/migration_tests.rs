@@ -0,0 +1,73 @@
+//! Data migrations tested against the schema they run on
+//!
+//! Each test migrates up to just before the migration under test, seeds rows
+//! with raw SQL matching that older schema, then runs the remaining migrations.
+#![allow(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::doc_markdown,
+    clippy::uninlined_format_args
+)]
+
+use app::db::init_database;
+use app::entities::{notification_channels, prelude::*};
+use migration::{Migrator, MigratorTrait};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, DbBackend, EntityTrait, QueryFilter,
+    Statement,
+};
+use uuid::Uuid;
+
+/// Migrations applied before `m20261016_000002_create_notification_channels`
+const BEFORE_NOTIFICATION_CHANNELS: u32 = 11;
+
+async fn insert_legacy_user(db: &DatabaseConnection, email: &str, webhook: Option<&str>) -> Uuid {
+    let user_id = Uuid::new_v4();
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "INSERT INTO users (id, email, discord_webhook_url) VALUES (?, ?, ?)",
+        [user_id.into(), email.into(), webhook.into()],
+    ))
+    .await
+    .unwrap();
+    user_id
+}
+
+#[tokio::test]
+async fn test_discord_webhooks_move_to_notification_channels() {
+    let db = init_database("sqlite::memory:").await.unwrap();
+    Migrator::up(&db, Some(BEFORE_NOTIFICATION_CHANNELS))
+        .await
+        .unwrap();
+
+    let with_webhook = insert_legacy_user(
+        &db,
+        "webhook@test.com",
+        Some("https://discord.com/api/webhooks/1/abc"),
+    )
+    .await;
+    let without_webhook = insert_legacy_user(&db, "none@test.com", None).await;
+    let empty_webhook = insert_legacy_user(&db, "empty@test.com", Some("")).await;
+
+    Migrator::up(&db, None).await.unwrap();
+
+    let channels = NotificationChannels::find()
+        .filter(notification_channels::Column::UserId.eq(with_webhook))
+        .all(&db)
+        .await
+        .unwrap();
+    assert_eq!(channels.len(), 1);
+    assert_eq!(channels[0].kind, "discord");
+    assert_eq!(channels[0].target, "https://discord.com/api/webhooks/1/abc");
+    assert!(channels[0].enabled);
+
+    for user_id in [without_webhook, empty_webhook] {
+        let channels = NotificationChannels::find()
+            .filter(notification_channels::Column::UserId.eq(user_id))
+            .all(&db)
+            .await
+            .unwrap();
+        assert!(channels.is_empty());
+    }
+}
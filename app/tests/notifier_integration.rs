@@ -9,8 +9,8 @@
     clippy::uninlined_format_args
 )]
 
-use app::notifier::{DiscordNotifier, NotificationContext};
-use app::types::{BusSchedule, PricingPlan, SeatAvailability};
+use app::notifier::{DiscordNotifier, NotificationContext, Notifier, QueuedAlert};
+use app::types::{BusSchedule, Locale, PricingPlan, SeatAvailability};
 use wiremock::matchers::{method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
@@ -34,7 +34,9 @@ fn test_bus_schedule() -> BusSchedule {
             availability: SeatAvailability::Available {
                 remaining_seats: Some(5),
             },
+            details: None,
         }],
+        booking_url: None,
     }
 }
 
@@ -42,9 +44,13 @@ fn test_context() -> NotificationContext {
     NotificationContext {
         departure_station_name: "Shinjuku".to_string(),
         arrival_station_name: "Kawaguchiko".to_string(),
+        route_name: String::new(),
         date_range: ("20250115".to_string(), "20250120".to_string()),
         passenger_count: 2,
         time_filter: None,
+        snooze_url: None,
+        availability_url: None,
+        locale: Locale::default(),
     }
 }
 
@@ -63,14 +69,14 @@ async fn test_send_startup_notification_success() {
     let webhook_url = format!("{}/webhook", mock_server.uri());
 
     let result = notifier
-        .send_startup_notification(&webhook_url, 5, 10)
+        .send_startup_notification(&webhook_url, 5, 10, Locale::Fr)
         .await;
 
     assert!(result.is_ok());
 }
 
 #[tokio::test]
-async fn test_send_startup_notification_failure_handled() {
+async fn test_send_startup_notification_failure_reported() {
     let mock_server = MockServer::start().await;
 
     Mock::given(method("POST"))
@@ -83,12 +89,11 @@ async fn test_send_startup_notification_failure_handled() {
     let notifier = DiscordNotifier::new();
     let webhook_url = format!("{}/webhook", mock_server.uri());
 
-    // Should not return error even on 500 status
     let result = notifier
-        .send_startup_notification(&webhook_url, 5, 10)
+        .send_startup_notification(&webhook_url, 5, 10, Locale::Fr)
         .await;
 
-    assert!(result.is_ok());
+    assert!(result.is_err());
 }
 
 #[tokio::test]
@@ -108,7 +113,7 @@ async fn test_send_availability_alert_success() {
     let context = test_context();
 
     let result = notifier
-        .send_availability_alert(&webhook_url, &schedules, &context)
+        .send_availability_alert(&webhook_url, &schedules, &[], &context)
         .await;
 
     assert!(result.is_ok());
@@ -132,7 +137,7 @@ async fn test_send_availability_alert_empty_schedules() {
     let context = test_context();
 
     let result = notifier
-        .send_availability_alert(&webhook_url, &schedules, &context)
+        .send_availability_alert(&webhook_url, &schedules, &[], &context)
         .await;
 
     assert!(result.is_ok());
@@ -156,19 +161,19 @@ async fn test_send_availability_alert_with_time_filter() {
     context.time_filter = Some(("08:00".to_string(), "12:00".to_string()));
 
     let result = notifier
-        .send_availability_alert(&webhook_url, &schedules, &context)
+        .send_availability_alert(&webhook_url, &schedules, &[], &context)
         .await;
 
     assert!(result.is_ok());
 }
 
 #[tokio::test]
-async fn test_send_availability_alert_failure_handled() {
+async fn test_send_availability_alert_failure_reported() {
     let mock_server = MockServer::start().await;
 
     Mock::given(method("POST"))
         .and(path("/webhook"))
-        .respond_with(ResponseTemplate::new(429)) // Rate limited
+        .respond_with(ResponseTemplate::new(500))
         .expect(1)
         .mount(&mock_server)
         .await;
@@ -178,14 +183,185 @@ async fn test_send_availability_alert_failure_handled() {
     let schedules = vec![test_bus_schedule()];
     let context = test_context();
 
-    // Should not return error even on rate limit
     let result = notifier
-        .send_availability_alert(&webhook_url, &schedules, &context)
+        .send_availability_alert(&webhook_url, &schedules, &[], &context)
+        .await;
+
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_send_availability_alert_retries_after_rate_limit() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/webhook"))
+        .respond_with(
+            ResponseTemplate::new(429)
+                .set_body_json(serde_json::json!({ "retry_after": 0.01, "global": false })),
+        )
+        .up_to_n_times(1)
+        .expect(1)
+        .with_priority(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/webhook"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let notifier = DiscordNotifier::new();
+    let webhook_url = format!("{}/webhook", mock_server.uri());
+    let schedules = vec![test_bus_schedule()];
+    let context = test_context();
+
+    let result = notifier
+        .send_availability_alert(&webhook_url, &schedules, &[], &context)
         .await;
 
     assert!(result.is_ok());
 }
 
+#[tokio::test]
+async fn test_send_availability_alert_gives_up_when_rate_limited() {
+    let mock_server = MockServer::start().await;
+
+    // First attempt plus three retries
+    Mock::given(method("POST"))
+        .and(path("/webhook"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+        .expect(4)
+        .mount(&mock_server)
+        .await;
+
+    let notifier = DiscordNotifier::new();
+    let webhook_url = format!("{}/webhook", mock_server.uri());
+    let schedules = vec![test_bus_schedule()];
+    let context = test_context();
+
+    let result = notifier
+        .send_availability_alert(&webhook_url, &schedules, &[], &context)
+        .await;
+
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_send_availability_alert_split_across_messages() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/webhook"))
+        .respond_with(ResponseTemplate::new(204))
+        .mount(&mock_server)
+        .await;
+
+    let notifier = DiscordNotifier::new();
+    let webhook_url = format!("{}/webhook", mock_server.uri());
+    // 300 plans: far more than one embed, or one message, can hold
+    let schedules: Vec<_> = (0..300)
+        .map(|i| {
+            let mut schedule = test_bus_schedule();
+            schedule.bus_number = format!("Bus_{i}");
+            schedule
+        })
+        .collect();
+    let context = test_context();
+
+    let result = notifier
+        .send_availability_alert(&webhook_url, &schedules, &[], &context)
+        .await;
+
+    assert!(result.is_ok());
+    let requests = mock_server.received_requests().await.unwrap();
+    assert!(requests.len() > 1);
+    let fields: usize = requests
+        .iter()
+        .map(|request| {
+            let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+            let embeds = body["embeds"].as_array().unwrap();
+            assert!(embeds.len() <= 10);
+            embeds
+                .iter()
+                .map(|embed| embed["fields"].as_array().unwrap().len())
+                .sum::<usize>()
+        })
+        .sum();
+    assert_eq!(fields, 300);
+}
+
+#[tokio::test]
+async fn test_resume_availability_alert_skips_delivered_messages() {
+    let mock_server = MockServer::start().await;
+
+    // The first message goes through, the second fails once
+    Mock::given(method("POST"))
+        .and(path("/webhook"))
+        .respond_with(ResponseTemplate::new(204))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/webhook"))
+        .respond_with(ResponseTemplate::new(500))
+        .up_to_n_times(1)
+        .with_priority(2)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/webhook"))
+        .respond_with(ResponseTemplate::new(204))
+        .with_priority(3)
+        .mount(&mock_server)
+        .await;
+
+    let notifier = DiscordNotifier::new();
+    let webhook_url = format!("{}/webhook", mock_server.uri());
+    let alert = QueuedAlert {
+        schedules: (0..300)
+            .map(|i| {
+                let mut schedule = test_bus_schedule();
+                schedule.bus_number = format!("Bus_{i}");
+                schedule
+            })
+            .collect(),
+        changes: Vec::new(),
+        context: test_context(),
+    };
+
+    let failure = notifier
+        .resume_availability_alert(&webhook_url, &alert, 0)
+        .await
+        .unwrap_err();
+    assert_eq!(failure.sent, 1);
+
+    notifier
+        .resume_availability_alert(&webhook_url, &alert, failure.sent)
+        .await
+        .unwrap();
+
+    // Every plan was delivered once, the failed message aside
+    let requests = mock_server.received_requests().await.unwrap();
+    let fields: usize = requests
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| *index != 1)
+        .map(|(_, request)| {
+            let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+            body["embeds"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|embed| embed["fields"].as_array().unwrap().len())
+                .sum::<usize>()
+        })
+        .sum();
+    assert_eq!(fields, 300);
+}
+
 #[tokio::test]
 async fn test_send_availability_alert_multiple_schedules() {
     let mock_server = MockServer::start().await;
@@ -209,7 +385,7 @@ async fn test_send_availability_alert_multiple_schedules() {
     let context = test_context();
 
     let result = notifier
-        .send_availability_alert(&webhook_url, &schedules, &context)
+        .send_availability_alert(&webhook_url, &schedules, &[], &context)
         .await;
 
     assert!(result.is_ok());
@@ -227,7 +403,9 @@ async fn test_notifier_default_trait() {
         .await;
 
     let webhook_url = format!("{}/webhook", mock_server.uri());
-    let result = notifier.send_startup_notification(&webhook_url, 1, 1).await;
+    let result = notifier
+        .send_startup_notification(&webhook_url, 1, 1, Locale::Fr)
+        .await;
 
     assert!(result.is_ok());
 }
@@ -3,6 +3,7 @@
 //! Covers:
 //! - Network errors (lines 62-64, 97-99)
 //! - HTTP error responses (lines 56-57)
+//! - Failures reported to the caller
 //! - Empty `available_plans` (line 114)
 //! - `SeatAvailability::Available` with None seats (line 125)
 #![allow(
@@ -14,8 +15,8 @@
     clippy::cloned_ref_to_slice_refs
 )]
 
-use app::notifier::{DiscordNotifier, NotificationContext};
-use app::types::{BusSchedule, PricingPlan, SeatAvailability};
+use app::notifier::{DiscordNotifier, NotificationContext, Notifier};
+use app::types::{BusSchedule, Locale, PricingPlan, SeatAvailability};
 use wiremock::matchers::method;
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
@@ -23,9 +24,13 @@ fn test_context() -> NotificationContext {
     NotificationContext {
         departure_station_name: "Tokyo".to_string(),
         arrival_station_name: "Osaka".to_string(),
+        route_name: String::new(),
         date_range: ("20250201".to_string(), "20250210".to_string()),
         passenger_count: 3,
         time_filter: Some(("09:00".to_string(), "18:00".to_string())),
+        snooze_url: None,
+        availability_url: None,
+        locale: Locale::default(),
     }
 }
 
@@ -43,10 +48,12 @@ async fn test_send_startup_notification_http_error_response() {
     let notifier = DiscordNotifier::new();
     let webhook_url = format!("{}/webhook", mock_server.uri());
 
-    // Should handle error gracefully and still return Ok
-    let result = notifier.send_startup_notification(&webhook_url, 2, 5).await;
+    // Reported to the caller, which logs it
+    let result = notifier
+        .send_startup_notification(&webhook_url, 2, 5, Locale::Fr)
+        .await;
 
-    assert!(result.is_ok());
+    assert!(result.is_err());
 }
 
 #[tokio::test]
@@ -55,10 +62,12 @@ async fn test_send_startup_notification_network_error() {
     // Use invalid URL to trigger network error (lines 62-64)
     let invalid_url = "http://invalid-host-that-does-not-exist:9999/webhook";
 
-    // Should handle network error gracefully and still return Ok
-    let result = notifier.send_startup_notification(invalid_url, 1, 3).await;
+    // Reported to the caller, which logs it
+    let result = notifier
+        .send_startup_notification(invalid_url, 1, 3, Locale::Fr)
+        .await;
 
-    assert!(result.is_ok());
+    assert!(result.is_err());
 }
 
 #[tokio::test]
@@ -94,17 +103,19 @@ async fn test_send_availability_alert_http_error_response() {
             availability: SeatAvailability::Available {
                 remaining_seats: Some(10),
             },
+            details: None,
         }],
+        booking_url: None,
     }];
 
     let context = test_context();
 
-    // Should handle error gracefully and still return Ok
+    // The failure is reported so the alert can be sent again later
     let result = notifier
-        .send_availability_alert(&webhook_url, &schedules, &context)
+        .send_availability_alert(&webhook_url, &schedules, &[], &context)
         .await;
 
-    assert!(result.is_ok());
+    assert!(result.is_err());
 }
 
 #[tokio::test]
@@ -132,17 +143,19 @@ async fn test_send_availability_alert_network_error() {
             availability: SeatAvailability::Available {
                 remaining_seats: Some(5),
             },
+            details: None,
         }],
+        booking_url: None,
     }];
 
     let context = test_context();
 
-    // Should handle network error gracefully and still return Ok
+    // The failure is reported so the alert can be sent again later
     let result = notifier
-        .send_availability_alert(invalid_url, &schedules, &context)
+        .send_availability_alert(invalid_url, &schedules, &[], &context)
         .await;
 
-    assert!(result.is_ok());
+    assert!(result.is_err());
 }
 
 #[tokio::test]
@@ -171,6 +184,7 @@ async fn test_send_availability_alert_with_empty_available_plans() {
             arrival_time: "12:00".to_string(),
             way_no: 1,
             available_plans: vec![], // Empty - should be skipped in loop
+            booking_url: None,
         },
         BusSchedule {
             bus_number: "Bus_WithPlan".to_string(),
@@ -191,14 +205,16 @@ async fn test_send_availability_alert_with_empty_available_plans() {
                 availability: SeatAvailability::Available {
                     remaining_seats: Some(2),
                 },
+                details: None,
             }],
+            booking_url: None,
         },
     ];
 
     let context = test_context();
 
     let result = notifier
-        .send_availability_alert(&webhook_url, &schedules, &context)
+        .send_availability_alert(&webhook_url, &schedules, &[], &context)
         .await;
 
     assert!(result.is_ok());
@@ -237,13 +253,15 @@ async fn test_send_availability_alert_with_none_remaining_seats() {
             availability: SeatAvailability::Available {
                 remaining_seats: None, // Test None case (line 125)
             },
+            details: None,
         }],
+        booking_url: None,
     }];
 
     let context = test_context();
 
     let result = notifier
-        .send_availability_alert(&webhook_url, &schedules, &context)
+        .send_availability_alert(&webhook_url, &schedules, &[], &context)
         .await;
 
     assert!(result.is_ok());
@@ -281,20 +299,26 @@ async fn test_notification_context_building_variations() {
             availability: SeatAvailability::Available {
                 remaining_seats: Some(8),
             },
+            details: None,
         }],
+        booking_url: None,
     };
 
     // Test with time_filter
     let context_with_filter = NotificationContext {
         departure_station_name: "Kyoto".to_string(),
         arrival_station_name: "Nagoya".to_string(),
+        route_name: String::new(),
         date_range: ("20250205".to_string(), "20250212".to_string()),
         passenger_count: 1,
         time_filter: Some(("06:00".to_string(), "22:00".to_string())),
+        snooze_url: None,
+        availability_url: None,
+        locale: Locale::default(),
     };
 
     let result1 = notifier
-        .send_availability_alert(&webhook_url, &[schedule.clone()], &context_with_filter)
+        .send_availability_alert(&webhook_url, &[schedule.clone()], &[], &context_with_filter)
         .await;
     assert!(result1.is_ok());
 
@@ -302,13 +326,17 @@ async fn test_notification_context_building_variations() {
     let context_no_filter = NotificationContext {
         departure_station_name: "Fukuoka".to_string(),
         arrival_station_name: "Hiroshima".to_string(),
+        route_name: String::new(),
         date_range: ("20250205".to_string(), "20250212".to_string()),
         passenger_count: 4,
         time_filter: None,
+        snooze_url: None,
+        availability_url: None,
+        locale: Locale::default(),
     };
 
     let result2 = notifier
-        .send_availability_alert(&webhook_url, &[schedule], &context_no_filter)
+        .send_availability_alert(&webhook_url, &[schedule], &[], &context_no_filter)
         .await;
     assert!(result2.is_ok());
 }
@@ -347,6 +375,7 @@ async fn test_send_availability_alert_multiple_plans_per_schedule() {
                 availability: SeatAvailability::Available {
                     remaining_seats: Some(15),
                 },
+                details: None,
             },
             PricingPlan {
                 plan_id: 602,
@@ -357,6 +386,7 @@ async fn test_send_availability_alert_multiple_plans_per_schedule() {
                 availability: SeatAvailability::Available {
                     remaining_seats: None,
                 },
+                details: None,
             },
             PricingPlan {
                 plan_id: 603,
@@ -367,14 +397,16 @@ async fn test_send_availability_alert_multiple_plans_per_schedule() {
                 availability: SeatAvailability::Available {
                     remaining_seats: Some(3),
                 },
+                details: None,
             },
         ],
+        booking_url: None,
     }];
 
     let context = test_context();
 
     let result = notifier
-        .send_availability_alert(&webhook_url, &schedules, &context)
+        .send_availability_alert(&webhook_url, &schedules, &[], &context)
         .await;
 
     assert!(result.is_ok());
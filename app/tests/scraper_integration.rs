@@ -10,8 +10,15 @@
     clippy::uninlined_format_args
 )]
 
+use app::circuit_breaker::{CircuitBreakerConfig, CircuitState};
+use app::error::ScraperError;
+use app::rate_limiter::RateLimitConfig;
+use app::retry::RetryPolicy;
 use app::scraper::BusScraper;
-use app::types::{DateRange, PassengerCount, ScrapeRequest, TimeFilter};
+use app::types::{
+    Amenity, DateRange, PassengerCount, PlanFilter, ScrapeRequest, SeatType, TimeFilter,
+};
+use std::time::{Duration, Instant};
 use wiremock::matchers::{body_string_contains, method, path, query_param};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
@@ -25,12 +32,24 @@ fn test_scrape_request(date: &str) -> ScrapeRequest {
         date_range: DateRange {
             start: date.to_string(),
             end: date.to_string(),
+            window: None,
         },
         passengers: PassengerCount::default(),
         time_filter: None,
+        plan_filter: None,
     }
 }
 
+/// Helper to create a scraper that gives up on the first failure
+fn scraper_without_retries(uri: String) -> BusScraper {
+    BusScraper::new(uri)
+        .unwrap()
+        .with_retry_policy(RetryPolicy {
+            max_retries: 0,
+            ..RetryPolicy::default()
+        })
+}
+
 // === fetch_routes TESTS ===
 
 #[tokio::test]
@@ -92,7 +111,7 @@ async fn test_fetch_routes_http_error() {
         .mount(&mock_server)
         .await;
 
-    let scraper = BusScraper::new(mock_server.uri()).unwrap();
+    let scraper = scraper_without_retries(mock_server.uri());
     let result = scraper.fetch_routes(100).await;
 
     assert!(result.is_err());
@@ -159,6 +178,48 @@ async fn test_fetch_arrival_stations_success() {
     assert_eq!(stations[1].name, "富士急ハイランド");
 }
 
+// === fetch_available_dates TESTS ===
+
+#[tokio::test]
+async fn test_fetch_available_dates_success() {
+    let mock_server = MockServer::start().await;
+
+    let dates_xml = r#"<?xml version="1.0" encoding="UTF-8" ?>
+<results>
+<rosen>
+    <num>2</num>
+    <id>20250115</id>
+        <name>2025/01/15(水)</name>
+        <switchChangeableFlg></switchChangeableFlg>
+        <nearbyStationFlg></nearbyStationFlg>
+    <id>20250117</id>
+        <name>2025/01/17(金)</name>
+        <switchChangeableFlg></switchChangeableFlg>
+        <nearbyStationFlg></nearbyStationFlg>
+</rosen>
+</results>"#;
+
+    Mock::given(method("POST"))
+        .and(path("/ajaxPulldown"))
+        .and(body_string_contains("mode=date"))
+        .and(body_string_contains("id=110"))
+        .and(body_string_contains("onStation=001"))
+        .and(body_string_contains("offStation=064"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(dates_xml))
+        .mount(&mock_server)
+        .await;
+
+    let scraper = BusScraper::new(mock_server.uri()).unwrap();
+    let dates = scraper
+        .fetch_available_dates("110", "001", "064")
+        .await
+        .unwrap();
+
+    assert_eq!(dates.len(), 2);
+    assert_eq!(dates[0].date_id, "20250115");
+    assert_eq!(dates[1].display_name, "2025/01/17(金)");
+}
+
 // === fetch_schedules TESTS ===
 
 #[tokio::test]
@@ -202,6 +263,13 @@ async fn test_fetch_schedules_success() {
     assert_eq!(schedules[0].arrival_time, "10:00");
     assert!(!schedules[0].available_plans.is_empty());
     assert_eq!(schedules[0].available_plans[0].price, 2100);
+
+    let booking_url = schedules[0].booking_url.as_deref().unwrap();
+    assert!(booking_url.starts_with(&format!("{}/reservation/rsvPlanList?", mock_server.uri())));
+    assert!(booking_url.contains("mode=search"));
+    assert!(booking_url.contains("bordingDate=20250115"));
+    assert!(booking_url.contains("adultMen=1"));
+    assert!(booking_url.contains("handicapChildWomen=0"));
 }
 
 #[tokio::test]
@@ -310,24 +378,408 @@ async fn test_fetch_schedules_http_error() {
     assert!(result.is_err());
 }
 
+// === fetch_plan_details TESTS ===
+
+const PLAN_INFO_XML: &str = include_str!("fixtures/plan_info_response.xml");
+
+#[tokio::test]
+async fn test_fetch_schedules_attaches_cached_plan_details() {
+    let mock_server = MockServer::start().await;
+
+    let schedules_html = r#"<!DOCTYPE html>
+<html><body>
+    <section class="busSvclistItem">
+        <ul>
+            <li class="dep"><p class="time">7:45 発</p></li>
+            <li class="arr"><p class="time">10:00 着</p></li>
+        </ul>
+        <div class="planArea">
+            <p class="planName">通常運賃（窓口・コンビニ購入用）</p>
+            <p class="price">2,100円</p>
+            <form name="selectPlan">
+                <input type="hidden" class="seat_0" value="1" data-index="0">
+                <input type="hidden" name="discntPlanNo" value="27775">
+                <button>残り5席</button>
+            </form>
+        </div>
+    </section>
+</body></html>"#;
+
+    Mock::given(method("GET"))
+        .and(path("/reservation/rsvPlanList"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(schedules_html))
+        .mount(&mock_server)
+        .await;
+
+    // Two searches share one plan lookup
+    Mock::given(method("GET"))
+        .and(path("/reservation/ajaxPlanInfoPrint"))
+        .and(query_param("discntPlanNo", "27775"))
+        .and(query_param("reference", "reservation"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(PLAN_INFO_XML))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let scraper = BusScraper::new(mock_server.uri()).unwrap();
+    let request = test_scrape_request("2025-01-15");
+    scraper.fetch_schedules(&request, "20250115").await.unwrap();
+    let schedules = scraper.fetch_schedules(&request, "20250116").await.unwrap();
+
+    let plan = &schedules[0].available_plans[0];
+    assert_eq!(plan.plan_name, "通常運賃（窓口・コンビニ購入用）");
+    let details = plan.details.as_ref().unwrap();
+    assert_eq!(
+        details.discount_note,
+        "バスタ新宿と富士五湖方面を結ぶバスの運賃になります。"
+    );
+    assert_eq!(details.seat_type, SeatType::Standard);
+    assert_eq!(details.amenities, vec![Amenity::FreeWifi]);
+    assert!(!details.lady_only);
+}
+
+#[tokio::test]
+async fn test_fetch_schedules_without_plan_details() {
+    let mock_server = MockServer::start().await;
+
+    let schedules_html = r#"<!DOCTYPE html>
+<html><body>
+    <section class="busSvclistItem">
+        <ul>
+            <li class="dep"><p class="time">7:45 発</p></li>
+            <li class="arr"><p class="time">10:00 着</p></li>
+        </ul>
+        <div class="planArea">
+            <p class="price">2,100円</p>
+            <form name="selectPlan">
+                <input type="hidden" class="seat_0" value="1" data-index="0">
+                <input type="hidden" name="discntPlanNo" value="27775">
+                <button>残り5席</button>
+            </form>
+        </div>
+    </section>
+</body></html>"#;
+
+    Mock::given(method("GET"))
+        .and(path("/reservation/rsvPlanList"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(schedules_html))
+        .mount(&mock_server)
+        .await;
+
+    // The failure is remembered, so the second search does not ask again
+    Mock::given(method("GET"))
+        .and(path("/reservation/ajaxPlanInfoPrint"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let scraper = BusScraper::new(mock_server.uri()).unwrap();
+    let request = test_scrape_request("2025-01-15");
+    scraper.fetch_schedules(&request, "20250115").await.unwrap();
+    let schedules = scraper.fetch_schedules(&request, "20250116").await.unwrap();
+
+    // A failed lookup keeps the schedule, just without details
+    assert_eq!(schedules.len(), 1);
+    assert!(schedules[0].available_plans[0].plan_name.is_empty());
+    assert!(schedules[0].available_plans[0].details.is_none());
+}
+
+#[tokio::test]
+async fn test_fetch_schedules_applies_plan_filter() {
+    let mock_server = MockServer::start().await;
+
+    let schedules_html = r#"<!DOCTYPE html>
+<html><body>
+    <section class="busSvclistItem">
+        <ul>
+            <li class="dep"><p class="time">7:45 発</p></li>
+            <li class="arr"><p class="time">10:00 着</p></li>
+        </ul>
+        <div class="planArea">
+            <p class="price">2,100円</p>
+            <form name="selectPlan">
+                <input type="hidden" class="seat_0" value="1" data-index="0">
+                <input type="hidden" name="discntPlanNo" value="27775">
+                <button>残り5席</button>
+            </form>
+        </div>
+    </section>
+    <section class="busSvclistItem">
+        <ul>
+            <li class="dep"><p class="time">9:00 発</p></li>
+            <li class="arr"><p class="time">11:15 着</p></li>
+        </ul>
+        <div class="planArea">
+            <p class="price">9,800円</p>
+            <form name="selectPlan">
+                <input type="hidden" class="seat_0" value="1" data-index="0">
+                <input type="hidden" name="discntPlanNo" value="30000">
+                <button>予約する</button>
+            </form>
+        </div>
+    </section>
+</body></html>"#;
+
+    Mock::given(method("GET"))
+        .and(path("/reservation/rsvPlanList"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(schedules_html))
+        .mount(&mock_server)
+        .await;
+
+    let three_row_plan =
+        PLAN_INFO_XML.replace("<oneSeatFlg>0</oneSeatFlg>", "<oneSeatFlg>1</oneSeatFlg>");
+    Mock::given(method("GET"))
+        .and(path("/reservation/ajaxPlanInfoPrint"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(three_row_plan))
+        .mount(&mock_server)
+        .await;
+
+    let scraper = BusScraper::new(mock_server.uri()).unwrap();
+    let mut request = test_scrape_request("2025-01-15");
+    request.plan_filter = Some(PlanFilter {
+        max_price: Some(8000),
+        seat_type: Some(SeatType::ThreeRow),
+        ..PlanFilter::default()
+    });
+
+    let schedules = scraper.fetch_schedules(&request, "20250115").await.unwrap();
+
+    // The 9,800円 bus has no plan left and is dropped
+    assert_eq!(schedules.len(), 1);
+    assert_eq!(schedules[0].departure_time, "7:45");
+    assert_eq!(schedules[0].available_plans[0].plan_id, 27775);
+
+    request.plan_filter = Some(PlanFilter {
+        plan_ids_deny: vec![27775],
+        min_remaining_seats: Some(6),
+        ..PlanFilter::default()
+    });
+
+    let schedules = scraper.fetch_schedules(&request, "20250115").await.unwrap();
+
+    // Plan 30000 shows no seat count, so the seat minimum does not apply to it
+    assert_eq!(schedules.len(), 1);
+    assert_eq!(schedules[0].available_plans[0].plan_id, 30000);
+}
+
 // === Retry logic TESTS ===
 
 #[tokio::test]
 async fn test_retry_on_503_returns_error() {
     let mock_server = MockServer::start().await;
 
-    // 503 is converted to InvalidResponse, not ServiceUnavailable
-    // So it errors immediately without retrying
+    // 503 is retried, then reported as Upstream once retries run out
     Mock::given(method("POST"))
         .and(path("/ajaxPulldown"))
         .respond_with(ResponseTemplate::new(503))
+        .expect(2)
         .mount(&mock_server)
         .await;
 
-    let scraper = BusScraper::new(mock_server.uri()).unwrap();
+    let scraper = BusScraper::new(mock_server.uri())
+        .unwrap()
+        .with_retry_policy(RetryPolicy {
+            max_retries: 1,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(10),
+        });
     let result = scraper.fetch_routes(100).await;
 
-    assert!(result.is_err());
+    assert!(matches!(
+        result,
+        Err(ScraperError::Upstream { status: 503, .. })
+    ));
+}
+
+// === Schedule page sharing TESTS ===
+
+#[tokio::test]
+async fn test_identical_schedule_requests_share_one_fetch() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/reservation/rsvPlanList"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string("<html><body></body></html>")
+                .set_delay(Duration::from_millis(100)),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let scraper = BusScraper::new(mock_server.uri()).unwrap();
+    let request = test_scrape_request("2025-01-15");
+
+    // Two trackers at once, then one slightly later
+    let (a, b) = tokio::join!(
+        scraper.fetch_schedules(&request, "20250115"),
+        scraper.fetch_schedules(&request, "20250115"),
+    );
+    let c = scraper.fetch_schedules(&request, "20250115").await;
+
+    assert!(a.is_ok() && b.is_ok() && c.is_ok());
+}
+
+#[tokio::test]
+async fn test_different_passengers_are_fetched_separately() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/reservation/rsvPlanList"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<html><body></body></html>"))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let scraper = BusScraper::new(mock_server.uri()).unwrap();
+    let solo = test_scrape_request("2025-01-15");
+    let mut pair = test_scrape_request("2025-01-15");
+    pair.passengers.adult_women = 1;
+
+    assert!(scraper.fetch_schedules(&solo, "20250115").await.is_ok());
+    assert!(scraper.fetch_schedules(&pair, "20250115").await.is_ok());
+}
+
+// === Circuit breaker TESTS ===
+
+#[tokio::test]
+async fn test_circuit_opens_then_recovers_through_probe() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/ajaxPulldown"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(2)
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/ajaxPulldown"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<routes></routes>"))
+        .mount(&mock_server)
+        .await;
+
+    let scraper =
+        scraper_without_retries(mock_server.uri()).with_circuit_breaker(CircuitBreakerConfig {
+            failure_threshold: 2,
+            open_for: Duration::from_millis(100),
+        });
+    let mut upstream = scraper.watch_upstream();
+
+    assert!(scraper.fetch_routes(100).await.is_err());
+    assert!(scraper.fetch_routes(100).await.is_err());
+    assert_eq!(scraper.upstream_state(), CircuitState::Open);
+    assert_eq!(*upstream.borrow_and_update(), CircuitState::Open);
+
+    // Short-circuited: the server sees no third request
+    assert!(matches!(
+        scraper.fetch_routes(100).await,
+        Err(ScraperError::CircuitOpen { .. })
+    ));
+
+    tokio::time::sleep(Duration::from_millis(150)).await;
+
+    // The probe reaches the recovered server and closes the circuit
+    assert!(scraper.fetch_routes(100).await.is_ok());
+    assert_eq!(scraper.upstream_state(), CircuitState::Closed);
+}
+
+#[tokio::test]
+async fn test_check_availability_full_fails_fast_while_circuit_open() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/ajaxPulldown"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/reservation/rsvPlanList"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&mock_server)
+        .await;
+
+    let scraper =
+        scraper_without_retries(mock_server.uri()).with_circuit_breaker(CircuitBreakerConfig {
+            failure_threshold: 1,
+            open_for: Duration::from_mins(1),
+        });
+    assert!(scraper.fetch_routes(100).await.is_err());
+
+    let result = scraper
+        .check_availability_full(&test_scrape_request("2025-01-15"))
+        .await;
+
+    assert!(matches!(result, Err(ScraperError::CircuitOpen { .. })));
+}
+
+#[tokio::test]
+async fn test_failing_plan_details_do_not_open_circuit() {
+    let mock_server = MockServer::start().await;
+
+    let schedules_html = r#"<!DOCTYPE html>
+<html><body>
+    <section class="busSvclistItem">
+        <ul>
+            <li class="dep"><p class="time">8:30 発</p></li>
+            <li class="arr"><p class="time">11:30 着</p></li>
+        </ul>
+        <div class="planArea">
+            <p class="price">2,800円</p>
+            <form name="selectPlan">
+                <input type="hidden" class="seat_0" value="1" data-index="0">
+                <input type="hidden" name="discntPlanNo" value="300">
+                <button>残り8席</button>
+            </form>
+        </div>
+        <div class="planArea">
+            <p class="price">3,100円</p>
+            <form name="selectPlan">
+                <input type="hidden" class="seat_1" value="1" data-index="1">
+                <input type="hidden" name="discntPlanNo" value="301">
+                <button>残り2席</button>
+            </form>
+        </div>
+    </section>
+</body></html>"#;
+
+    Mock::given(method("GET"))
+        .and(path("/reservation/rsvPlanList"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(schedules_html))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/reservation/ajaxPlanInfoPrint"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let scraper =
+        scraper_without_retries(mock_server.uri()).with_circuit_breaker(CircuitBreakerConfig {
+            failure_threshold: 1,
+            open_for: Duration::from_mins(1),
+        });
+
+    for date in ["2025-01-20", "2025-01-21"] {
+        let schedules = scraper
+            .check_availability_full(&test_scrape_request(date))
+            .await
+            .unwrap()
+            .into_schedules();
+
+        assert_eq!(schedules.len(), 1);
+        assert_eq!(schedules[0].available_plans.len(), 2);
+        assert!(schedules[0].available_plans[0].details.is_none());
+    }
+    assert_eq!(scraper.upstream_state(), CircuitState::Closed);
 }
 
 // === Network error TESTS ===
@@ -411,17 +863,91 @@ async fn test_check_availability_full_multiple_dates() {
         date_range: DateRange {
             start: "2025-01-15".to_string(),
             end: "2025-01-17".to_string(), // 3 days: 15, 16, 17
+            window: None,
         },
         passengers: PassengerCount::default(),
         time_filter: None,
+        plan_filter: None,
     };
 
-    let schedules = scraper.check_availability_full(&request).await.unwrap();
+    let schedules = scraper
+        .check_availability_full(&request)
+        .await
+        .unwrap()
+        .into_schedules();
 
     // Should have schedules from all 3 days
     assert_eq!(schedules.len(), 3);
 }
 
+#[tokio::test]
+async fn test_check_availability_full_skips_dates_without_service() {
+    let mock_server = MockServer::start().await;
+
+    // The 16th is not in the sales calendar
+    let dates_xml = r#"<?xml version="1.0" encoding="UTF-8" ?>
+<results>
+<rosen>
+    <num>2</num>
+    <id>20250115</id>
+        <name>2025/01/15(水)</name>
+    <id>20250117</id>
+        <name>2025/01/17(金)</name>
+</rosen>
+</results>"#;
+
+    Mock::given(method("POST"))
+        .and(path("/ajaxPulldown"))
+        .and(body_string_contains("mode=date"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(dates_xml))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/reservation/rsvPlanList"))
+        .and(query_param("bordingDate", "20250116"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/reservation/rsvPlanList"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(r#"<!DOCTYPE html><html><body></body></html>"#),
+        )
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let scraper = BusScraper::new(mock_server.uri()).unwrap();
+
+    let request = ScrapeRequest {
+        area_id: 100,
+        route_id: 110,
+        departure_station: "001".to_string(),
+        arrival_station: "064".to_string(),
+        date_range: DateRange {
+            start: "2025-01-15".to_string(),
+            end: "2025-01-17".to_string(),
+            window: None,
+        },
+        passengers: PassengerCount::default(),
+        time_filter: None,
+        plan_filter: None,
+    };
+
+    let schedules = scraper
+        .check_availability_full(&request)
+        .await
+        .unwrap()
+        .into_schedules();
+
+    assert!(schedules.is_empty());
+}
+
 #[tokio::test]
 async fn test_check_availability_full_with_some_failures() {
     use std::sync::Arc;
@@ -466,7 +992,7 @@ async fn test_check_availability_full_with_some_failures() {
         .mount(&mock_server)
         .await;
 
-    let scraper = BusScraper::new(mock_server.uri()).unwrap();
+    let scraper = scraper_without_retries(mock_server.uri());
 
     let request = ScrapeRequest {
         area_id: 100,
@@ -476,18 +1002,58 @@ async fn test_check_availability_full_with_some_failures() {
         date_range: DateRange {
             start: "2025-01-15".to_string(),
             end: "2025-01-17".to_string(),
+            window: None,
         },
         passengers: PassengerCount::default(),
         time_filter: None,
+        plan_filter: None,
     };
 
-    // Should succeed even with one failure - should have 2 schedules (days 1 and 3)
-    let schedules = scraper.check_availability_full(&request).await.unwrap();
+    // Should succeed even with one failure - should have 2 schedules
+    let check = scraper.check_availability_full(&request).await.unwrap();
+
+    // The failed date is reported instead of looking like a day without buses
+    assert!(!check.is_complete());
+    assert_eq!(check.failed.len(), 1);
+    assert_eq!(check.fetched.len(), 2);
 
     // 2 successful days out of 3
+    let schedules = check.into_schedules();
     assert_eq!(schedules.len(), 2);
 }
 
+#[tokio::test]
+async fn test_check_availability_full_reports_failed_dates() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/reservation/rsvPlanList"))
+        .and(query_param("bordingDate", "20250116"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/reservation/rsvPlanList"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<html><body></body></html>"))
+        .mount(&mock_server)
+        .await;
+
+    let scraper = scraper_without_retries(mock_server.uri());
+    let mut request = test_scrape_request("2025-01-15");
+    request.date_range.end = "2025-01-17".to_string();
+
+    let check = scraper.check_availability_full(&request).await.unwrap();
+
+    assert_eq!(check.failed_dates(), vec!["20250116"]);
+    assert!(matches!(
+        check.failed[0].1,
+        ScraperError::Upstream { status: 500, .. }
+    ));
+    let fetched: Vec<_> = check.fetched.iter().map(|(d, _)| d.as_str()).collect();
+    assert_eq!(fetched, vec!["20250115", "20250117"]);
+}
+
 #[tokio::test]
 async fn test_check_availability_full_empty_results() {
     let mock_server = MockServer::start().await;
@@ -512,16 +1078,88 @@ async fn test_check_availability_full_empty_results() {
         date_range: DateRange {
             start: "2025-01-15".to_string(),
             end: "2025-01-15".to_string(),
+            window: None,
         },
         passengers: PassengerCount::default(),
         time_filter: None,
+        plan_filter: None,
     };
 
-    let schedules = scraper.check_availability_full(&request).await.unwrap();
+    let schedules = scraper
+        .check_availability_full(&request)
+        .await
+        .unwrap()
+        .into_schedules();
 
     assert!(schedules.is_empty());
 }
 
+#[tokio::test]
+async fn test_check_availability_full_fetches_dates_concurrently() {
+    let mock_server = MockServer::start().await;
+
+    let schedules_html = r#"<!DOCTYPE html>
+<html><body>
+    <section class="busSvclistItem">
+        <ul>
+            <li class="dep"><p class="time">9:00 発</p></li>
+            <li class="arr"><p class="time">12:00 着</p></li>
+        </ul>
+        <div class="planArea">
+            <p class="price">3,000円</p>
+            <form name="selectPlan">
+                <input type="hidden" class="seat_0" value="1" data-index="0">
+                <button>残り3席</button>
+            </form>
+        </div>
+    </section>
+</body></html>"#;
+
+    Mock::given(method("GET"))
+        .and(path("/reservation/rsvPlanList"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(schedules_html)
+                .set_delay(Duration::from_millis(300)),
+        )
+        .expect(6)
+        .mount(&mock_server)
+        .await;
+
+    let scraper = BusScraper::new(mock_server.uri())
+        .unwrap()
+        .with_rate_limit(RateLimitConfig {
+            max_concurrent: 3,
+            min_interval: Duration::from_millis(10),
+        });
+
+    let mut request = test_scrape_request("2025-01-15");
+    request.date_range.end = "2025-01-20".to_string();
+
+    let start = Instant::now();
+    let schedules = scraper
+        .check_availability_full(&request)
+        .await
+        .unwrap()
+        .into_schedules();
+    let elapsed = start.elapsed();
+
+    // Two waves of three instead of six sequential requests
+    assert!(elapsed >= Duration::from_millis(600));
+    assert!(elapsed < Duration::from_millis(1500));
+
+    let dates: Vec<_> = schedules
+        .iter()
+        .map(|s| s.departure_date.as_str())
+        .collect();
+    assert_eq!(
+        dates,
+        [
+            "20250115", "20250116", "20250117", "20250118", "20250119", "20250120"
+        ]
+    );
+}
+
 #[tokio::test]
 async fn test_check_availability_full_single_date() {
     let mock_server = MockServer::start().await;
@@ -562,12 +1200,18 @@ async fn test_check_availability_full_single_date() {
         date_range: DateRange {
             start: "2025-01-20".to_string(),
             end: "2025-01-20".to_string(),
+            window: None,
         },
         passengers: PassengerCount::default(),
         time_filter: None,
+        plan_filter: None,
     };
 
-    let schedules = scraper.check_availability_full(&request).await.unwrap();
+    let schedules = scraper
+        .check_availability_full(&request)
+        .await
+        .unwrap()
+        .into_schedules();
 
     assert_eq!(schedules.len(), 1);
     assert_eq!(schedules[0].departure_time, "8:30");
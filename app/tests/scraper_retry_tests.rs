@@ -1,19 +1,12 @@
 //! Retry logic tests for scraper.rs
 //!
-//! Tests verify the `fetch_with_retry` mechanism and documents current behavior:
-//! - HTTP 503 responses become `InvalidResponse` (no retry)
-//! - Only reqwest-level errors with 503 status trigger `ServiceUnavailable` retry
-//! - Non-503 errors don't retry
+//! Tests verify the retry behavior shared by the `ajaxPulldown` POSTs and the
+//! `rsvPlanList` schedule page:
+//! - 429 and 5xx responses become `Upstream` and are retried up to `max_retries`
+//! - Other HTTP errors (400, 404) become `InvalidResponse` and are not retried
+//! - A `Retry-After` header replaces the computed backoff
 //!
-//! IMPORTANT: The retry logic (lines 192-200) exists but is only triggered by
-//! `ServiceUnavailable` errors. However, `fetch_data` converts HTTP 503 responses
-//! to `InvalidResponse` (line 217-222), not `ServiceUnavailable`. `ServiceUnavailable`
-//! is only returned when reqwest itself encounters 503 during connection handling,
-//! which is extremely rare in practice.
-//!
-//! These tests document this behavior and verify that:
-//! 1. HTTP error responses (400, 404, 500, 503) don't retry
-//! 2. The retry logic itself is correct (if `ServiceUnavailable` could be triggered)
+//! Scrapers use a policy with millisecond delays so the tests stay fast.
 #![allow(
     clippy::unwrap_used,
     clippy::expect_used,
@@ -25,12 +18,43 @@
     clippy::assertions_on_constants
 )]
 
+use app::error::ScraperError;
+use app::retry::RetryPolicy;
 use app::scraper::BusScraper;
+use app::types::{DateRange, PassengerCount, ScrapeRequest};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant};
 use wiremock::matchers::{body_string_contains, method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
+/// Helper to create a scraper that retries twice with near-zero delays
+fn fast_scraper(uri: String) -> BusScraper {
+    BusScraper::new(uri)
+        .unwrap()
+        .with_retry_policy(RetryPolicy {
+            max_retries: 2,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(10),
+        })
+}
+
+/// Helper to count requests and answer with `status` the first `failures` times
+fn flaky(
+    call_count: Arc<AtomicU32>,
+    failures: u32,
+    status: u16,
+    body: String,
+) -> impl Fn(&wiremock::Request) -> ResponseTemplate {
+    move |_req: &wiremock::Request| {
+        if call_count.fetch_add(1, Ordering::SeqCst) < failures {
+            ResponseTemplate::new(status)
+        } else {
+            ResponseTemplate::new(200).set_body_string(body.clone())
+        }
+    }
+}
+
 /// Helper to create test XML response
 fn routes_xml() -> String {
     r#"<?xml version="1.0" encoding="UTF-8"?>
@@ -42,68 +66,96 @@ fn routes_xml() -> String {
         .to_string()
 }
 
-// === ACTUAL BEHAVIOR: HTTP ERROR RESPONSES DON'T RETRY ===
+// === RETRYABLE RESPONSES ===
 
 #[tokio::test]
-async fn test_http_503_becomes_invalid_response_no_retry() {
+async fn test_http_5xx_retried_up_to_max() {
+    for status in [500, 502, 503] {
+        let mock_server = MockServer::start().await;
+        let call_count = Arc::new(AtomicU32::new(0));
+
+        Mock::given(method("POST"))
+            .and(path("/ajaxPulldown"))
+            .and(body_string_contains("mode=line%3Afull"))
+            .respond_with(flaky(call_count.clone(), u32::MAX, status, String::new()))
+            .mount(&mock_server)
+            .await;
+
+        let scraper = fast_scraper(mock_server.uri());
+        let err = scraper.fetch_routes(100).await.unwrap_err();
+
+        assert!(
+            matches!(err, ScraperError::Upstream { status: s, .. } if s == status),
+            "{err:?}"
+        );
+        assert!(err.to_string().contains(&status.to_string()));
+        // First attempt plus two retries
+        assert_eq!(call_count.load(Ordering::SeqCst), 3);
+    }
+}
+
+#[tokio::test]
+async fn test_http_429_retried() {
     let mock_server = MockServer::start().await;
     let call_count = Arc::new(AtomicU32::new(0));
-    let call_count_clone = call_count.clone();
 
     Mock::given(method("POST"))
         .and(path("/ajaxPulldown"))
-        .and(body_string_contains("mode=line%3Afull"))
-        .respond_with(move |_req: &wiremock::Request| {
-            call_count_clone.fetch_add(1, Ordering::SeqCst);
-            ResponseTemplate::new(503)
-        })
+        .respond_with(flaky(call_count.clone(), u32::MAX, 429, String::new()))
         .mount(&mock_server)
         .await;
 
-    let scraper = BusScraper::new(mock_server.uri()).unwrap();
-    let result = scraper.fetch_routes(100).await;
+    let scraper = fast_scraper(mock_server.uri());
+    let err = scraper.fetch_routes(100).await.unwrap_err();
 
-    assert!(result.is_err());
-    let err = result.unwrap_err();
+    assert!(matches!(err, ScraperError::Upstream { status: 429, .. }));
+    assert_eq!(call_count.load(Ordering::SeqCst), 3);
+}
 
-    // HTTP 503 becomes InvalidResponse, not ServiceUnavailable
-    assert!(err.to_string().contains("Invalid response"));
-    assert!(err.to_string().contains("503"));
+#[tokio::test]
+async fn test_success_after_transient_failure() {
+    let mock_server = MockServer::start().await;
+    let call_count = Arc::new(AtomicU32::new(0));
 
-    // No retry happens - only one attempt
-    assert_eq!(call_count.load(Ordering::SeqCst), 1);
+    Mock::given(method("POST"))
+        .and(path("/ajaxPulldown"))
+        .and(body_string_contains("mode=line%3Afull"))
+        .respond_with(flaky(call_count.clone(), 1, 503, routes_xml()))
+        .mount(&mock_server)
+        .await;
+
+    let scraper = fast_scraper(mock_server.uri());
+    let routes = scraper.fetch_routes(100).await.unwrap();
+
+    assert_eq!(routes.len(), 1);
+    assert_eq!(call_count.load(Ordering::SeqCst), 2);
 }
 
 #[tokio::test]
-async fn test_http_400_no_retry() {
+async fn test_retries_disabled() {
     let mock_server = MockServer::start().await;
     let call_count = Arc::new(AtomicU32::new(0));
-    let call_count_clone = call_count.clone();
 
     Mock::given(method("POST"))
         .and(path("/ajaxPulldown"))
-        .and(body_string_contains("mode=line%3Afull"))
-        .respond_with(move |_req: &wiremock::Request| {
-            call_count_clone.fetch_add(1, Ordering::SeqCst);
-            ResponseTemplate::new(400)
-        })
+        .respond_with(flaky(call_count.clone(), u32::MAX, 503, String::new()))
         .mount(&mock_server)
         .await;
 
-    let scraper = BusScraper::new(mock_server.uri()).unwrap();
+    let scraper = BusScraper::new(mock_server.uri())
+        .unwrap()
+        .with_retry_policy(RetryPolicy {
+            max_retries: 0,
+            ..RetryPolicy::default()
+        });
     let result = scraper.fetch_routes(100).await;
 
     assert!(result.is_err());
-    let err = result.unwrap_err();
-    assert!(err.to_string().contains("Invalid response"));
-    assert!(err.to_string().contains("400"));
-
-    // Should only attempt once (no retries)
     assert_eq!(call_count.load(Ordering::SeqCst), 1);
 }
 
 #[tokio::test]
-async fn test_http_404_no_retry() {
+async fn test_retry_after_header_honored() {
     let mock_server = MockServer::start().await;
     let call_count = Arc::new(AtomicU32::new(0));
     let call_count_clone = call_count.clone();
@@ -111,24 +163,74 @@ async fn test_http_404_no_retry() {
     Mock::given(method("POST"))
         .and(path("/ajaxPulldown"))
         .respond_with(move |_req: &wiremock::Request| {
-            call_count_clone.fetch_add(1, Ordering::SeqCst);
-            ResponseTemplate::new(404)
+            if call_count_clone.fetch_add(1, Ordering::SeqCst) == 0 {
+                ResponseTemplate::new(429).insert_header("Retry-After", "1")
+            } else {
+                ResponseTemplate::new(200).set_body_string(routes_xml())
+            }
         })
         .mount(&mock_server)
         .await;
 
-    let scraper = BusScraper::new(mock_server.uri()).unwrap();
-    let result = scraper.fetch_routes(100).await;
+    let scraper = BusScraper::new(mock_server.uri())
+        .unwrap()
+        .with_retry_policy(RetryPolicy {
+            max_retries: 1,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_secs(5),
+        });
 
-    assert!(result.is_err());
-    let err = result.unwrap_err();
-    assert!(err.to_string().contains("Invalid response"));
-    assert!(err.to_string().contains("404"));
-    assert_eq!(call_count.load(Ordering::SeqCst), 1);
+    let start = Instant::now();
+    let routes = scraper.fetch_routes(100).await.unwrap();
+
+    // The one-second Retry-After wins over the 1ms backoff
+    assert!(start.elapsed() >= Duration::from_secs(1));
+    assert_eq!(routes.len(), 1);
+    assert_eq!(call_count.load(Ordering::SeqCst), 2);
 }
 
 #[tokio::test]
-async fn test_http_500_no_retry() {
+async fn test_schedule_page_retried() {
+    let mock_server = MockServer::start().await;
+    let call_count = Arc::new(AtomicU32::new(0));
+
+    Mock::given(method("GET"))
+        .and(path("/reservation/rsvPlanList"))
+        .respond_with(flaky(
+            call_count.clone(),
+            2,
+            503,
+            "<html><body></body></html>".to_string(),
+        ))
+        .mount(&mock_server)
+        .await;
+
+    let scraper = fast_scraper(mock_server.uri());
+    let request = ScrapeRequest {
+        area_id: 100,
+        route_id: 110,
+        departure_station: "001".to_string(),
+        arrival_station: "064".to_string(),
+        date_range: DateRange {
+            start: "2025-01-15".to_string(),
+            end: "2025-01-15".to_string(),
+            window: None,
+        },
+        passengers: PassengerCount::default(),
+        time_filter: None,
+        plan_filter: None,
+    };
+
+    let schedules = scraper.fetch_schedules(&request, "20250115").await.unwrap();
+
+    assert!(schedules.is_empty());
+    assert_eq!(call_count.load(Ordering::SeqCst), 3);
+}
+
+// === NON-RETRYABLE RESPONSES ===
+
+#[tokio::test]
+async fn test_http_400_no_retry() {
     let mock_server = MockServer::start().await;
     let call_count = Arc::new(AtomicU32::new(0));
     let call_count_clone = call_count.clone();
@@ -138,25 +240,25 @@ async fn test_http_500_no_retry() {
         .and(body_string_contains("mode=line%3Afull"))
         .respond_with(move |_req: &wiremock::Request| {
             call_count_clone.fetch_add(1, Ordering::SeqCst);
-            ResponseTemplate::new(500)
+            ResponseTemplate::new(400)
         })
         .mount(&mock_server)
         .await;
 
-    let scraper = BusScraper::new(mock_server.uri()).unwrap();
+    let scraper = fast_scraper(mock_server.uri());
     let result = scraper.fetch_routes(100).await;
 
     assert!(result.is_err());
     let err = result.unwrap_err();
     assert!(err.to_string().contains("Invalid response"));
-    assert!(err.to_string().contains("500"));
+    assert!(err.to_string().contains("400"));
 
-    // Should only attempt once (no retries for 500)
+    // Should only attempt once (no retries)
     assert_eq!(call_count.load(Ordering::SeqCst), 1);
 }
 
 #[tokio::test]
-async fn test_http_502_no_retry() {
+async fn test_http_404_no_retry() {
     let mock_server = MockServer::start().await;
     let call_count = Arc::new(AtomicU32::new(0));
     let call_count_clone = call_count.clone();
@@ -165,18 +267,18 @@ async fn test_http_502_no_retry() {
         .and(path("/ajaxPulldown"))
         .respond_with(move |_req: &wiremock::Request| {
             call_count_clone.fetch_add(1, Ordering::SeqCst);
-            ResponseTemplate::new(502)
+            ResponseTemplate::new(404)
         })
         .mount(&mock_server)
         .await;
 
-    let scraper = BusScraper::new(mock_server.uri()).unwrap();
+    let scraper = fast_scraper(mock_server.uri());
     let result = scraper.fetch_routes(100).await;
 
     assert!(result.is_err());
     let err = result.unwrap_err();
     assert!(err.to_string().contains("Invalid response"));
-    assert!(err.to_string().contains("502"));
+    assert!(err.to_string().contains("404"));
     assert_eq!(call_count.load(Ordering::SeqCst), 1);
 }
 
@@ -207,57 +309,15 @@ async fn test_success_on_first_try() {
     assert_eq!(call_count.load(Ordering::SeqCst), 1);
 }
 
-// === DOCUMENTATION: HOW RETRY LOGIC WOULD WORK IF TRIGGERED ===
-
-/// This test documents how the retry logic WOULD work if ServiceUnavailable
-/// could be triggered. In practice, this requires mocking the scraper internals
-/// or triggering specific reqwest error conditions.
-///
-/// The retry mechanism in fetch_with_retry (lines 192-200):
-/// - Retries up to MAX_RETRIES (3) times
-/// - Uses exponential backoff: attempt_num * RETRY_DELAY_MS (1000ms)
-/// - Only retries on ServiceUnavailable error
-/// - Returns immediately on any other error
-///
-/// Example retry delays:
-/// - Attempt 1 fails -> wait 1000ms
-/// - Attempt 2 fails -> wait 2000ms
-/// - Attempt 3 fails -> return error (no more retries)
-#[test]
-fn test_retry_logic_documentation() {
-    // This test documents the expected behavior without running actual retries
-    // since HTTP 503 responses don't trigger ServiceUnavailable.
-
-    const MAX_RETRIES: u32 = 3;
-    const RETRY_DELAY_MS: u64 = 1000;
-
-    // Verify retry count
-    assert_eq!(MAX_RETRIES, 3, "MAX_RETRIES should be 3");
-
-    // Verify delay calculation for each attempt
-    let delays: Vec<u64> = (1..=MAX_RETRIES)
-        .map(|n| RETRY_DELAY_MS * u64::from(n))
-        .collect();
-
-    assert_eq!(delays[0], 1000); // First retry: 1s
-    assert_eq!(delays[1], 2000); // Second retry: 2s
-    assert_eq!(delays[2], 3000); // Third retry: 3s
-
-    // Total maximum delay if all retries fail
-    let total_delay: u64 = delays.iter().sum();
-    assert_eq!(total_delay, 6000); // 6 seconds total
-}
-
 // === EDGE CASES ===
 
 #[tokio::test]
-async fn test_connection_timeout_no_retry() {
+async fn test_slow_response_within_timeout_no_retry() {
     let mock_server = MockServer::start().await;
     let call_count = Arc::new(AtomicU32::new(0));
     let call_count_clone = call_count.clone();
 
-    // Simulate slow response that exceeds reqwest timeout (30s configured)
-    // This would cause a timeout error, which becomes Http error, not ServiceUnavailable
+    // Slow response that stays under the reqwest timeout (30s configured)
     Mock::given(method("POST"))
         .and(path("/ajaxPulldown"))
         .respond_with(move |_req: &wiremock::Request| {
@@ -321,34 +381,3 @@ async fn test_malformed_xml_returns_empty_vec_no_retry() {
     // No retry happens (HTTP 200 is success)
     assert_eq!(call_count.load(Ordering::SeqCst), 1);
 }
-
-// === COVERAGE: RETRY LOGIC PATH ===
-
-/// This test attempts to exercise the retry logic by demonstrating the gap:
-/// fetch_with_retry's retry mechanism (lines 192-200) is unreachable because
-/// fetch_data converts all non-2xx HTTP responses to InvalidResponse, including 503.
-///
-/// To achieve coverage of lines 192-200, one would need to either:
-/// 1. Refactor fetch_data to return ServiceUnavailable for 503 status codes
-/// 2. Mock internal scraper methods to inject ServiceUnavailable errors
-/// 3. Trigger connection-level reqwest errors that include 503 status
-#[test]
-fn test_retry_logic_unreachable_coverage_gap() {
-    // This test documents that the retry logic exists but is unreachable
-    // with the current fetch_data implementation.
-
-    // The retry condition: Err(ScraperError::ServiceUnavailable) if attempts < MAX_RETRIES
-    // is at line 192, but fetch_data converts HTTP 503 to InvalidResponse at line 217.
-
-    // To fix this and make retry logic reachable:
-    // In fetch_data, change line 217-222 to:
-    //
-    // if response.status() == StatusCode::SERVICE_UNAVAILABLE {
-    //     return Err(ScraperError::ServiceUnavailable);
-    // }
-    // if !response.status().is_success() {
-    //     return Err(ScraperError::InvalidResponse(...));
-    // }
-
-    assert!(true, "This test documents the coverage gap in retry logic");
-}
@@ -11,7 +11,9 @@
 
 use app::db::init_database;
 use app::entities::{prelude::*, users};
+use app::repositories::get_enabled_notification_channels;
 use app::seed::seed_from_env;
+use app::types::{ChannelKind, NotificationChannel};
 use migration::{Migrator, MigratorTrait};
 use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
 use serial_test::serial;
@@ -147,10 +149,23 @@ async fn test_seed_discord_webhook_url() {
             .unwrap()
             .unwrap();
 
+        let channels = get_enabled_notification_channels(&db, user.id)
+            .await
+            .unwrap();
         assert_eq!(
-            user.discord_webhook_url,
-            Some("https://discord.webhook/test".to_string())
+            channels,
+            vec![NotificationChannel {
+                kind: ChannelKind::Discord,
+                target: "https://discord.webhook/test".to_string(),
+            }]
         );
+
+        // Seeding again replaces the channel instead of duplicating it
+        seed_from_env(&db).await.unwrap();
+        let channels = get_enabled_notification_channels(&db, user.id)
+            .await
+            .unwrap();
+        assert_eq!(channels.len(), 1);
     })
     .await;
 }
@@ -0,0 +1,172 @@
+//! Integration tests for the Slack notifier using wiremock
+//!
+//! Tests Slack incoming-webhook notifications with mocked HTTP server
+#![allow(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::doc_markdown,
+    clippy::uninlined_format_args
+)]
+
+use app::notifier::{NotificationContext, NotificationDispatcher, Notifier, SlackNotifier};
+use app::types::{
+    BusSchedule, ChannelKind, Locale, NotificationChannel, PricingPlan, SeatAvailability,
+};
+use wiremock::matchers::{body_partial_json, body_string_contains, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn test_bus_schedule() -> BusSchedule {
+    BusSchedule {
+        bus_number: "Bus_1".to_string(),
+        route_name: "Test Route".to_string(),
+        departure_station: "001".to_string(),
+        departure_date: "20250115".to_string(),
+        departure_time: "08:30".to_string(),
+        arrival_station: "064".to_string(),
+        arrival_date: "20250115".to_string(),
+        arrival_time: "10:45".to_string(),
+        way_no: 1,
+        available_plans: vec![PricingPlan {
+            plan_id: 12345,
+            plan_index: 0,
+            plan_name: "Standard".to_string(),
+            price: 2100,
+            display_price: "2,100円".to_string(),
+            availability: SeatAvailability::Available {
+                remaining_seats: Some(5),
+            },
+            details: None,
+        }],
+        booking_url: None,
+    }
+}
+
+fn test_context() -> NotificationContext {
+    NotificationContext {
+        departure_station_name: "Shinjuku".to_string(),
+        arrival_station_name: "Kawaguchiko".to_string(),
+        route_name: String::new(),
+        date_range: ("20250115".to_string(), "20250120".to_string()),
+        passenger_count: 2,
+        time_filter: None,
+        snooze_url: None,
+        availability_url: None,
+        locale: Locale::default(),
+    }
+}
+
+#[tokio::test]
+async fn test_send_startup_notification_success() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/services/T000/B000/XXX"))
+        .and(body_partial_json(
+            serde_json::json!({ "text": "✅ Bot démarré" }),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let notifier = SlackNotifier::new();
+    let webhook_url = format!("{}/services/T000/B000/XXX", mock_server.uri());
+
+    let result = notifier
+        .send_startup_notification(&webhook_url, 5, 10, Locale::Fr)
+        .await;
+
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_send_availability_alert_posts_block_kit() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/services/T000/B000/XXX"))
+        .and(body_partial_json(serde_json::json!({
+            "blocks": [{ "type": "header", "text": { "text": "🚌 Bus disponibles !" } }]
+        })))
+        .and(body_string_contains("Shinjuku → Kawaguchiko"))
+        .and(body_string_contains("5 sièges"))
+        .and(body_string_contains("2,100円"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let notifier = SlackNotifier::new();
+    let webhook_url = format!("{}/services/T000/B000/XXX", mock_server.uri());
+
+    let result = notifier
+        .send_availability_alert(&webhook_url, &[test_bus_schedule()], &[], &test_context())
+        .await;
+
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_send_availability_alert_empty_schedules() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&mock_server)
+        .await;
+
+    let notifier = SlackNotifier::new();
+    let webhook_url = format!("{}/services/T000/B000/XXX", mock_server.uri());
+
+    let result = notifier
+        .send_availability_alert(&webhook_url, &[], &[], &test_context())
+        .await;
+
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_send_availability_alert_failure_reported() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(404).set_body_string("no_service"))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let notifier = SlackNotifier::new();
+    let webhook_url = format!("{}/services/T000/B000/XXX", mock_server.uri());
+
+    let result = notifier
+        .send_availability_alert(&webhook_url, &[test_bus_schedule()], &[], &test_context())
+        .await;
+
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_dispatcher_routes_slack_channel() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/slack"))
+        .and(body_string_contains("blocks"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let channels = vec![NotificationChannel {
+        kind: ChannelKind::Slack,
+        target: format!("{}/slack", mock_server.uri()),
+    }];
+
+    let result = NotificationDispatcher::new()
+        .send_availability_alert(&channels, &[test_bus_schedule()], &[], &test_context())
+        .await;
+
+    assert!(result.is_ok());
+}
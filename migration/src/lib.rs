@@ -10,6 +10,20 @@ mod m20251014_060000_alter_stations_add_columns;
 mod m20251212_000001_fix_route_id_types;
 mod m20251212_000002_seed_routes_data;
 mod m20251212_000003_drop_routes_stations_tables;
+mod m20261016_000001_create_availability_snapshots;
+mod m20261016_000002_create_notification_channels;
+mod m20261017_000001_add_plan_filters_to_user_routes;
+mod m20261017_000002_add_date_window_to_user_routes;
+mod m20261017_000003_add_status_to_user_routes;
+mod m20261017_000004_add_pause_to_user_routes;
+mod m20261017_000005_add_polling_to_user_routes;
+mod m20261017_000006_add_quiet_hours_to_users;
+mod m20261017_000007_add_last_snapshot_to_route_states;
+mod m20261017_000008_add_notify_on_sold_out_to_user_routes;
+mod m20261017_000009_create_notification_outbox;
+mod m20261017_000010_add_locale_to_users;
+mod m20261017_000011_add_digest_to_notification_outbox;
+mod m20261017_000012_add_messages_sent_to_notification_outbox;
 
 pub struct Migrator;
 
@@ -27,6 +41,20 @@ impl MigratorTrait for Migrator {
             Box::new(m20251212_000001_fix_route_id_types::Migration),
             Box::new(m20251212_000002_seed_routes_data::Migration),
             Box::new(m20251212_000003_drop_routes_stations_tables::Migration),
+            Box::new(m20261016_000001_create_availability_snapshots::Migration),
+            Box::new(m20261016_000002_create_notification_channels::Migration),
+            Box::new(m20261017_000001_add_plan_filters_to_user_routes::Migration),
+            Box::new(m20261017_000002_add_date_window_to_user_routes::Migration),
+            Box::new(m20261017_000003_add_status_to_user_routes::Migration),
+            Box::new(m20261017_000004_add_pause_to_user_routes::Migration),
+            Box::new(m20261017_000005_add_polling_to_user_routes::Migration),
+            Box::new(m20261017_000006_add_quiet_hours_to_users::Migration),
+            Box::new(m20261017_000007_add_last_snapshot_to_route_states::Migration),
+            Box::new(m20261017_000008_add_notify_on_sold_out_to_user_routes::Migration),
+            Box::new(m20261017_000009_create_notification_outbox::Migration),
+            Box::new(m20261017_000010_add_locale_to_users::Migration),
+            Box::new(m20261017_000011_add_digest_to_notification_outbox::Migration),
+            Box::new(m20261017_000012_add_messages_sent_to_notification_outbox::Migration),
         ]
     }
 }
@@ -0,0 +1,75 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AvailabilitySnapshots::Table)
+                    .if_not_exists()
+                    .col(uuid(AvailabilitySnapshots::Id).primary_key())
+                    .col(uuid(AvailabilitySnapshots::UserRouteId))
+                    .col(timestamp(AvailabilitySnapshots::CheckedAt))
+                    .col(string(AvailabilitySnapshots::DepartureDate))
+                    .col(string(AvailabilitySnapshots::DepartureTime))
+                    .col(big_integer(AvailabilitySnapshots::PlanId))
+                    .col(string(AvailabilitySnapshots::Status))
+                    .col(integer_null(AvailabilitySnapshots::RemainingSeats))
+                    .col(big_integer(AvailabilitySnapshots::Price))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_availability_snapshots_user_route_id")
+                            .from(
+                                AvailabilitySnapshots::Table,
+                                AvailabilitySnapshots::UserRouteId,
+                            )
+                            .to(UserRoutes::Table, UserRoutes::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_availability_snapshots_route_checked_at")
+                    .table(AvailabilitySnapshots::Table)
+                    .col(AvailabilitySnapshots::UserRouteId)
+                    .col(AvailabilitySnapshots::CheckedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AvailabilitySnapshots::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AvailabilitySnapshots {
+    Table,
+    Id,
+    UserRouteId,
+    CheckedAt,
+    DepartureDate,
+    DepartureTime,
+    PlanId,
+    Status,
+    RemainingSeats,
+    Price,
+}
+
+#[derive(DeriveIden)]
+enum UserRoutes {
+    Table,
+    Id,
+}
@@ -0,0 +1,114 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(NotificationChannels::Table)
+                    .if_not_exists()
+                    .col(uuid(NotificationChannels::Id).primary_key())
+                    .col(uuid(NotificationChannels::UserId))
+                    .col(string(NotificationChannels::Kind))
+                    .col(string(NotificationChannels::Target))
+                    .col(boolean(NotificationChannels::Enabled).default(true))
+                    .col(
+                        timestamp(NotificationChannels::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_notification_channels_user_id")
+                            .from(NotificationChannels::Table, NotificationChannels::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_notification_channels_user_id")
+                    .table(NotificationChannels::Table)
+                    .col(NotificationChannels::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Move existing Discord webhooks into the new table before dropping the column
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r"
+                INSERT INTO notification_channels (id, user_id, kind, target, enabled, created_at)
+                SELECT randomblob(16), id, 'discord', discord_webhook_url, 1, CURRENT_TIMESTAMP
+                FROM users
+                WHERE discord_webhook_url IS NOT NULL AND discord_webhook_url != '';
+                ",
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .drop_column(Users::DiscordWebhookUrl)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column(string_null(Users::DiscordWebhookUrl))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Only one webhook fits in the old column: keep the oldest Discord channel
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r"
+                UPDATE users SET discord_webhook_url = (
+                    SELECT target FROM notification_channels
+                    WHERE notification_channels.user_id = users.id AND kind = 'discord'
+                    ORDER BY created_at LIMIT 1
+                );
+                ",
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(NotificationChannels::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum NotificationChannels {
+    Table,
+    Id,
+    UserId,
+    Kind,
+    Target,
+    Enabled,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+    DiscordWebhookUrl,
+}
@@ -0,0 +1,60 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite only accepts one column per ALTER TABLE
+        for column in [
+            integer_null(UserRoutes::MaxPrice),
+            string_null(UserRoutes::SeatType),
+            string_null(UserRoutes::PlanIdsAllow),
+            string_null(UserRoutes::PlanIdsDeny),
+            integer_null(UserRoutes::MinRemainingSeats),
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(UserRoutes::Table)
+                        .add_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            UserRoutes::MaxPrice,
+            UserRoutes::SeatType,
+            UserRoutes::PlanIdsAllow,
+            UserRoutes::PlanIdsDeny,
+            UserRoutes::MinRemainingSeats,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(UserRoutes::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserRoutes {
+    Table,
+    MaxPrice,
+    SeatType,
+    PlanIdsAllow,
+    PlanIdsDeny,
+    MinRemainingSeats,
+}
@@ -0,0 +1,50 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite only accepts one column per ALTER TABLE
+        for column in [
+            big_integer_null(UserRoutes::ScrapeIntervalSecs),
+            boolean(UserRoutes::AdaptiveInterval)
+                .default(false)
+                .to_owned(),
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(UserRoutes::Table)
+                        .add_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [UserRoutes::ScrapeIntervalSecs, UserRoutes::AdaptiveInterval] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(UserRoutes::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserRoutes {
+    Table,
+    ScrapeIntervalSecs,
+    AdaptiveInterval,
+}
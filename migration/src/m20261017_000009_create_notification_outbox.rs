@@ -0,0 +1,90 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(NotificationOutbox::Table)
+                    .if_not_exists()
+                    .col(uuid(NotificationOutbox::Id).primary_key())
+                    .col(uuid(NotificationOutbox::UserRouteId))
+                    .col(string(NotificationOutbox::ChannelKind))
+                    .col(string(NotificationOutbox::Target))
+                    .col(text(NotificationOutbox::Payload))
+                    .col(string(NotificationOutbox::Status).default("pending"))
+                    .col(integer(NotificationOutbox::Attempts).default(0))
+                    .col(timestamp(NotificationOutbox::NextAttemptAt))
+                    .col(text_null(NotificationOutbox::LastError))
+                    .col(
+                        timestamp(NotificationOutbox::CreatedAt).default(Expr::current_timestamp()),
+                    )
+                    .col(timestamp_null(NotificationOutbox::DeliveredAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_notification_outbox_user_route_id")
+                            .from(NotificationOutbox::Table, NotificationOutbox::UserRouteId)
+                            .to(UserRoutes::Table, UserRoutes::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // The delivery worker polls for pending rows that are due
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_notification_outbox_status_next_attempt_at")
+                    .table(NotificationOutbox::Table)
+                    .col(NotificationOutbox::Status)
+                    .col(NotificationOutbox::NextAttemptAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_notification_outbox_route_created_at")
+                    .table(NotificationOutbox::Table)
+                    .col(NotificationOutbox::UserRouteId)
+                    .col(NotificationOutbox::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(NotificationOutbox::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum NotificationOutbox {
+    Table,
+    Id,
+    UserRouteId,
+    ChannelKind,
+    Target,
+    Payload,
+    Status,
+    Attempts,
+    NextAttemptAt,
+    LastError,
+    CreatedAt,
+    DeliveredAt,
+}
+
+#[derive(DeriveIden)]
+enum UserRoutes {
+    Table,
+    Id,
+}
@@ -3,13 +3,27 @@
 mod tracker;
 mod tracker_impl;
 
-use app::{components::App, db, scraper::BusScraper};
+use app::{
+    api_impl,
+    circuit_breaker::CircuitBreakerConfig,
+    components::App,
+    db,
+    error::ScraperError,
+    notifier::{LinkPage, link_page_html},
+    rate_limiter::RateLimitConfig,
+    repositories::{get_route_owner_locale, get_route_state},
+    response_cache::ResponseCache,
+    retry::RetryPolicy,
+    route_events::{RouteChange, RouteChangeSender, route_change_channel},
+    scraper::BusScraper,
+    types::Locale,
+};
 use axum::extract::FromRef;
 use axum::{
     Router,
     body::Body,
-    extract::State,
-    http::Request,
+    extract::{Path, State},
+    http::{Request, StatusCode},
     response::{Html, IntoResponse, Response},
     routing::get,
 };
@@ -28,6 +42,7 @@ pub struct AppState {
     pub leptos_options: LeptosOptions,
     pub db: DatabaseConnection,
     pub scraper: Arc<BusScraper>,
+    pub route_changes: RouteChangeSender,
 }
 
 impl FromRef<AppState> for LeptosOptions {
@@ -53,11 +68,15 @@ async fn main() -> anyhow::Result<()> {
     // Create scraper for live API fetching
     let base_url =
         dotenvy::var("BASE_URL").unwrap_or_else(|_| "https://www.highwaybus.com/gp".to_string());
-    let scraper = Arc::new(BusScraper::new(base_url)?);
+    let scraper = Arc::new(
+        BusScraper::new(base_url)?
+            .with_rate_limit(RateLimitConfig::from_env())
+            .with_retry_policy(RetryPolicy::from_env())
+            .with_circuit_breaker(CircuitBreakerConfig::from_env())
+            .with_schedule_cache_ttl(ResponseCache::ttl_from_env()),
+    );
 
-    let should_seed = dotenvy::var("SEED_FROM_ENV")
-        .map(|v| v == "true")
-        .unwrap_or(false);
+    let should_seed = dotenvy::var("SEED_FROM_ENV").is_ok_and(|v| v == "true");
 
     if should_seed {
         info!("Seeding database from .env configuration...");
@@ -70,22 +89,25 @@ async fn main() -> anyhow::Result<()> {
         .site_root("target/site")
         .build();
 
+    let (route_changes_tx, route_changes_rx) = route_change_channel();
+
     let state = AppState {
         leptos_options,
         db: db.clone(),
         scraper: scraper.clone(),
+        route_changes: route_changes_tx,
     };
 
     let db_for_tracker = Arc::new(db);
 
-    let enable_tracker = dotenvy::var("ENABLE_TRACKER")
-        .map(|v| v == "true")
-        .unwrap_or(true);
+    let enable_tracker = dotenvy::var("ENABLE_TRACKER").map_or(true, |v| v == "true");
 
     if enable_tracker {
         let db_clone = Arc::clone(&db_for_tracker);
+        // Shared with the server functions so UI lookups count against the same rate limit
+        let scraper_clone = Arc::clone(&scraper);
         tokio::spawn(async move {
-            if let Err(e) = tracker::run_tracker(db_clone).await {
+            if let Err(e) = tracker::run_tracker(db_clone, scraper_clone, route_changes_rx).await {
                 error!("Tracker error: {}", e);
             }
         });
@@ -98,15 +120,19 @@ async fn main() -> anyhow::Result<()> {
             "/api/{*fn_name}",
             get(server_fn_handler).post(server_fn_handler),
         )
+        .route("/routes/{id}/snooze", get(snooze_page).post(snooze_route))
+        .route("/routes/{id}/availability", get(availability_page))
         .leptos_routes_with_context(
             &state,
             routes,
             {
                 let db = state.db.clone();
                 let scraper = state.scraper.clone();
+                let route_changes = state.route_changes.clone();
                 move || {
                     provide_context(db.clone());
                     provide_context(scraper.clone());
+                    provide_context(route_changes.clone());
                 }
             },
             {
@@ -154,12 +180,125 @@ async fn server_fn_handler(State(state): State<AppState>, req: Request<Body>) ->
         move || {
             provide_context(state.db.clone());
             provide_context(state.scraper.clone());
+            provide_context(state.route_changes.clone());
         },
         req,
     )
     .await
 }
 
+/// Confirmation page behind the snooze link of notifications.
+///
+/// The link only opens this page; link previews and mail scanners follow
+/// links too, so the route is paused by the form it holds.
+async fn snooze_page(State(state): State<AppState>, Path(id): Path<String>) -> Response {
+    let Ok(uuid) = api_impl::parse_uuid(&id) else {
+        return link_page(
+            StatusCode::NOT_FOUND,
+            LinkPage::RouteNotFound,
+            Locale::default(),
+        );
+    };
+
+    match get_route_owner_locale(&state.db, uuid).await {
+        Ok(Some(locale)) => link_page(StatusCode::OK, LinkPage::SnoozeForm, locale),
+        Ok(None) => link_page(
+            StatusCode::NOT_FOUND,
+            LinkPage::RouteNotFound,
+            Locale::default(),
+        ),
+        Err(e) => {
+            error!("Failed to load owner of route {}: {}", uuid, e);
+            link_page(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                LinkPage::SnoozeFailed,
+                Locale::default(),
+            )
+        }
+    }
+}
+
+async fn snooze_route(State(state): State<AppState>, Path(id): Path<String>) -> Response {
+    let Ok(uuid) = api_impl::parse_uuid(&id) else {
+        return link_page(
+            StatusCode::NOT_FOUND,
+            LinkPage::RouteNotFound,
+            Locale::default(),
+        );
+    };
+    let locale = route_locale(&state, uuid).await;
+
+    match api_impl::snooze_user_route_impl(&state.db, uuid).await {
+        Ok(route) => {
+            // The tracker may be disabled, in which case nobody is listening
+            state
+                .route_changes
+                .send(RouteChange::RouteUpdated(uuid))
+                .ok();
+            let until = route
+                .paused_until
+                .and_then(|until| chrono::DateTime::parse_from_rfc3339(&until).ok())
+                .map(|until| until.with_timezone(&chrono::Utc));
+            link_page(StatusCode::OK, LinkPage::Snoozed(until), locale)
+        }
+        Err(ScraperError::NotFound(_)) => {
+            link_page(StatusCode::NOT_FOUND, LinkPage::RouteNotFound, locale)
+        }
+        Err(e) => {
+            error!("Failed to snooze route {}: {}", uuid, e);
+            link_page(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                LinkPage::SnoozeFailed,
+                locale,
+            )
+        }
+    }
+}
+
+/// Every bus of the last check, behind the full-list link of notifications
+async fn availability_page(State(state): State<AppState>, Path(id): Path<String>) -> Response {
+    let Ok(uuid) = api_impl::parse_uuid(&id) else {
+        return link_page(
+            StatusCode::NOT_FOUND,
+            LinkPage::RouteNotFound,
+            Locale::default(),
+        );
+    };
+    let locale = route_locale(&state, uuid).await;
+
+    match get_route_state(&state.db, uuid).await {
+        Ok(Some(route_state)) => link_page(
+            StatusCode::OK,
+            LinkPage::Availability(route_state.last_snapshot.as_deref()),
+            locale,
+        ),
+        Ok(None) => link_page(StatusCode::NOT_FOUND, LinkPage::RouteNotFound, locale),
+        Err(e) => {
+            error!("Failed to load availability of route {}: {}", uuid, e);
+            link_page(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                LinkPage::AvailabilityFailed,
+                locale,
+            )
+        }
+    }
+}
+
+/// Language of the route owner, the default one when it cannot be loaded
+async fn route_locale(state: &AppState, user_route_id: uuid::Uuid) -> Locale {
+    get_route_owner_locale(&state.db, user_route_id)
+        .await
+        .unwrap_or_else(|e| {
+            error!("Failed to load owner of route {}: {}", user_route_id, e);
+            None
+        })
+        .unwrap_or_default()
+}
+
+fn link_page(status: StatusCode, page: LinkPage<'_>, locale: Locale) -> Response {
+    (status, Html(link_page_html(page, locale))).into_response()
+}
+
 async fn file_and_error_handler(State(state): State<AppState>, req: Request<Body>) -> Response {
     let path = req.uri().path();
 
@@ -176,6 +315,7 @@ async fn file_and_error_handler(State(state): State<AppState>, req: Request<Body
         move || {
             provide_context(state.db.clone());
             provide_context(state.scraper.clone());
+            provide_context(state.route_changes.clone());
         },
         move || shell(options.clone()),
     );
@@ -1,87 +1,541 @@
 use crate::tracker_impl;
 use app::{
-    error,
-    notifier::{DiscordNotifier, NotificationContext},
+    availability_diff,
+    circuit_breaker::CircuitState,
+    error::{self, ScraperError},
+    notifier::{
+        NotificationContext, NotificationDispatcher, Notifier, PartialDelivery, QueuedAlert,
+        UpstreamEvent, availability_url, snooze_url,
+    },
     repositories::{
-        UserRouteWithDetails, get_all_active_user_routes, get_route_state, update_route_state,
+        PendingDelivery, UserRouteWithDetails, archive_user_routes, get_all_active_user_routes,
+        get_due_deliveries, get_route_state, mark_delivery_failed, mark_delivery_sent,
+        record_alert, record_availability_snapshots, update_route_state,
     },
+    route_events::RouteChangeReceiver,
     scraper::BusScraper,
-    types::{self, DateRange, PassengerCount, ScrapeRequest, TimeFilter},
+    state_hash,
+    types::{
+        self, ChannelKind, Locale, NotificationChannel, PassengerCount, ScrapeRequest, TimeFilter,
+    },
 };
+use chrono::{DateTime, Local, Utc};
 use sea_orm::DatabaseConnection;
 use std::collections::HashMap;
-use std::collections::{HashSet, hash_map::DefaultHasher};
-use std::hash::{Hash, Hasher};
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::time::Duration;
-use tracing::{error, info, warn};
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+const DEFAULT_RECONCILE_INTERVAL_SECS: u64 = 60;
+/// How often the outbox is checked for retries that are due; new alerts wake the worker up
+const OUTBOX_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+/// Deliveries attempted per pass of the outbox worker
+const OUTBOX_BATCH_SIZE: u64 = 50;
 
 /// Station name cache: `station_id` -> `station_name`
 pub type StationCache = Arc<tokio::sync::RwLock<HashMap<String, String>>>;
 
-pub async fn run_tracker(db: Arc<DatabaseConnection>) -> anyhow::Result<()> {
-    let base_url =
-        dotenvy::var("BASE_URL").unwrap_or_else(|_| "https://www.highwaybus.com/gp".to_string());
+/// Route name cache: `route_id` -> name in the operator's catalog
+pub type RouteNameCache = Arc<tokio::sync::RwLock<HashMap<String, String>>>;
 
-    let scraper = Arc::new(BusScraper::new(base_url)?);
+pub async fn run_tracker(
+    db: Arc<DatabaseConnection>,
+    scraper: Arc<BusScraper>,
+    route_changes: RouteChangeReceiver,
+) -> anyhow::Result<()> {
+    let notifier = Arc::new(NotificationDispatcher::from_env()?);
+    // Without it notifications carry no snooze or full-list link: there is no address to point them at
+    let public_url: Option<Arc<str>> = dotenvy::var("PUBLIC_URL")
+        .ok()
+        .filter(|url| !url.is_empty())
+        .map(Into::into);
 
     let user_routes = get_all_active_user_routes(&db).await?;
 
     if user_routes.is_empty() {
-        warn!("No active user routes found in database");
-        return Ok(());
+        warn!("No active user routes found in database, waiting for routes to be added");
+    } else {
+        info!("Starting tracking for {} user route(s)", user_routes.len());
+
+        let unique_users: HashSet<String> = user_routes.iter().map(|r| r.email.clone()).collect();
+
+        let unique_channels: HashSet<(&NotificationChannel, Locale)> = user_routes
+            .iter()
+            .flat_map(|r| r.notification_channels.iter().map(|c| (c, r.locale)))
+            .collect();
+
+        for (channel, locale) in unique_channels {
+            if let Err(e) = notifier
+                .send_startup_notification(channel, unique_users.len(), user_routes.len(), locale)
+                .await
+            {
+                error!("Failed to send startup notification: {}", e);
+            }
+        }
     }
 
-    info!("Starting tracking for {} user route(s)", user_routes.len());
+    let outbox = Arc::new(Notify::new());
+    let worker = OutboxWorker {
+        db: Arc::clone(&db),
+        notifier: Arc::clone(&notifier),
+        wake: Arc::clone(&outbox),
+    };
+    tokio::spawn(worker.run());
 
-    // Build station cache at startup by fetching all stations for user routes
     let station_cache: StationCache = Arc::new(tokio::sync::RwLock::new(HashMap::new()));
-    info!("Building station name cache from API...");
-    for user_route in &user_routes {
-        if let Err(e) = populate_station_cache(&scraper, &station_cache, &user_route.route_id).await
+    let route_names: RouteNameCache = Arc::new(tokio::sync::RwLock::new(HashMap::new()));
+    let mut supervisor = TrackerSupervisor {
+        scraper,
+        notifier,
+        db,
+        station_cache,
+        route_names,
+        public_url,
+        outbox,
+        trackers: HashMap::new(),
+        upstream_down: false,
+    };
+
+    supervisor.reconcile_with(user_routes).await;
+    supervisor.run(route_changes).await;
+
+    Ok(())
+}
+
+/// A running `UserTracker` task together with the configuration it was started with.
+struct TrackerHandle {
+    user_route: UserRouteWithDetails,
+    task: JoinHandle<()>,
+}
+
+/// Owns one tracker task per `user_route_id` and keeps them in sync with the database.
+///
+/// Reconciliation is triggered by `RouteChange` events from the API write paths and,
+/// as a fallback for changes made outside the API, on a fixed interval.
+struct TrackerSupervisor {
+    scraper: Arc<BusScraper>,
+    notifier: Arc<NotificationDispatcher>,
+    db: Arc<DatabaseConnection>,
+    station_cache: StationCache,
+    route_names: RouteNameCache,
+    /// Address of the web UI, for links in notifications
+    public_url: Option<Arc<str>>,
+    /// Wakes the [`OutboxWorker`] when a tracker queues an alert
+    outbox: Arc<Notify>,
+    trackers: HashMap<Uuid, TrackerHandle>,
+    /// Whether users were told the site is down and not yet that it recovered
+    upstream_down: bool,
+}
+
+impl TrackerSupervisor {
+    async fn run(mut self, mut route_changes: RouteChangeReceiver) {
+        let reconcile_secs = dotenvy::var("TRACKER_RECONCILE_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_RECONCILE_INTERVAL_SECS);
+
+        let mut interval = tokio::time::interval(Duration::from_secs(reconcile_secs));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        // The first tick completes immediately and the initial reconciliation already ran
+        interval.tick().await;
+
+        let mut events_open = true;
+        let mut upstream = self.scraper.watch_upstream();
+        let mut upstream_open = true;
+
+        loop {
+            tokio::select! {
+                change = route_changes.recv(), if events_open => {
+                    if let Some(change) = change {
+                        info!("Route change received: {:?}", change);
+                        // Coalesce bursts of changes into a single reconciliation
+                        while let Ok(change) = route_changes.try_recv() {
+                            info!("Route change received: {:?}", change);
+                        }
+                    } else {
+                        warn!("Route change channel closed, relying on periodic reconciliation");
+                        events_open = false;
+                        continue;
+                    }
+                }
+                changed = upstream.changed(), if upstream_open => {
+                    if changed.is_ok() {
+                        let state = *upstream.borrow_and_update();
+                        self.report_upstream_state(state).await;
+                    } else {
+                        upstream_open = false;
+                    }
+                    continue;
+                }
+                _ = interval.tick() => {}
+            }
+
+            self.reconcile().await;
+        }
+    }
+
+    /// Tell every channel of a running tracker that the site went down or came back
+    async fn report_upstream_state(&mut self, state: CircuitState) {
+        let Some(event) = tracker_impl::upstream_event(self.upstream_down, state) else {
+            return;
+        };
+        self.upstream_down = !self.upstream_down;
+
+        match event {
+            UpstreamEvent::Down => warn!("Upstream site is down, pausing checks"),
+            UpstreamEvent::Recovered => info!("Upstream site recovered, resuming checks"),
+        }
+
+        let channels: HashSet<(&NotificationChannel, Locale)> = self
+            .trackers
+            .values()
+            .flat_map(|handle| {
+                let user_route = &handle.user_route;
+                user_route
+                    .notification_channels
+                    .iter()
+                    .map(|channel| (channel, user_route.locale))
+            })
+            .collect();
+
+        for (channel, locale) in channels {
+            if let Err(e) = self
+                .notifier
+                .send_upstream_status(channel, event, locale)
+                .await
+            {
+                error!("Failed to send upstream status notification: {}", e);
+            }
+        }
+    }
+
+    async fn reconcile(&mut self) {
+        match get_all_active_user_routes(&self.db).await {
+            Ok(user_routes) => self.reconcile_with(user_routes).await,
+            Err(e) => error!("Failed to load active user routes: {}", e),
+        }
+    }
+
+    async fn reconcile_with(&mut self, user_routes: Vec<UserRouteWithDetails>) {
+        let user_routes = self.archive_ended(user_routes).await;
+        let mut desired: HashMap<Uuid, UserRouteWithDetails> = user_routes
+            .into_iter()
+            .map(|r| (r.user_route_id, r))
+            .collect();
+
+        let plan = {
+            let running: HashMap<Uuid, &UserRouteWithDetails> = self
+                .trackers
+                .iter()
+                .map(|(id, handle)| (*id, &handle.user_route))
+                .collect();
+            let wanted: HashMap<Uuid, &UserRouteWithDetails> =
+                desired.iter().map(|(id, route)| (*id, route)).collect();
+            tracker_impl::plan_reconciliation(&running, &wanted)
+        };
+
+        if plan.is_empty() {
+            return;
+        }
+
+        for id in &plan.stop {
+            if let Some(handle) = self.trackers.remove(id) {
+                handle.task.abort();
+                info!("Stopped tracker for route {}", id);
+            }
+        }
+
+        for id in &plan.restart {
+            if let Some(handle) = self.trackers.remove(id) {
+                handle.task.abort();
+            }
+            if let Some(user_route) = desired.remove(id) {
+                info!(
+                    "Restarting tracker for route {} with updated configuration",
+                    id
+                );
+                self.spawn_tracker(user_route).await;
+            }
+        }
+
+        for id in &plan.start {
+            if let Some(user_route) = desired.remove(id) {
+                self.spawn_tracker(user_route).await;
+            }
+        }
+
+        info!(
+            "Tracker reconciliation: {} started, {} restarted, {} stopped ({} running)",
+            plan.start.len(),
+            plan.restart.len(),
+            plan.stop.len(),
+            self.trackers.len()
+        );
+    }
+
+    /// Archive routes whose dates have all passed and tell their owners.
+    ///
+    /// Returns the routes still worth tracking. Routes that fail to be archived
+    /// are left out too, and retried on the next reconciliation.
+    async fn archive_ended(
+        &self,
+        user_routes: Vec<UserRouteWithDetails>,
+    ) -> Vec<UserRouteWithDetails> {
+        let (active, ended) =
+            tracker_impl::split_ended_routes(user_routes, Local::now().date_naive());
+        if ended.is_empty() {
+            return active;
+        }
+
+        let ids: Vec<Uuid> = ended.iter().map(|r| r.user_route_id).collect();
+        if let Err(e) = archive_user_routes(&self.db, &ids).await {
+            error!("Failed to archive {} ended route(s): {}", ids.len(), e);
+            return active;
+        }
+
+        for user_route in &ended {
+            info!(
+                "Archived route {} of user {}: its dates have passed",
+                user_route.user_route_id, user_route.email
+            );
+
+            self.cache_names(user_route).await;
+            // The route is no longer tracked, so there is nothing to snooze
+            let context =
+                notification_context(user_route, &self.station_cache, &self.route_names, None)
+                    .await;
+            if let Err(e) = self
+                .notifier
+                .send_route_archived(&user_route.notification_channels, &context)
+                .await
+            {
+                error!("Failed to send route archived notification: {}", e);
+            }
+        }
+
+        active
+    }
+
+    /// Cache the station and route names of a route, for its notifications
+    async fn cache_names(&self, user_route: &UserRouteWithDetails) {
+        let cached = self
+            .station_cache
+            .read()
+            .await
+            .contains_key(&user_route.departure_station);
+        if !cached
+            && let Err(e) =
+                populate_station_cache(&self.scraper, &self.station_cache, &user_route.route_id)
+                    .await
         {
             warn!(
                 "Failed to cache stations for route {}: {}",
                 user_route.route_id, e
             );
         }
-    }
-    info!(
-        "Station cache built with {} entries",
-        station_cache.read().await.len()
-    );
-
-    let unique_users: HashSet<String> = user_routes.iter().map(|r| r.email.clone()).collect();
-
-    let unique_webhooks: HashSet<String> = user_routes
-        .iter()
-        .filter_map(|r| r.discord_webhook_url.clone())
-        .collect();
 
-    let notifier = DiscordNotifier::new();
-    for webhook_url in &unique_webhooks {
-        if let Err(e) = notifier
-            .send_startup_notification(webhook_url, unique_users.len(), user_routes.len())
+        let cached = self
+            .route_names
+            .read()
             .await
+            .contains_key(&user_route.route_id);
+        if !cached
+            && let Err(e) =
+                populate_route_names(&self.scraper, &self.route_names, user_route.area_id as u32)
+                    .await
         {
-            error!("Failed to send startup notification: {}", e);
+            warn!(
+                "Failed to cache route names for area {}: {}",
+                user_route.area_id, e
+            );
         }
     }
 
-    for user_route in user_routes {
+    async fn spawn_tracker(&mut self, user_route: UserRouteWithDetails) {
+        self.cache_names(&user_route).await;
+
         let tracker = UserTracker {
-            user_route,
-            scraper: Arc::clone(&scraper),
-            db: Arc::clone(&db),
-            station_cache: Arc::clone(&station_cache),
-            notifier: DiscordNotifier::new(),
+            user_route: user_route.clone(),
+            scraper: Arc::clone(&self.scraper),
+            db: Arc::clone(&self.db),
+            station_cache: Arc::clone(&self.station_cache),
+            route_names: Arc::clone(&self.route_names),
+            public_url: self.public_url.clone(),
+            outbox: Arc::clone(&self.outbox),
+            last_known: HashMap::new(),
         };
 
-        tokio::spawn(async move {
+        let task = tokio::spawn(async move {
             tracker.run().await;
         });
+
+        self.trackers
+            .insert(user_route.user_route_id, TrackerHandle { user_route, task });
+    }
+}
+
+/// Delivers the alerts stored in the outbox, one channel at a time, and
+/// retries failed ones following [`tracker_impl::DELIVERY_RETRY`].
+struct OutboxWorker {
+    db: Arc<DatabaseConnection>,
+    notifier: Arc<NotificationDispatcher>,
+    wake: Arc<Notify>,
+}
+
+impl OutboxWorker {
+    async fn run(self) {
+        let mut interval = tokio::time::interval(OUTBOX_CHECK_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                () = self.wake.notified() => {}
+            }
+
+            self.deliver_due(Utc::now()).await;
+        }
+    }
+
+    /// Attempt the deliveries due at `now`
+    async fn deliver_due(&self, now: DateTime<Utc>) {
+        match get_due_deliveries(&self.db, now, OUTBOX_BATCH_SIZE).await {
+            Ok(due) => {
+                let (alerts, digests) = tracker_impl::split_digests(due);
+                for delivery in alerts {
+                    self.deliver(delivery).await;
+                }
+                for (channel, held) in digests {
+                    self.deliver_digest(&channel, &held).await;
+                }
+            }
+            Err(e) => error!("Failed to load due alert deliveries: {}", e),
+        }
+    }
+
+    /// Send an alert, from its first message not yet delivered by an earlier attempt
+    async fn deliver(&self, delivery: PendingDelivery) {
+        let channel = &delivery.channel;
+
+        let result = match self.notifier_for(channel.kind, delivery.messages_sent) {
+            Ok(notifier) => {
+                notifier
+                    .resume_availability_alert(
+                        &channel.target,
+                        &delivery.alert,
+                        delivery.messages_sent,
+                    )
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+
+        self.record_outcome(&delivery, &result).await;
     }
 
+    /// Send the alerts held for `channel` during quiet hours as one digest,
+    /// every held row sharing the outcome
+    async fn deliver_digest(&self, channel: &NotificationChannel, held: &[PendingDelivery]) {
+        let alerts = tracker_impl::digest_alerts(held);
+        info!(
+            "Quiet hours over - sending {} digest of {} alert(s)",
+            channel.kind.label(),
+            alerts.len()
+        );
+
+        // Held rows fail together, so they share how far the digest got
+        let sent = held.iter().map(|d| d.messages_sent).min().unwrap_or(0);
+        let result = match self.notifier_for(channel.kind, sent) {
+            Ok(notifier) => notifier.resume_digest(&channel.target, &alerts, sent).await,
+            Err(e) => Err(e),
+        };
+
+        for delivery in held {
+            self.record_outcome(delivery, &result).await;
+        }
+    }
+
+    /// The notifier of `kind`, or a failure keeping the `sent` messages of the delivery
+    fn notifier_for(&self, kind: ChannelKind, sent: u32) -> Result<&dyn Notifier, PartialDelivery> {
+        self.notifier.notifier(kind).ok_or_else(|| PartialDelivery {
+            sent,
+            error: ScraperError::Config(format!(
+                "No notifier registered for {} channel",
+                kind.label()
+            )),
+        })
+    }
+
+    /// Mark a delivery sent, or failed and due again following [`tracker_impl::DELIVERY_RETRY`]
+    async fn record_outcome(
+        &self,
+        delivery: &PendingDelivery,
+        result: &Result<(), PartialDelivery>,
+    ) {
+        let attempts = delivery.attempts + 1;
+        let channel = &delivery.channel;
+
+        let recorded = match result {
+            Ok(()) => mark_delivery_sent(&self.db, delivery.id, attempts).await,
+            Err(PartialDelivery { sent, error: e }) => {
+                let retry_in =
+                    tracker_impl::next_delivery_attempt(&tracker_impl::DELIVERY_RETRY, attempts, e);
+                if let Some(wait) = retry_in {
+                    warn!(
+                        "{} alert {} failed (attempt {}), retrying in {}s: {}",
+                        channel.kind.label(),
+                        delivery.id,
+                        attempts,
+                        wait.as_secs(),
+                        e
+                    );
+                } else {
+                    error!(
+                        "{} alert {} failed after {} attempts, giving up: {}",
+                        channel.kind.label(),
+                        delivery.id,
+                        attempts,
+                        e
+                    );
+                }
+                let retry_at = retry_in
+                    .and_then(|wait| chrono::Duration::from_std(wait).ok())
+                    .map(|wait| Utc::now() + wait);
+                mark_delivery_failed(
+                    &self.db,
+                    delivery.id,
+                    attempts,
+                    *sent,
+                    &e.to_string(),
+                    retry_at,
+                )
+                .await
+            }
+        };
+
+        if let Err(e) = recorded {
+            error!(
+                "Failed to record the outcome of alert {}: {}",
+                delivery.id, e
+            );
+        }
+    }
+}
+
+async fn populate_route_names(
+    scraper: &BusScraper,
+    cache: &RouteNameCache,
+    area_id: u32,
+) -> anyhow::Result<()> {
+    let routes = scraper.fetch_routes(area_id).await?;
+    let mut cache_lock = cache.write().await;
+    for route in routes {
+        cache_lock.insert(route.id, route.name);
+    }
     Ok(())
 }
 
@@ -103,97 +557,216 @@ struct UserTracker {
     scraper: Arc<BusScraper>,
     db: Arc<DatabaseConnection>,
     station_cache: StationCache,
-    notifier: DiscordNotifier,
+    route_names: RouteNameCache,
+    public_url: Option<Arc<str>>,
+    outbox: Arc<Notify>,
+    /// Schedules from the last successful fetch of each date, to stand in for dates that fail
+    last_known: HashMap<String, Vec<types::BusSchedule>>,
 }
 
 impl UserTracker {
-    async fn run(self) {
+    async fn run(mut self) {
         info!(
             "Starting tracker for user {} (route {})",
             self.user_route.email, self.user_route.user_route_id
         );
 
-        let mut interval = tokio::time::interval(Duration::from_secs(
-            self.user_route.scrape_interval_secs as u64,
-        ));
-        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
-
         loop {
-            interval.tick().await;
-
-            if let Err(e) = self.check_and_notify().await {
-                error!(
+            match self.check_and_notify().await {
+                Ok(()) => {}
+                // Already reported once by the supervisor for all routes
+                Err(ScraperError::CircuitOpen { .. }) => debug!(
+                    "Route {} - upstream down, skipping check",
+                    self.user_route.user_route_id
+                ),
+                Err(e) => error!(
                     "Error checking availability for user {} route {}: {}",
                     self.user_route.email, self.user_route.user_route_id, e
-                );
+                ),
             }
+
+            tokio::time::sleep(self.poll_interval()).await;
         }
     }
 
-    async fn check_and_notify(&self) -> error::Result<()> {
+    /// Wait before the next check, shorter as departure approaches in adaptive mode
+    fn poll_interval(&self) -> Duration {
+        let base = Duration::from_secs(self.user_route.scrape_interval_secs.max(1) as u64);
+        if !self.user_route.adaptive_interval {
+            return base;
+        }
+
+        let today = Local::now().date_naive();
+        let dates = self
+            .user_route
+            .date_range()
+            .dates_on(today)
+            .unwrap_or_default();
+        tracker_impl::poll_interval(
+            base,
+            true,
+            tracker_impl::days_until_next_departure(&dates, today),
+        )
+    }
+
+    async fn check_and_notify(&mut self) -> error::Result<()> {
         let request = self.build_scrape_request();
 
-        let schedules = self.scraper.check_availability_full(&request).await?;
+        let check = self.scraper.check_availability_full(&request).await?;
+
+        // History only records what was actually observed this round
+        let fetched: Vec<_> = check
+            .fetched
+            .iter()
+            .flat_map(|(_, schedules)| schedules.iter().cloned())
+            .collect();
+        if let Err(e) = record_availability_snapshots(
+            &self.db,
+            self.user_route.user_route_id,
+            Utc::now(),
+            &fetched,
+        )
+        .await
+        {
+            warn!(
+                "Failed to record availability history for route {}: {}",
+                self.user_route.user_route_id, e
+            );
+        }
+
+        let failed_dates = check.failed_dates().join(", ");
+        let Some(schedules) = tracker_impl::merge_with_last_known(&mut self.last_known, check)
+        else {
+            warn!(
+                "Route {} - no data for failed date(s) {}, skipping state update",
+                self.user_route.user_route_id, failed_dates
+            );
+            return Ok(());
+        };
+        if !failed_dates.is_empty() {
+            info!(
+                "Route {} - using last known schedules for failed date(s) {}",
+                self.user_route.user_route_id, failed_dates
+            );
+        }
 
         let schedules_with_seats: Vec<_> = schedules
             .iter()
-            .filter(|s| !s.available_plans.is_empty())
+            .filter(|s| s.has_bookable_plans())
             .cloned()
             .collect();
 
-        let current_hash = calculate_state_hash(&schedules_with_seats);
+        // Hash every bus, sold-out ones included, so that both "became
+        // available" and "sold out again" register as state changes.
+        let current_hash = calculate_state_hash(&schedules);
 
         let state = get_route_state(&self.db, self.user_route.user_route_id).await?;
 
-        let hash_str = format!("{current_hash}");
+        let hash_str = state_hash::format_state_hash(current_hash);
         let last_hash = state.as_ref().map(|s| s.last_seen_hash.as_str());
-        let state_changed = tracker_impl::has_state_changed(last_hash, &hash_str);
+        let state_changed = match last_hash {
+            // Written by an older hash format: the current state becomes the new baseline
+            Some(hash) if !state_hash::is_current_version(hash) => {
+                info!(
+                    "Route {} - stored state hash has an older format, re-baselining",
+                    self.user_route.user_route_id
+                );
+                false
+            }
+            _ => tracker_impl::has_state_changed(last_hash, &hash_str),
+        };
+
+        let previous = state
+            .as_ref()
+            .and_then(|s| s.last_snapshot.as_deref())
+            .unwrap_or_default();
+        let changes = tracker_impl::reportable_changes(
+            availability_diff::diff_schedules(previous, &schedules),
+            self.user_route.notify_on_sold_out,
+        );
+        // A bus leaving the searched dates changes the hash but is nothing to report
+        let state_changed = state_changed && !changes.is_empty();
+        let availability_lost = tracker_impl::availability_lost(&changes);
 
         let should_notify = tracker_impl::should_send_notification(
             self.user_route.notify_on_change_only,
             state_changed,
             !schedules_with_seats.is_empty(),
+            availability_lost,
         );
 
-        if should_notify {
-            if let Some(ref webhook_url) = self.user_route.discord_webhook_url {
-                info!(
-                    "Sending notification for user {} - {} buses with seats",
-                    self.user_route.email,
-                    schedules_with_seats.len()
-                );
-
-                let context = self.build_notification_context().await?;
-                self.notifier
-                    .send_availability_alert(webhook_url, &schedules_with_seats, &context)
-                    .await?;
+        let channels = &self.user_route.notification_channels;
+        let alerted = should_notify && !channels.is_empty();
+        let mut outbox_alert = None;
+        let mut held_until = None;
+        if alerted {
+            info!(
+                "Sending notification for user {} - {} buses with seats, {} change(s)",
+                self.user_route.email,
+                schedules_with_seats.len(),
+                changes.len()
+            );
 
-                update_route_state(
-                    &self.db,
-                    self.user_route.user_route_id,
-                    format!("{current_hash}"),
-                    true,
-                )
-                .await?;
-            }
-        } else {
-            if !schedules.is_empty() && schedules_with_seats.is_empty() {
+            let context = notification_context(
+                &self.user_route,
+                &self.station_cache,
+                &self.route_names,
+                self.public_url.as_deref(),
+            )
+            .await;
+            let alert = QueuedAlert {
+                schedules: schedules_with_seats,
+                changes,
+                context,
+            };
+
+            held_until = self
+                .user_route
+                .quiet_hours
+                .as_ref()
+                .and_then(|quiet| quiet.quiet_until(Utc::now()));
+            if let Some(until) = held_until {
                 info!(
-                    "User {} - Found {} buses but no seats available",
-                    self.user_route.email,
-                    schedules.len()
+                    "Quiet hours for user {} - alert held for the digest until {}",
+                    self.user_route.email, until
                 );
             }
+            outbox_alert = Some(alert);
+        } else if !schedules.is_empty() && schedules_with_seats.is_empty() {
+            info!(
+                "User {} - Found {} buses but no seats available",
+                self.user_route.email,
+                schedules.len()
+            );
+        }
 
-            if !schedules_with_seats.is_empty() {
-                update_route_state(
-                    &self.db,
-                    self.user_route.user_route_id,
-                    format!("{current_hash}"),
-                    false,
-                )
-                .await?;
+        // Recorded even when nothing is left, so that seats going away and
+        // coming back are both seen as changes
+        if let Some(alert) = outbox_alert {
+            // Stored with the state, then delivered by the outbox worker once
+            // due, which for a held alert is when quiet hours end
+            record_alert(
+                &self.db,
+                self.user_route.user_route_id,
+                hash_str,
+                &schedules,
+                channels,
+                &alert,
+                held_until,
+            )
+            .await?;
+            if held_until.is_none() {
+                self.outbox.notify_one();
             }
+        } else {
+            update_route_state(
+                &self.db,
+                self.user_route.user_route_id,
+                hash_str,
+                &schedules,
+                false,
+            )
+            .await?;
         }
 
         Ok(())
@@ -205,10 +778,7 @@ impl UserTracker {
             route_id: self.user_route.route_id.parse().unwrap_or(0),
             departure_station: self.user_route.departure_station.clone(),
             arrival_station: self.user_route.arrival_station.clone(),
-            date_range: DateRange {
-                start: self.user_route.date_start.clone(),
-                end: self.user_route.date_end.clone(),
-            },
+            date_range: self.user_route.date_range(),
             passengers: PassengerCount {
                 adult_men: self.user_route.passengers.adult_men as u8,
                 adult_women: self.user_route.passengers.adult_women as u8,
@@ -229,65 +799,83 @@ impl UserTracker {
                     departure_max: max.clone(),
                 }),
             },
+            plan_filter: (!self.user_route.plan_filter.is_empty())
+                .then(|| self.user_route.plan_filter.clone()),
         }
     }
+}
 
-    async fn build_notification_context(&self) -> error::Result<NotificationContext> {
-        let cache = self.station_cache.read().await;
-
-        let departure_name = cache
-            .get(&self.user_route.departure_station)
-            .cloned()
-            .unwrap_or_else(|| format!("Station {}", self.user_route.departure_station));
-
-        let arrival_name = cache
-            .get(&self.user_route.arrival_station)
-            .cloned()
-            .unwrap_or_else(|| format!("Station {}", self.user_route.arrival_station));
-
-        Ok(NotificationContext {
-            departure_station_name: departure_name,
-            arrival_station_name: arrival_name,
-            date_range: (
-                self.user_route.date_start.clone(),
-                self.user_route.date_end.clone(),
-            ),
-            passenger_count: self.user_route.passengers.total() as u8,
-            time_filter: match (
-                &self.user_route.departure_time_min,
-                &self.user_route.departure_time_max,
-            ) {
-                (Some(min), Some(max)) => Some((min.clone(), max.clone())),
-                _ => None,
+/// Names and search parameters of a route, for notifications about it.
+///
+/// Snooze and full-list links are added when `public_url` is known.
+async fn notification_context(
+    user_route: &UserRouteWithDetails,
+    station_cache: &StationCache,
+    route_names: &RouteNameCache,
+    public_url: Option<&str>,
+) -> NotificationContext {
+    let route_name = route_names
+        .read()
+        .await
+        .get(&user_route.route_id)
+        .cloned()
+        .unwrap_or_default();
+    let cache = station_cache.read().await;
+
+    let departure_name = cache
+        .get(&user_route.departure_station)
+        .cloned()
+        .unwrap_or_else(|| format!("Station {}", user_route.departure_station));
+
+    let arrival_name = cache
+        .get(&user_route.arrival_station)
+        .cloned()
+        .unwrap_or_else(|| format!("Station {}", user_route.arrival_station));
+
+    NotificationContext {
+        departure_station_name: departure_name,
+        arrival_station_name: arrival_name,
+        route_name,
+        date_range: user_route.date_window.as_ref().map_or_else(
+            || (user_route.date_start.clone(), user_route.date_end.clone()),
+            |window| {
+                let (start, end) = window.bounds(Local::now().date_naive());
+                (
+                    start.format("%Y%m%d").to_string(),
+                    end.format("%Y%m%d").to_string(),
+                )
             },
-        })
+        ),
+        passenger_count: user_route.passengers.total() as u8,
+        time_filter: match (
+            &user_route.departure_time_min,
+            &user_route.departure_time_max,
+        ) {
+            (Some(min), Some(max)) => Some((min.clone(), max.clone())),
+            _ => None,
+        },
+        snooze_url: public_url.map(|url| snooze_url(url, user_route.user_route_id)),
+        availability_url: public_url.map(|url| availability_url(url, user_route.user_route_id)),
+        locale: user_route.locale,
     }
 }
 
 pub fn calculate_state_hash(schedules: &[types::BusSchedule]) -> u64 {
-    let mut hasher = DefaultHasher::new();
-
-    for schedule in schedules {
-        schedule.departure_date.hash(&mut hasher);
-        schedule.departure_time.hash(&mut hasher);
-
-        for plan in &schedule.available_plans {
-            plan.plan_id.hash(&mut hasher);
-            plan.price.hash(&mut hasher);
-
-            let types::SeatAvailability::Available { remaining_seats } = &plan.availability;
-            remaining_seats.hash(&mut hasher);
-        }
-    }
-
-    hasher.finish()
+    state_hash::state_hash(schedules)
 }
 
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
+    use app::entities::{
+        notification_channels, prelude::NotificationOutbox, user_passengers, user_routes, users,
+    };
     use app::types::{BusSchedule, PricingPlan, SeatAvailability};
+    use migration::{Migrator, MigratorTrait};
+    use sea_orm::{ActiveModelTrait, EntityTrait, Set};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
 
     fn create_test_schedule(
         departure_date: &str,
@@ -313,7 +901,9 @@ mod tests {
                 price,
                 display_price: format!("{price}円"),
                 availability: SeatAvailability::Available { remaining_seats },
+                details: None,
             }],
+            booking_url: None,
         }
     }
 
@@ -419,6 +1009,24 @@ mod tests {
         assert_ne!(hash1, hash2);
     }
 
+    #[test]
+    fn test_calculate_state_hash_sold_out_vs_available() {
+        let available = vec![create_test_schedule(
+            "20250115",
+            "08:30",
+            12345,
+            2100,
+            Some(5),
+        )];
+        let mut sold_out = available.clone();
+        sold_out[0].available_plans[0].availability = SeatAvailability::SoldOut;
+
+        assert_ne!(
+            calculate_state_hash(&available),
+            calculate_state_hash(&sold_out)
+        );
+    }
+
     #[test]
     fn test_calculate_state_hash_different_remaining_seats() {
         let schedules1 = vec![create_test_schedule(
@@ -528,4 +1136,167 @@ mod tests {
 
         assert_ne!(hash1, hash2);
     }
+
+    const SCHEDULES_HTML: &str = r#"<!DOCTYPE html>
+<html><body>
+    <section class="busSvclistItem">
+        <ul>
+            <li class="dep"><p class="time">7:45 発</p></li>
+            <li class="arr"><p class="time">10:00 着</p></li>
+        </ul>
+        <div class="planArea">
+            <p class="price">2,100円</p>
+            <form name="selectPlan">
+                <input type="hidden" class="seat_0" value="1" data-index="0">
+                <input type="hidden" name="discntPlanNo" value="100">
+                <button>残り5席</button>
+            </form>
+        </div>
+    </section>
+</body></html>"#;
+
+    /// A route whose owner is in quiet hours for the next hour, alerting `webhook`
+    async fn quiet_route(db: &DatabaseConnection, webhook: String) -> UserRouteWithDetails {
+        let now = Utc::now();
+        let user_id = Uuid::new_v4();
+        users::ActiveModel {
+            id: Set(user_id),
+            email: Set("quiet@example.com".to_string()),
+            enabled: Set(true),
+            notify_on_change_only: Set(true),
+            scrape_interval_secs: Set(300),
+            created_at: Set(now),
+            quiet_hours_start: Set(Some(
+                (now - chrono::Duration::hours(1))
+                    .format("%H:%M")
+                    .to_string(),
+            )),
+            quiet_hours_end: Set(Some(
+                (now + chrono::Duration::hours(1))
+                    .format("%H:%M")
+                    .to_string(),
+            )),
+            quiet_hours_tz: Set(Some("UTC".to_string())),
+            locale: Set("en".to_string()),
+        }
+        .insert(db)
+        .await
+        .unwrap();
+
+        notification_channels::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(user_id),
+            kind: Set("discord".to_string()),
+            target: Set(webhook),
+            enabled: Set(true),
+            created_at: Set(now),
+        }
+        .insert(db)
+        .await
+        .unwrap();
+
+        let route_id = Uuid::new_v4();
+        user_routes::ActiveModel {
+            id: Set(route_id),
+            user_id: Set(user_id),
+            area_id: Set(1),
+            route_id: Set("155".to_string()),
+            departure_station: Set("001".to_string()),
+            arrival_station: Set("498".to_string()),
+            date_start: Set("2099-01-15".to_string()),
+            date_end: Set("2099-01-15".to_string()),
+            departure_time_min: Set(None),
+            departure_time_max: Set(None),
+            created_at: Set(now),
+            max_price: Set(None),
+            seat_type: Set(None),
+            plan_ids_allow: Set(None),
+            plan_ids_deny: Set(None),
+            min_remaining_seats: Set(None),
+            date_window: Set(None),
+            status: Set("active".to_string()),
+            enabled: Set(true),
+            paused_until: Set(None),
+            scrape_interval_secs: Set(None),
+            adaptive_interval: Set(false),
+            notify_on_sold_out: Set(false),
+        }
+        .insert(db)
+        .await
+        .unwrap();
+
+        user_passengers::ActiveModel {
+            user_route_id: Set(route_id),
+            adult_men: Set(1),
+            adult_women: Set(0),
+            child_men: Set(0),
+            child_women: Set(0),
+            handicap_adult_men: Set(0),
+            handicap_adult_women: Set(0),
+            handicap_child_men: Set(0),
+            handicap_child_women: Set(0),
+        }
+        .insert(db)
+        .await
+        .unwrap();
+
+        get_all_active_user_routes(db).await.unwrap().remove(0)
+    }
+
+    #[tokio::test]
+    async fn test_quiet_hours_alert_survives_restart() {
+        let upstream = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/reservation/rsvPlanList"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(SCHEDULES_HTML))
+            .mount(&upstream)
+            .await;
+        let discord = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/webhook"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&discord)
+            .await;
+
+        let db_path = std::env::temp_dir().join(format!("tracker-{}.db", Uuid::new_v4()));
+        let db_url = format!("sqlite://{}?mode=rwc", db_path.display());
+        let db = app::db::init_database(&db_url).await.unwrap();
+        Migrator::up(&db, None).await.unwrap();
+        let user_route = quiet_route(&db, format!("{}/webhook", discord.uri())).await;
+
+        let mut tracker = UserTracker {
+            user_route,
+            scraper: Arc::new(BusScraper::new(upstream.uri()).unwrap()),
+            db: Arc::new(db),
+            station_cache: StationCache::default(),
+            route_names: RouteNameCache::default(),
+            public_url: None,
+            outbox: Arc::new(Notify::new()),
+            last_known: HashMap::new(),
+        };
+        tracker.check_and_notify().await.unwrap();
+        // The process stops before quiet hours end
+        drop(tracker);
+
+        let db = Arc::new(app::db::init_database(&db_url).await.unwrap());
+        let worker = OutboxWorker {
+            db: Arc::clone(&db),
+            notifier: Arc::new(NotificationDispatcher::new()),
+            wake: Arc::new(Notify::new()),
+        };
+
+        worker.deliver_due(Utc::now()).await;
+        assert!(discord.received_requests().await.unwrap().is_empty());
+
+        worker
+            .deliver_due(Utc::now() + chrono::Duration::hours(2))
+            .await;
+        let rows = NotificationOutbox::find().all(db.as_ref()).await.unwrap();
+        assert_eq!(rows.len(), 1);
+        assert!(rows[0].digest);
+        assert_eq!(rows[0].status, "sent");
+
+        let _ = std::fs::remove_file(db_path);
+    }
 }
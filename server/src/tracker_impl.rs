@@ -5,32 +5,83 @@
 //! better test coverage since these functions don't depend on async runtime
 //! or external services.
 
-use app::types::{BusSchedule, SeatAvailability};
+use app::availability_diff::{AvailabilityChange, ChangeKind};
+use app::circuit_breaker::CircuitState;
+use app::error::ScraperError;
+use app::notifier::{QueuedAlert, UpstreamEvent};
+use app::repositories::{PendingDelivery, UserRouteWithDetails};
+use app::retry::RetryPolicy;
+use app::scraper::AvailabilityCheck;
+use app::types::{BusSchedule, NotificationChannel, SeatAvailability};
+use chrono::NaiveDate;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Fastest polling the adaptive mode goes down to, unless the route's interval is already shorter
+const MIN_ADAPTIVE_INTERVAL: Duration = Duration::from_mins(1);
+
+/// Retries of an alert in the outbox: from 30 s apart, doubling up to an
+/// hour, for a few hours in total before the alert is marked failed
+pub const DELIVERY_RETRY: RetryPolicy = RetryPolicy {
+    max_retries: 10,
+    base_delay: Duration::from_secs(30),
+    max_delay: Duration::from_hours(1),
+};
 
 /// Determine if a notification should be sent based on:
 /// - `notify_on_change_only`: user preference for notification strategy
-/// - `state_changed`: whether the availability hash has changed since last check
+/// - `state_changed`: whether anything worth reporting changed since last check
 /// - `has_available_seats`: whether any schedules have seats available
+/// - `availability_lost`: whether seats seen on sale are gone, for routes
+///   opted in to "notify when availability disappears"
 ///
 /// Decision matrix:
-/// | notify_on_change_only | state_changed | has_seats | Result |
-/// |-----------------------|---------------|-----------|--------|
-/// | true                  | true          | true      | true   |
-/// | true                  | true          | false     | false  |
-/// | true                  | false         | true      | false  |
-/// | true                  | false         | false     | false  |
-/// | false                 | *             | true      | true   |
-/// | false                 | *             | false     | false  |
+/// | notify_on_change_only | state_changed | has_seats | lost  | Result |
+/// |-----------------------|---------------|-----------|-------|--------|
+/// | true                  | true          | true      | *     | true   |
+/// | true                  | true          | false     | true  | true   |
+/// | true                  | true          | false     | false | false  |
+/// | true                  | false         | *         | *     | false  |
+/// | false                 | *             | true      | *     | true   |
+/// | false                 | *             | false     | true  | true   |
+/// | false                 | *             | false     | false | false  |
+#[allow(clippy::fn_params_excessive_bools)]
 pub fn should_send_notification(
     notify_on_change_only: bool,
     state_changed: bool,
     has_available_seats: bool,
+    availability_lost: bool,
 ) -> bool {
+    let worth_telling = has_available_seats || availability_lost;
     if notify_on_change_only {
-        state_changed && has_available_seats
+        state_changed && worth_telling
     } else {
-        has_available_seats
+        worth_telling
+    }
+}
+
+/// Changes of a check worth an alert: sold-out plans only count on routes
+/// opted in to "notify when availability disappears".
+pub fn reportable_changes(
+    changes: Vec<AvailabilityChange>,
+    notify_on_sold_out: bool,
+) -> Vec<AvailabilityChange> {
+    if notify_on_sold_out {
+        return changes;
     }
+    changes
+        .into_iter()
+        .filter(|change| change.kind != ChangeKind::SoldOut)
+        .collect()
+}
+
+/// Whether `changes` include seats that are no longer on sale
+pub fn availability_lost(changes: &[AvailabilityChange]) -> bool {
+    changes
+        .iter()
+        .any(|change| change.kind == ChangeKind::SoldOut)
 }
 
 /// Filter schedules to only include those with at least one available seat.
@@ -58,59 +109,304 @@ pub fn has_state_changed(last_hash: Option<&str>, current_hash: &str) -> bool {
     }
 }
 
+/// Combine a check with the schedules last seen for each date.
+///
+/// Dates fetched in `check` replace their entry in `last_known`; dates that failed
+/// take their schedules from it. Returns `None` when a failed date has never been
+/// fetched, since treating it as empty would look like every bus disappeared.
+/// Dates no longer part of the check are dropped from `last_known`.
+pub fn merge_with_last_known<S: BuildHasher>(
+    last_known: &mut HashMap<String, Vec<BusSchedule>, S>,
+    check: AvailabilityCheck,
+) -> Option<Vec<BusSchedule>> {
+    let mut dates: Vec<String> = check
+        .fetched
+        .iter()
+        .map(|(date, _)| date.clone())
+        .chain(check.failed.iter().map(|(date, _)| date.clone()))
+        .collect();
+    dates.sort();
+
+    last_known.retain(|date, _| dates.contains(date));
+    last_known.extend(check.fetched);
+
+    let mut schedules = Vec::new();
+    for date in &dates {
+        schedules.extend(last_known.get(date)?.iter().cloned());
+    }
+    Some(schedules)
+}
+
+/// Notification to send when the circuit breaker moves to `state`.
+///
+/// `reported_down` is whether users were last told the site is down. Probing
+/// (half-open) is not reported, and a probe that fails does not repeat "down".
+pub fn upstream_event(reported_down: bool, state: CircuitState) -> Option<UpstreamEvent> {
+    match (reported_down, state) {
+        (false, CircuitState::Open) => Some(UpstreamEvent::Down),
+        (true, CircuitState::Closed) => Some(UpstreamEvent::Recovered),
+        _ => None,
+    }
+}
+
+/// Split routes still worth checking on `today` from those whose dates have all passed.
+pub fn split_ended_routes(
+    routes: Vec<UserRouteWithDetails>,
+    today: NaiveDate,
+) -> (Vec<UserRouteWithDetails>, Vec<UserRouteWithDetails>) {
+    routes
+        .into_iter()
+        .partition(|route| !route.date_range().has_ended(today))
+}
+
+/// Days from `today` to the first of `dates` (`YYYYMMDD`) that has not passed yet
+pub fn days_until_next_departure(dates: &[String], today: NaiveDate) -> Option<i64> {
+    dates
+        .iter()
+        .filter_map(|date| NaiveDate::parse_from_str(date, "%Y%m%d").ok())
+        .filter(|date| *date >= today)
+        .min()
+        .map(|date| (date - today).num_days())
+}
+
+/// Wait before the next check of a route polled every `base`.
+///
+/// In adaptive mode the wait shrinks as the next departure approaches: a
+/// quarter of `base` up to the next day, half within a week, `base` within a
+/// month and twice `base` further out.
+pub fn poll_interval(base: Duration, adaptive: bool, days_ahead: Option<i64>) -> Duration {
+    let Some(days) = days_ahead.filter(|_| adaptive) else {
+        return base;
+    };
+
+    let scaled = match days {
+        ..=1 => base / 4,
+        2..=7 => base / 2,
+        8..=30 => base,
+        _ => base * 2,
+    };
+    scaled.max(base.min(MIN_ADAPTIVE_INTERVAL))
+}
+
+/// Wait before the next attempt at delivering an alert that just failed for
+/// the `attempts`-th time, or `None` once `policy` has no retries left
+pub fn next_delivery_attempt(
+    policy: &RetryPolicy,
+    attempts: u32,
+    error: &ScraperError,
+) -> Option<Duration> {
+    (attempts <= policy.max_retries).then(|| policy.delay_for(attempts, error))
+}
+
+/// Split due outbox rows into alerts sent on their own and the alerts held
+/// back during quiet hours, grouped per channel to go out as one digest each.
+///
+/// Channels keep the order of their first held alert.
+pub fn split_digests(
+    due: Vec<PendingDelivery>,
+) -> (
+    Vec<PendingDelivery>,
+    Vec<(NotificationChannel, Vec<PendingDelivery>)>,
+) {
+    let mut alerts = Vec::new();
+    let mut digests: Vec<(NotificationChannel, Vec<PendingDelivery>)> = Vec::new();
+    for delivery in due {
+        if !delivery.digest {
+            alerts.push(delivery);
+            continue;
+        }
+        match digests
+            .iter_mut()
+            .find(|(channel, _)| *channel == delivery.channel)
+        {
+            Some((_, held)) => held.push(delivery),
+            None => digests.push((delivery.channel.clone(), vec![delivery])),
+        }
+    }
+    (alerts, digests)
+}
+
+/// Alerts of a digest: the latest alert of each route, in the order routes
+/// first alerted.
+///
+/// The changes of earlier alerts of a route are kept ahead of the latest
+/// ones, so the digest still tells everything that happened.
+pub fn digest_alerts(held: &[PendingDelivery]) -> Vec<QueuedAlert> {
+    let mut alerts: Vec<(Uuid, QueuedAlert)> = Vec::new();
+    for delivery in held {
+        let mut alert = delivery.alert.clone();
+        match alerts
+            .iter_mut()
+            .find(|(id, _)| *id == delivery.user_route_id)
+        {
+            Some((_, queued)) => {
+                let mut changes = std::mem::take(&mut queued.changes);
+                changes.append(&mut alert.changes);
+                alert.changes = changes;
+                *queued = alert;
+            }
+            None => alerts.push((delivery.user_route_id, alert)),
+        }
+    }
+    alerts.into_iter().map(|(_, alert)| alert).collect()
+}
+
+/// Actions the tracker supervisor must take to converge running trackers
+/// onto the routes currently active in the database.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ReconcilePlan {
+    pub start: Vec<Uuid>,
+    pub restart: Vec<Uuid>,
+    pub stop: Vec<Uuid>,
+}
+
+impl ReconcilePlan {
+    pub fn is_empty(&self) -> bool {
+        self.start.is_empty() && self.restart.is_empty() && self.stop.is_empty()
+    }
+}
+
+/// Compare running trackers with the desired route configurations.
+/// - Routes only in `desired` are started
+/// - Routes in both whose configuration differs are restarted
+/// - Routes only in `running` are stopped
+///
+/// Each list is sorted so the plan is deterministic.
+pub fn plan_reconciliation<T: PartialEq, S: BuildHasher>(
+    running: &HashMap<Uuid, T, S>,
+    desired: &HashMap<Uuid, T, S>,
+) -> ReconcilePlan {
+    let mut plan = ReconcilePlan::default();
+
+    for (id, config) in desired {
+        match running.get(id) {
+            None => plan.start.push(*id),
+            Some(current) if current != config => plan.restart.push(*id),
+            Some(_) => {}
+        }
+    }
+
+    plan.stop = running
+        .keys()
+        .filter(|id| !desired.contains_key(id))
+        .copied()
+        .collect();
+
+    plan.start.sort();
+    plan.restart.sort();
+    plan.stop.sort();
+    plan
+}
+
 #[cfg(test)]
+#[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
-    use app::types::PricingPlan;
+    use app::types::{Locale, PricingPlan};
 
     // === should_send_notification tests ===
 
     #[test]
     fn test_notify_on_change_only_true_state_changed_seats_available() {
         // User wants change-only notifications, state changed, seats available -> NOTIFY
-        assert!(should_send_notification(true, true, true));
+        assert!(should_send_notification(true, true, true, false));
     }
 
     #[test]
     fn test_notify_on_change_only_true_state_unchanged_seats_available() {
         // User wants change-only, but state hasn't changed -> NO NOTIFY
-        assert!(!should_send_notification(true, false, true));
+        assert!(!should_send_notification(true, false, true, false));
     }
 
     #[test]
     fn test_notify_on_change_only_true_state_changed_no_seats() {
         // User wants change-only, state changed but no seats -> NO NOTIFY
-        assert!(!should_send_notification(true, true, false));
+        assert!(!should_send_notification(true, true, false, false));
     }
 
     #[test]
     fn test_notify_on_change_only_true_state_unchanged_no_seats() {
         // User wants change-only, no change, no seats -> NO NOTIFY
-        assert!(!should_send_notification(true, false, false));
+        assert!(!should_send_notification(true, false, false, false));
     }
 
     #[test]
     fn test_notify_on_change_only_false_state_changed_seats_available() {
         // User wants all notifications, seats available -> NOTIFY
-        assert!(should_send_notification(false, true, true));
+        assert!(should_send_notification(false, true, true, false));
     }
 
     #[test]
     fn test_notify_on_change_only_false_state_unchanged_seats_available() {
         // User wants all notifications, state unchanged but seats available -> NOTIFY
-        assert!(should_send_notification(false, false, true));
+        assert!(should_send_notification(false, false, true, false));
     }
 
     #[test]
     fn test_notify_on_change_only_false_state_changed_no_seats() {
         // User wants all notifications, but no seats -> NO NOTIFY
-        assert!(!should_send_notification(false, true, false));
+        assert!(!should_send_notification(false, true, false, false));
     }
 
     #[test]
     fn test_notify_on_change_only_false_state_unchanged_no_seats() {
         // User wants all notifications, but no seats -> NO NOTIFY
-        assert!(!should_send_notification(false, false, false));
+        assert!(!should_send_notification(false, false, false, false));
+    }
+
+    #[test]
+    fn test_notify_on_change_only_true_state_changed_no_seats_availability_lost() {
+        // Opted-in route whose last seats went, state changed -> NOTIFY
+        assert!(should_send_notification(true, true, false, true));
+    }
+
+    #[test]
+    fn test_notify_on_change_only_true_state_unchanged_availability_lost() {
+        // Nothing changed since the seats went -> NO NOTIFY, even opted in
+        assert!(!should_send_notification(true, false, false, true));
+    }
+
+    #[test]
+    fn test_notify_on_change_only_true_state_changed_seats_and_availability_lost() {
+        // Some seats went while others remain -> NOTIFY
+        assert!(should_send_notification(true, true, true, true));
+    }
+
+    #[test]
+    fn test_notify_on_change_only_false_no_seats_availability_lost() {
+        // User wants all notifications, the last seats went -> NOTIFY
+        assert!(should_send_notification(false, true, false, true));
+        assert!(should_send_notification(false, false, false, true));
+    }
+
+    // === reportable_changes tests ===
+
+    fn change(kind: ChangeKind) -> AvailabilityChange {
+        let schedule = create_schedule_with_seats(Some(2));
+        AvailabilityChange {
+            plan: schedule.available_plans[0].clone(),
+            schedule,
+            kind,
+        }
+    }
+
+    #[test]
+    fn test_reportable_changes_drop_sold_out_unless_opted_in() {
+        let changes = || {
+            vec![
+                change(ChangeKind::SoldOut),
+                change(ChangeKind::PriceChanged { from: 1800 }),
+            ]
+        };
+
+        let kept = reportable_changes(changes(), false);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].kind, ChangeKind::PriceChanged { from: 1800 });
+        assert!(!availability_lost(&kept));
+
+        let kept = reportable_changes(changes(), true);
+        assert_eq!(kept.len(), 2);
+        assert!(availability_lost(&kept));
     }
 
     // === has_state_changed tests ===
@@ -163,7 +459,9 @@ mod tests {
                 availability: SeatAvailability::Available {
                     remaining_seats: remaining,
                 },
+                details: None,
             }],
+            booking_url: None,
         }
     }
 
@@ -235,10 +533,418 @@ mod tests {
             availability: SeatAvailability::Available {
                 remaining_seats: Some(2),
             },
+            details: None,
         });
         let schedules = vec![schedule];
         let result = filter_schedules_with_seats(schedules);
         // At least one plan has seats -> schedule is included
         assert_eq!(result.len(), 1);
     }
+
+    // === plan_reconciliation tests ===
+
+    #[test]
+    fn test_plan_reconciliation_nothing_running_starts_all() {
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+        let running: HashMap<Uuid, u32> = HashMap::new();
+        let desired = HashMap::from([(a, 1), (b, 2)]);
+
+        let plan = plan_reconciliation(&running, &desired);
+
+        let mut expected = vec![a, b];
+        expected.sort();
+        assert_eq!(plan.start, expected);
+        assert!(plan.restart.is_empty());
+        assert!(plan.stop.is_empty());
+    }
+
+    #[test]
+    fn test_plan_reconciliation_unchanged_is_empty() {
+        let a = Uuid::new_v4();
+        let running = HashMap::from([(a, 1)]);
+        let desired = HashMap::from([(a, 1)]);
+
+        assert!(plan_reconciliation(&running, &desired).is_empty());
+    }
+
+    #[test]
+    fn test_plan_reconciliation_changed_config_restarts() {
+        let a = Uuid::new_v4();
+        let running = HashMap::from([(a, 1)]);
+        let desired = HashMap::from([(a, 2)]);
+
+        let plan = plan_reconciliation(&running, &desired);
+        assert_eq!(plan.restart, vec![a]);
+        assert!(plan.start.is_empty());
+        assert!(plan.stop.is_empty());
+    }
+
+    #[test]
+    fn test_plan_reconciliation_removed_route_stops() {
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+        let running = HashMap::from([(a, 1), (b, 1)]);
+        let desired = HashMap::from([(a, 1)]);
+
+        let plan = plan_reconciliation(&running, &desired);
+        assert_eq!(plan.stop, vec![b]);
+        assert!(plan.start.is_empty());
+        assert!(plan.restart.is_empty());
+    }
+
+    #[test]
+    fn test_plan_reconciliation_mixed_changes_leave_others_untouched() {
+        let kept = Uuid::new_v4();
+        let edited = Uuid::new_v4();
+        let deleted = Uuid::new_v4();
+        let created = Uuid::new_v4();
+        let running = HashMap::from([(kept, 1), (edited, 1), (deleted, 1)]);
+        let desired = HashMap::from([(kept, 1), (edited, 5), (created, 1)]);
+
+        let plan = plan_reconciliation(&running, &desired);
+        assert_eq!(plan.start, vec![created]);
+        assert_eq!(plan.restart, vec![edited]);
+        assert_eq!(plan.stop, vec![deleted]);
+    }
+
+    // === merge_with_last_known tests ===
+
+    fn schedule_on(date: &str, remaining: Option<u32>) -> BusSchedule {
+        BusSchedule {
+            departure_date: date.to_string(),
+            ..create_schedule_with_seats(remaining)
+        }
+    }
+
+    /// `(departure_date, remaining_seats)` of each schedule, for comparisons
+    fn summary(schedules: &[BusSchedule]) -> Vec<(&str, Option<u32>)> {
+        schedules
+            .iter()
+            .map(|s| {
+                (
+                    s.departure_date.as_str(),
+                    s.available_plans[0].availability.remaining_seats(),
+                )
+            })
+            .collect()
+    }
+
+    fn failed(date: &str) -> (String, app::error::ScraperError) {
+        (
+            date.to_string(),
+            app::error::ScraperError::InvalidResponse("HTTP 500".to_string()),
+        )
+    }
+
+    #[test]
+    fn test_merge_complete_check_replaces_last_known() {
+        let mut last_known = HashMap::from([("20250115".to_string(), vec![])]);
+        let check = AvailabilityCheck {
+            fetched: vec![(
+                "20250115".to_string(),
+                vec![schedule_on("20250115", Some(3))],
+            )],
+            failed: vec![],
+        };
+
+        let merged = merge_with_last_known(&mut last_known, check).unwrap();
+        assert_eq!(summary(&merged), vec![("20250115", Some(3))]);
+        assert_eq!(summary(&last_known["20250115"]), summary(&merged));
+    }
+
+    #[test]
+    fn test_merge_failed_date_uses_last_known_in_date_order() {
+        let mut last_known = HashMap::from([(
+            "20250115".to_string(),
+            vec![schedule_on("20250115", Some(1))],
+        )]);
+        let check = AvailabilityCheck {
+            fetched: vec![(
+                "20250116".to_string(),
+                vec![schedule_on("20250116", Some(2))],
+            )],
+            failed: vec![failed("20250115")],
+        };
+
+        let merged = merge_with_last_known(&mut last_known, check).unwrap();
+        assert_eq!(
+            summary(&merged),
+            vec![("20250115", Some(1)), ("20250116", Some(2))]
+        );
+    }
+
+    #[test]
+    fn test_merge_failed_date_never_seen_returns_none() {
+        let mut last_known = HashMap::new();
+        let check = AvailabilityCheck {
+            fetched: vec![(
+                "20250116".to_string(),
+                vec![schedule_on("20250116", Some(2))],
+            )],
+            failed: vec![failed("20250115")],
+        };
+
+        assert!(merge_with_last_known(&mut last_known, check).is_none());
+        // What was fetched is still remembered for the next round
+        assert!(last_known.contains_key("20250116"));
+    }
+
+    #[test]
+    fn test_merge_drops_dates_outside_check() {
+        let mut last_known = HashMap::from([("20250101".to_string(), vec![])]);
+        let check = AvailabilityCheck {
+            fetched: vec![("20250115".to_string(), vec![])],
+            failed: vec![],
+        };
+
+        merge_with_last_known(&mut last_known, check).unwrap();
+        assert!(!last_known.contains_key("20250101"));
+    }
+
+    #[test]
+    fn test_upstream_event_reports_each_outage_once() {
+        assert_eq!(
+            upstream_event(false, CircuitState::Open),
+            Some(UpstreamEvent::Down)
+        );
+        assert_eq!(upstream_event(true, CircuitState::Open), None);
+        assert_eq!(upstream_event(true, CircuitState::HalfOpen), None);
+        assert_eq!(
+            upstream_event(true, CircuitState::Closed),
+            Some(UpstreamEvent::Recovered)
+        );
+        assert_eq!(upstream_event(false, CircuitState::Closed), None);
+        assert_eq!(upstream_event(false, CircuitState::HalfOpen), None);
+    }
+
+    // === split_ended_routes tests ===
+
+    fn route_ending(date_end: &str) -> UserRouteWithDetails {
+        UserRouteWithDetails {
+            user_route_id: Uuid::new_v4(),
+            email: "test@example.com".to_string(),
+            notify_on_change_only: true,
+            scrape_interval_secs: 300,
+            adaptive_interval: false,
+            notify_on_sold_out: false,
+            quiet_hours: None,
+            locale: Locale::default(),
+            notification_channels: Vec::new(),
+            area_id: 1,
+            route_id: "155".to_string(),
+            departure_station: "001".to_string(),
+            arrival_station: "498".to_string(),
+            date_start: "20261001".to_string(),
+            date_end: date_end.to_string(),
+            date_window: None,
+            departure_time_min: None,
+            departure_time_max: None,
+            plan_filter: app::types::PlanFilter::default(),
+            passengers: app::repositories::PassengerDetails {
+                adult_men: 1,
+                adult_women: 0,
+                child_men: 0,
+                child_women: 0,
+                handicap_adult_men: 0,
+                handicap_adult_women: 0,
+                handicap_child_men: 0,
+                handicap_child_women: 0,
+            },
+        }
+    }
+
+    #[test]
+    fn test_days_until_next_departure() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+        let dates = |list: &[&str]| list.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        assert_eq!(
+            days_until_next_departure(&dates(&["20261016", "20261020", "20261018"]), today),
+            Some(1)
+        );
+        assert_eq!(
+            days_until_next_departure(&dates(&["20261017"]), today),
+            Some(0)
+        );
+        assert_eq!(
+            days_until_next_departure(&dates(&["20261016"]), today),
+            None
+        );
+        assert_eq!(days_until_next_departure(&[], today), None);
+    }
+
+    #[test]
+    fn test_next_delivery_attempt() {
+        let policy = RetryPolicy {
+            max_retries: 2,
+            base_delay: Duration::from_secs(30),
+            max_delay: Duration::from_hours(1),
+        };
+        let error = ScraperError::InvalidResponse("HTTP 500".to_string());
+
+        let first = next_delivery_attempt(&policy, 1, &error).unwrap();
+        assert!(first >= Duration::from_secs(15) && first <= Duration::from_secs(30));
+        let second = next_delivery_attempt(&policy, 2, &error).unwrap();
+        assert!(second >= Duration::from_secs(30) && second <= Duration::from_mins(1));
+        assert_eq!(next_delivery_attempt(&policy, 3, &error), None);
+
+        // A delay asked for by the channel wins
+        let rate_limited = ScraperError::Upstream {
+            status: 429,
+            retry_after: Some(Duration::from_secs(5)),
+        };
+        assert_eq!(
+            next_delivery_attempt(&policy, 1, &rate_limited),
+            Some(Duration::from_secs(5))
+        );
+    }
+
+    #[test]
+    fn test_poll_interval() {
+        let base = Duration::from_mins(10);
+
+        assert_eq!(poll_interval(base, false, Some(0)), base);
+        assert_eq!(poll_interval(base, true, None), base);
+        assert_eq!(poll_interval(base, true, Some(1)), Duration::from_secs(150));
+        assert_eq!(poll_interval(base, true, Some(5)), Duration::from_mins(5));
+        assert_eq!(poll_interval(base, true, Some(30)), base);
+        assert_eq!(poll_interval(base, true, Some(90)), Duration::from_mins(20));
+
+        // Never faster than a minute, unless the route was already polled faster
+        assert_eq!(
+            poll_interval(Duration::from_mins(2), true, Some(0)),
+            Duration::from_mins(1)
+        );
+        assert_eq!(
+            poll_interval(Duration::from_secs(30), true, Some(0)),
+            Duration::from_secs(30)
+        );
+    }
+
+    fn queued(departure_station_name: &str) -> QueuedAlert {
+        QueuedAlert {
+            schedules: Vec::new(),
+            changes: Vec::new(),
+            context: app::notifier::NotificationContext {
+                departure_station_name: departure_station_name.to_string(),
+                arrival_station_name: "Kamikochi".to_string(),
+                route_name: String::new(),
+                date_range: ("20261020".to_string(), "20261020".to_string()),
+                passenger_count: 1,
+                time_filter: None,
+                snooze_url: None,
+                availability_url: None,
+                locale: Locale::default(),
+            },
+        }
+    }
+
+    fn held(user_route_id: Uuid, target: &str, alert: QueuedAlert) -> PendingDelivery {
+        PendingDelivery {
+            id: Uuid::new_v4(),
+            user_route_id,
+            channel: NotificationChannel {
+                kind: app::types::ChannelKind::Discord,
+                target: target.to_string(),
+            },
+            alert,
+            attempts: 0,
+            digest: true,
+            messages_sent: 0,
+        }
+    }
+
+    #[test]
+    fn test_split_digests_groups_held_alerts_per_channel() {
+        let route = Uuid::new_v4();
+        let now = PendingDelivery {
+            digest: false,
+            ..held(route, "https://a", queued("Shinjuku"))
+        };
+        let due = vec![
+            held(route, "https://b", queued("Shinjuku")),
+            now.clone(),
+            held(route, "https://a", queued("Shinjuku")),
+            held(Uuid::new_v4(), "https://b", queued("Tokyo")),
+        ];
+
+        let (alerts, digests) = split_digests(due);
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].id, now.id);
+        let grouped: Vec<_> = digests
+            .iter()
+            .map(|(channel, held)| (channel.target.as_str(), held.len()))
+            .collect();
+        assert_eq!(grouped, vec![("https://b", 2), ("https://a", 1)]);
+    }
+
+    #[test]
+    fn test_digest_alerts_keeps_latest_alert_per_route() {
+        let route = Uuid::new_v4();
+        let held = vec![
+            held(route, "https://a", queued("Shinjuku")),
+            held(Uuid::new_v4(), "https://a", queued("Tokyo")),
+            // A later alert of the same route replaces the earlier one
+            held(route, "https://a", queued("Busta")),
+        ];
+
+        let names: Vec<_> = digest_alerts(&held)
+            .into_iter()
+            .map(|alert| alert.context.departure_station_name)
+            .collect();
+        assert_eq!(names, vec!["Busta", "Tokyo"]);
+    }
+
+    #[test]
+    fn test_digest_alerts_keeps_changes_of_replaced_alerts() {
+        let route = Uuid::new_v4();
+        let held = vec![
+            held(
+                route,
+                "https://a",
+                QueuedAlert {
+                    changes: vec![change(ChangeKind::NowAvailable)],
+                    ..queued("Shinjuku")
+                },
+            ),
+            held(
+                route,
+                "https://a",
+                QueuedAlert {
+                    changes: vec![change(ChangeKind::PriceChanged { from: 1800 })],
+                    ..queued("Shinjuku")
+                },
+            ),
+        ];
+
+        let alerts = digest_alerts(&held);
+        assert_eq!(alerts.len(), 1);
+        let kinds: Vec<_> = alerts[0].changes.iter().map(|c| c.kind.clone()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ChangeKind::NowAvailable,
+                ChangeKind::PriceChanged { from: 1800 }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_ended_routes() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+        let past = route_ending("20261016");
+        let last_day = route_ending("20261017");
+        let rolling = UserRouteWithDetails {
+            date_window: app::types::DateWindow::parse("relative:0:7"),
+            ..route_ending("20261001")
+        };
+
+        let (active, ended) =
+            split_ended_routes(vec![past.clone(), last_day.clone(), rolling.clone()], today);
+
+        assert_eq!(active, vec![last_day, rolling]);
+        assert_eq!(ended, vec![past]);
+    }
 }
@@ -94,8 +94,11 @@ async fn create_test_user_in_db() -> (DatabaseConnection, String) {
         enabled: Set(true),
         notify_on_change_only: Set(false),
         scrape_interval_secs: Set(300),
-        discord_webhook_url: Set(Some("https://discord.com/webhook".to_string())),
         created_at: Set(chrono::Utc::now()),
+        quiet_hours_start: Set(None),
+        quiet_hours_end: Set(None),
+        quiet_hours_tz: Set(None),
+        locale: Set("fr".to_string()),
     };
 
     new_user.insert(&db).await.unwrap();
@@ -121,8 +121,11 @@ async fn test_users_page_ssr_with_data() {
         enabled: Set(true),
         notify_on_change_only: Set(false),
         scrape_interval_secs: Set(300),
-        discord_webhook_url: Set(Some("https://discord.com/api/webhooks/test".to_string())),
         created_at: Set(chrono::Utc::now()),
+        quiet_hours_start: Set(None),
+        quiet_hours_end: Set(None),
+        quiet_hours_tz: Set(None),
+        locale: Set("fr".to_string()),
     };
     user.insert(&db).await.unwrap();
 
@@ -163,8 +166,11 @@ async fn test_users_page_ssr_with_multiple_users() {
             enabled: Set(i % 2 == 0),
             notify_on_change_only: Set(true),
             scrape_interval_secs: Set(300),
-            discord_webhook_url: Set(None),
             created_at: Set(chrono::Utc::now()),
+            quiet_hours_start: Set(None),
+            quiet_hours_end: Set(None),
+            quiet_hours_tz: Set(None),
+            locale: Set("fr".to_string()),
         };
         user.insert(&db).await.unwrap();
     }
@@ -202,8 +208,11 @@ async fn test_users_page_ssr_disabled_user() {
         enabled: Set(false),
         notify_on_change_only: Set(true),
         scrape_interval_secs: Set(600),
-        discord_webhook_url: Set(None),
         created_at: Set(chrono::Utc::now()),
+        quiet_hours_start: Set(None),
+        quiet_hours_end: Set(None),
+        quiet_hours_tz: Set(None),
+        locale: Set("fr".to_string()),
     };
     user.insert(&db).await.unwrap();
 
@@ -263,8 +272,11 @@ async fn test_user_routes_page_ssr_with_user() {
         enabled: Set(true),
         notify_on_change_only: Set(false),
         scrape_interval_secs: Set(300),
-        discord_webhook_url: Set(None),
         created_at: Set(chrono::Utc::now()),
+        quiet_hours_start: Set(None),
+        quiet_hours_end: Set(None),
+        quiet_hours_tz: Set(None),
+        locale: Set("fr".to_string()),
     };
     user.insert(&db).await.unwrap();
 
@@ -302,8 +314,11 @@ async fn test_user_routes_page_ssr_with_route_and_passengers() {
         enabled: Set(true),
         notify_on_change_only: Set(false),
         scrape_interval_secs: Set(300),
-        discord_webhook_url: Set(None),
         created_at: Set(chrono::Utc::now()),
+        quiet_hours_start: Set(None),
+        quiet_hours_end: Set(None),
+        quiet_hours_tz: Set(None),
+        locale: Set("fr".to_string()),
     };
     user.insert(&db).await.unwrap();
 
@@ -318,9 +333,21 @@ async fn test_user_routes_page_ssr_with_route_and_passengers() {
         arrival_station: Set("Osaka".to_string()),
         date_start: Set("2025-01-01".to_string()),
         date_end: Set("2025-01-31".to_string()),
+        date_window: Set(None),
         departure_time_min: Set(Some("09:00".to_string())),
         departure_time_max: Set(Some("18:00".to_string())),
         created_at: Set(chrono::Utc::now()),
+        max_price: Set(None),
+        seat_type: Set(None),
+        plan_ids_allow: Set(None),
+        plan_ids_deny: Set(None),
+        min_remaining_seats: Set(None),
+        status: Set("active".to_string()),
+        enabled: Set(true),
+        paused_until: Set(None),
+        scrape_interval_secs: Set(None),
+        adaptive_interval: Set(false),
+        notify_on_sold_out: Set(false),
     };
     route.insert(&db).await.unwrap();
 
@@ -370,8 +397,11 @@ async fn test_user_routes_page_ssr_multiple_users_and_routes() {
             enabled: Set(true),
             notify_on_change_only: Set(false),
             scrape_interval_secs: Set(300),
-            discord_webhook_url: Set(None),
             created_at: Set(chrono::Utc::now()),
+            quiet_hours_start: Set(None),
+            quiet_hours_end: Set(None),
+            quiet_hours_tz: Set(None),
+            locale: Set("fr".to_string()),
         };
         user.insert(&db).await.unwrap();
 
@@ -386,9 +416,21 @@ async fn test_user_routes_page_ssr_multiple_users_and_routes() {
             arrival_station: Set(format!("Station{}", i + 1)),
             date_start: Set("2025-01-01".to_string()),
             date_end: Set("2025-12-31".to_string()),
+            date_window: Set(None),
             departure_time_min: Set(None),
             departure_time_max: Set(None),
             created_at: Set(chrono::Utc::now()),
+            max_price: Set(None),
+            seat_type: Set(None),
+            plan_ids_allow: Set(None),
+            plan_ids_deny: Set(None),
+            min_remaining_seats: Set(None),
+            status: Set("active".to_string()),
+            enabled: Set(true),
+            paused_until: Set(None),
+            scrape_interval_secs: Set(None),
+            adaptive_interval: Set(false),
+            notify_on_sold_out: Set(false),
         };
         route.insert(&db).await.unwrap();
 
@@ -15,9 +15,9 @@
 
 use app::{
     db,
-    entities::{user_passengers, user_routes, users},
+    entities::{notification_channels, user_passengers, user_routes, users},
     repositories::{get_all_active_user_routes, get_route_state, update_route_state},
-    types::{BusSchedule, PricingPlan, SeatAvailability},
+    types::{BusSchedule, ChannelKind, NotificationChannel, PricingPlan, SeatAvailability},
 };
 use migration::{Migrator, MigratorTrait};
 use sea_orm::{ActiveModelTrait, DatabaseConnection, Set};
@@ -39,10 +39,26 @@ async fn create_test_user(db: &DatabaseConnection, enabled: bool) -> Uuid {
         enabled: Set(enabled),
         notify_on_change_only: Set(true),
         scrape_interval_secs: Set(300),
-        discord_webhook_url: Set(Some("https://discord.com/api/webhooks/test".to_string())),
         created_at: Set(chrono::Utc::now()),
+        quiet_hours_start: Set(None),
+        quiet_hours_end: Set(None),
+        quiet_hours_tz: Set(None),
+        locale: Set("fr".to_string()),
     };
     user.insert(db).await.unwrap();
+
+    notification_channels::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user_id),
+        kind: Set("discord".to_string()),
+        target: Set("https://discord.com/api/webhooks/test".to_string()),
+        enabled: Set(true),
+        created_at: Set(chrono::Utc::now()),
+    }
+    .insert(db)
+    .await
+    .unwrap();
+
     user_id
 }
 
@@ -60,6 +76,18 @@ async fn create_test_route(db: &DatabaseConnection, user_id: Uuid) -> Uuid {
         departure_time_min: Set(None),
         departure_time_max: Set(None),
         created_at: Set(chrono::Utc::now()),
+        max_price: Set(None),
+        seat_type: Set(None),
+        plan_ids_allow: Set(None),
+        plan_ids_deny: Set(None),
+        min_remaining_seats: Set(None),
+        date_window: Set(None),
+        status: Set("active".to_string()),
+        enabled: Set(true),
+        paused_until: Set(None),
+        scrape_interval_secs: Set(None),
+        adaptive_interval: Set(false),
+        notify_on_sold_out: Set(false),
     };
     route.insert(db).await.unwrap();
 
@@ -99,7 +127,9 @@ fn create_test_schedule(date: &str, time: &str, price: u32, seats: Option<u32>)
             availability: SeatAvailability::Available {
                 remaining_seats: seats,
             },
+            details: None,
         }],
+        booking_url: None,
     }
 }
 
@@ -112,8 +142,7 @@ fn calculate_state_hash(schedules: &[BusSchedule]) -> u64 {
         for plan in &schedule.available_plans {
             plan.plan_id.hash(&mut hasher);
             plan.price.hash(&mut hasher);
-            let SeatAvailability::Available { remaining_seats } = &plan.availability;
-            remaining_seats.hash(&mut hasher);
+            plan.availability.hash(&mut hasher);
         }
     }
     hasher.finish()
@@ -163,7 +192,7 @@ async fn test_update_route_state_creates_new_state() {
     let route_id = create_test_route(&db, user_id).await;
 
     // Update state
-    update_route_state(&db, route_id, "hash123".to_string(), false)
+    update_route_state(&db, route_id, "hash123".to_string(), &[], false)
         .await
         .unwrap();
 
@@ -180,12 +209,12 @@ async fn test_update_route_state_updates_existing() {
     let route_id = create_test_route(&db, user_id).await;
 
     // First update
-    update_route_state(&db, route_id, "hash1".to_string(), false)
+    update_route_state(&db, route_id, "hash1".to_string(), &[], false)
         .await
         .unwrap();
 
     // Second update
-    update_route_state(&db, route_id, "hash2".to_string(), true)
+    update_route_state(&db, route_id, "hash2".to_string(), &[], true)
         .await
         .unwrap();
 
@@ -266,7 +295,7 @@ async fn test_should_not_notify_when_hash_unchanged() {
     let hash = format!("{}", calculate_state_hash(&schedules));
 
     // Set initial state
-    update_route_state(&db, route_id, hash.clone(), true)
+    update_route_state(&db, route_id, hash.clone(), &[], true)
         .await
         .unwrap();
 
@@ -291,7 +320,7 @@ async fn test_should_notify_when_availability_changes() {
     // Set initial state with 5 seats
     let schedules1 = vec![create_test_schedule("20250115", "08:30", 2100, Some(5))];
     let hash1 = format!("{}", calculate_state_hash(&schedules1));
-    update_route_state(&db, route_id, hash1.clone(), false)
+    update_route_state(&db, route_id, hash1.clone(), &[], false)
         .await
         .unwrap();
 
@@ -330,7 +359,7 @@ async fn test_user_route_contains_passenger_details() {
 }
 
 #[tokio::test]
-async fn test_user_route_contains_discord_webhook() {
+async fn test_user_route_contains_notification_channels() {
     let db = setup_test_db().await;
     let user_id = create_test_user(&db, true).await;
     create_test_route(&db, user_id).await;
@@ -338,13 +367,12 @@ async fn test_user_route_contains_discord_webhook() {
     let routes = get_all_active_user_routes(&db).await.unwrap();
 
     assert_eq!(routes.len(), 1);
-    assert!(routes[0].discord_webhook_url.is_some());
-    assert!(
-        routes[0]
-            .discord_webhook_url
-            .as_ref()
-            .unwrap()
-            .contains("discord.com")
+    assert_eq!(
+        routes[0].notification_channels,
+        vec![NotificationChannel {
+            kind: ChannelKind::Discord,
+            target: "https://discord.com/api/webhooks/test".to_string(),
+        }]
     );
 }
 
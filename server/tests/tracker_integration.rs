@@ -18,11 +18,14 @@
 
 use app::{
     db,
-    entities::{user_passengers, user_routes, users},
+    entities::{notification_channels, user_passengers, user_routes, users},
     notifier::NotificationContext,
     repositories::{PassengerDetails, UserRouteWithDetails, get_all_active_user_routes},
     scraper::BusScraper,
-    types::{DateRange, PassengerCount, ScrapeRequest, TimeFilter},
+    types::{
+        ChannelKind, DateRange, Locale, NotificationChannel, PassengerCount, PlanFilter,
+        ScrapeRequest, TimeFilter,
+    },
 };
 use migration::{Migrator, MigratorTrait};
 use sea_orm::{ActiveModelTrait, DatabaseConnection, Set};
@@ -48,8 +51,7 @@ fn calculate_state_hash(schedules: &[app::types::BusSchedule]) -> u64 {
         for plan in &schedule.available_plans {
             plan.plan_id.hash(&mut hasher);
             plan.price.hash(&mut hasher);
-            let app::types::SeatAvailability::Available { remaining_seats } = &plan.availability;
-            remaining_seats.hash(&mut hasher);
+            plan.availability.hash(&mut hasher);
         }
     }
     hasher.finish()
@@ -78,10 +80,28 @@ async fn create_test_user(
         enabled: Set(enabled),
         notify_on_change_only: Set(notify_on_change),
         scrape_interval_secs: Set(300),
-        discord_webhook_url: Set(webhook_url),
         created_at: Set(chrono::Utc::now()),
+        quiet_hours_start: Set(None),
+        quiet_hours_end: Set(None),
+        quiet_hours_tz: Set(None),
+        locale: Set("fr".to_string()),
     };
     user.insert(db).await.unwrap();
+
+    if let Some(url) = webhook_url {
+        notification_channels::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(user_id),
+            kind: Set("discord".to_string()),
+            target: Set(url),
+            enabled: Set(true),
+            created_at: Set(chrono::Utc::now()),
+        }
+        .insert(db)
+        .await
+        .unwrap();
+    }
+
     user_id
 }
 
@@ -109,6 +129,18 @@ async fn create_test_route_with_details(
         departure_time_min: Set(departure_time_min),
         departure_time_max: Set(departure_time_max),
         created_at: Set(chrono::Utc::now()),
+        max_price: Set(None),
+        seat_type: Set(None),
+        plan_ids_allow: Set(None),
+        plan_ids_deny: Set(None),
+        min_remaining_seats: Set(None),
+        date_window: Set(None),
+        status: Set("active".to_string()),
+        enabled: Set(true),
+        paused_until: Set(None),
+        scrape_interval_secs: Set(None),
+        adaptive_interval: Set(false),
+        notify_on_sold_out: Set(false),
     };
     route.insert(db).await.unwrap();
 
@@ -149,15 +181,27 @@ fn build_user_route_details(
         email: email.to_string(),
         notify_on_change_only,
         scrape_interval_secs,
-        discord_webhook_url,
+        adaptive_interval: false,
+        notify_on_sold_out: false,
+        quiet_hours: None,
+        locale: Locale::default(),
+        notification_channels: discord_webhook_url
+            .into_iter()
+            .map(|target| NotificationChannel {
+                kind: ChannelKind::Discord,
+                target,
+            })
+            .collect(),
         area_id,
         route_id: route_id.to_string(),
         departure_station: departure_station.to_string(),
         arrival_station: arrival_station.to_string(),
         date_start: date_start.to_string(),
         date_end: date_end.to_string(),
+        date_window: None,
         departure_time_min,
         departure_time_max,
+        plan_filter: PlanFilter::default(),
         passengers,
     }
 }
@@ -304,6 +348,7 @@ async fn test_build_scrape_request_basic() {
         date_range: DateRange {
             start: user_route.date_start.clone(),
             end: user_route.date_end.clone(),
+            window: None,
         },
         passengers: PassengerCount {
             adult_men: user_route.passengers.adult_men as u8,
@@ -316,6 +361,7 @@ async fn test_build_scrape_request_basic() {
             handicap_child_women: user_route.passengers.handicap_child_women as u8,
         },
         time_filter: None,
+        plan_filter: None,
     };
 
     assert_eq!(request.area_id, 1);
@@ -366,6 +412,7 @@ async fn test_build_scrape_request_with_time_filter() {
         date_range: DateRange {
             start: user_route.date_start.clone(),
             end: user_route.date_end.clone(),
+            window: None,
         },
         passengers: PassengerCount {
             adult_men: user_route.passengers.adult_men as u8,
@@ -387,6 +434,7 @@ async fn test_build_scrape_request_with_time_filter() {
                 departure_max: max.clone(),
             }),
         },
+        plan_filter: None,
     };
 
     assert!(request.time_filter.is_some());
@@ -432,6 +480,7 @@ async fn test_build_scrape_request_with_partial_time_filter() {
         date_range: DateRange {
             start: user_route.date_start.clone(),
             end: user_route.date_end.clone(),
+            window: None,
         },
         passengers: PassengerCount {
             adult_men: user_route.passengers.adult_men as u8,
@@ -453,6 +502,7 @@ async fn test_build_scrape_request_with_partial_time_filter() {
                 departure_max: max.clone(),
             }),
         },
+        plan_filter: None,
     };
 
     assert!(request.time_filter.is_some());
@@ -497,6 +547,7 @@ async fn test_build_scrape_request_with_all_passenger_types() {
         date_range: DateRange {
             start: user_route.date_start.clone(),
             end: user_route.date_end.clone(),
+            window: None,
         },
         passengers: PassengerCount {
             adult_men: user_route.passengers.adult_men as u8,
@@ -509,6 +560,7 @@ async fn test_build_scrape_request_with_all_passenger_types() {
             handicap_child_women: user_route.passengers.handicap_child_women as u8,
         },
         time_filter: None,
+        plan_filter: None,
     };
 
     assert_eq!(request.passengers.adult_men, 2);
@@ -559,6 +611,7 @@ async fn test_build_scrape_request_invalid_route_id() {
         date_range: DateRange {
             start: user_route.date_start.clone(),
             end: user_route.date_end.clone(),
+            window: None,
         },
         passengers: PassengerCount {
             adult_men: user_route.passengers.adult_men as u8,
@@ -571,6 +624,7 @@ async fn test_build_scrape_request_invalid_route_id() {
             handicap_child_women: user_route.passengers.handicap_child_women as u8,
         },
         time_filter: None,
+        plan_filter: None,
     };
 
     assert_eq!(request.route_id, 0);
@@ -629,6 +683,7 @@ async fn test_build_notification_context_with_cached_stations() {
     let context = NotificationContext {
         departure_station_name: departure_name,
         arrival_station_name: arrival_name,
+        route_name: String::new(),
         date_range: (user_route.date_start.clone(), user_route.date_end.clone()),
         passenger_count: user_route.passengers.total() as u8,
         time_filter: match (
@@ -638,6 +693,9 @@ async fn test_build_notification_context_with_cached_stations() {
             (Some(min), Some(max)) => Some((min.clone(), max.clone())),
             _ => None,
         },
+        snooze_url: None,
+        availability_url: None,
+        locale: Locale::default(),
     };
 
     assert_eq!(context.departure_station_name, "Tokyo Station");
@@ -697,9 +755,13 @@ async fn test_build_notification_context_with_missing_stations() {
     let context = NotificationContext {
         departure_station_name: departure_name,
         arrival_station_name: arrival_name,
+        route_name: String::new(),
         date_range: (user_route.date_start.clone(), user_route.date_end.clone()),
         passenger_count: user_route.passengers.total() as u8,
         time_filter: None,
+        snooze_url: None,
+        availability_url: None,
+        locale: Locale::default(),
     };
 
     // Should fall back to generic names
@@ -755,6 +817,7 @@ async fn test_build_notification_context_no_time_filter() {
     let context = NotificationContext {
         departure_station_name: departure_name,
         arrival_station_name: arrival_name,
+        route_name: String::new(),
         date_range: (user_route.date_start.clone(), user_route.date_end.clone()),
         passenger_count: user_route.passengers.total() as u8,
         time_filter: match (
@@ -764,6 +827,9 @@ async fn test_build_notification_context_no_time_filter() {
             (Some(min), Some(max)) => Some((min.clone(), max.clone())),
             _ => None,
         },
+        snooze_url: None,
+        availability_url: None,
+        locale: Locale::default(),
     };
 
     assert!(context.time_filter.is_none());
@@ -885,6 +951,7 @@ async fn test_calculate_state_hash_with_multiple_plans_per_schedule() {
                 availability: SeatAvailability::Available {
                     remaining_seats: Some(5),
                 },
+                details: None,
             },
             PricingPlan {
                 plan_id: 12346,
@@ -895,8 +962,10 @@ async fn test_calculate_state_hash_with_multiple_plans_per_schedule() {
                 availability: SeatAvailability::Available {
                     remaining_seats: Some(3),
                 },
+                details: None,
             },
         ],
+        booking_url: None,
     };
 
     let schedules = vec![schedule];
@@ -936,7 +1005,9 @@ async fn test_calculate_state_hash_with_zero_remaining_seats() {
             availability: SeatAvailability::Available {
                 remaining_seats: Some(0),
             },
+            details: None,
         }],
+        booking_url: None,
     };
 
     let hash1 = calculate_state_hash(&vec![schedule.clone()]);
@@ -976,7 +1047,9 @@ async fn test_calculate_state_hash_large_schedule_list() {
                 availability: SeatAvailability::Available {
                     remaining_seats: Some(5),
                 },
+                details: None,
             }],
+            booking_url: None,
         });
     }
 